@@ -0,0 +1,9 @@
+create table "calendar_tokens"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INT NOT NULL UNIQUE,
+    token text NOT NULL UNIQUE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_calendar_tokens_token ON calendar_tokens(token);
@@ -0,0 +1,51 @@
+create table "meeting_series"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    club_id INT NOT NULL,
+    rrule text NOT NULL,
+    starts_at DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+
+-- Occurrences that were cancelled, so expanding the series again doesn't bring them back.
+-- Moved occurrences don't need a row here as the meeting keeps its original occurrence.
+create table "meeting_series_exceptions"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    series_id INT NOT NULL,
+    occurrence DATETIME NOT NULL,
+    FOREIGN KEY (series_id) REFERENCES meeting_series(id) ON DELETE CASCADE,
+    UNIQUE(series_id, occurrence)
+);
+
+-- Meetings generated from a series don't have a book yet, so book_id becomes nullable.
+-- SQLite can't relax a NOT NULL constraint in place, so the table is rebuilt.
+create table "meetings_new"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date DATETIME NOT NULL,
+    book_id INT,
+    club_id INT NOT NULL,
+    series_id INT,
+    occurrence DATETIME,
+    FOREIGN KEY (book_id) REFERENCES books(id),
+    FOREIGN KEY (club_id) REFERENCES clubs(id),
+    FOREIGN KEY (series_id) REFERENCES meeting_series(id) ON DELETE SET NULL,
+    UNIQUE(series_id, occurrence)
+);
+INSERT INTO meetings_new (id, date, book_id, club_id)
+SELECT id, date, book_id, club_id FROM meetings;
+
+-- attendance references meetings, so its rows are set aside while the old table is dropped.
+CREATE TEMP TABLE attendance_backup AS SELECT * FROM attendance;
+DELETE FROM attendance;
+DROP TABLE meetings;
+ALTER TABLE meetings_new RENAME TO meetings;
+INSERT INTO attendance SELECT * FROM attendance_backup;
+DROP TABLE attendance_backup;
+
+CREATE INDEX idx_meetings_date ON meetings(date);
+CREATE INDEX idx_meetings_book ON meetings(book_id);
+CREATE INDEX idx_meetings_club_date ON meetings(club_id, date);
+CREATE INDEX idx_meeting_series_club_id ON meeting_series(club_id);
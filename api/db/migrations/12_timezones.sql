@@ -0,0 +1,8 @@
+-- Meeting times used to be stored as the naive time the client sent, with no
+-- timezone. Every existing club starts out in UTC, so those rows are read as
+-- UTC and show the same wall-clock time as before. They are not converted:
+-- a club that then sets its real timezone sees its earlier meetings shifted
+-- and has to move them.
+ALTER TABLE clubs ADD COLUMN timezone text NOT NULL DEFAULT 'UTC';
+-- Users without a timezone see times in the club's timezone.
+ALTER TABLE users ADD COLUMN timezone text;
@@ -0,0 +1,18 @@
+-- Every meeting location is a venue. Saved venues make up the club's reusable
+-- list, one-off locations entered on a meeting are stored unsaved.
+create table "venues"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    club_id INT NOT NULL,
+    name text,
+    address text,
+    directions text,
+    video_url text,
+    private BOOLEAN NOT NULL DEFAULT 0,
+    saved BOOLEAN NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_venues_club_id ON venues(club_id);
+
+ALTER TABLE meetings ADD COLUMN venue_id INT REFERENCES venues(id) ON DELETE SET NULL;
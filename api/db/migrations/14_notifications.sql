@@ -0,0 +1,30 @@
+-- Notifications are written to the outbox in the same request that triggers
+-- them and delivered by a background worker, so nothing is lost on a crash.
+create table "notification_outbox"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INT NOT NULL,
+    event text NOT NULL,
+    subject text NOT NULL,
+    body text NOT NULL,
+    -- Stops the same notification from being queued twice, e.g. reminders.
+    dedup_key text UNIQUE,
+    status text NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error text,
+    next_attempt_at DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    sent_at DATETIME,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_notification_outbox_pending ON notification_outbox(status, next_attempt_at);
+
+-- Users receive every event unless they opted out of it.
+create table "notification_preferences"
+(
+    user_id INT NOT NULL,
+    event text NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    PRIMARY KEY (user_id, event),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
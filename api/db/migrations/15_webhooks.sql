@@ -0,0 +1,31 @@
+create table "webhooks"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    club_id INT NOT NULL,
+    url text NOT NULL,
+    -- Key for the HMAC signature sent with every delivery.
+    secret text NOT NULL,
+    -- Comma separated event names, NULL subscribes to every event.
+    events text,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_webhooks_club_id ON webhooks(club_id);
+
+create table "webhook_deliveries"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    webhook_id INT NOT NULL,
+    event text NOT NULL,
+    payload text NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    response_status INT,
+    last_error text,
+    next_attempt_at DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    delivered_at DATETIME,
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries(status, next_attempt_at);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
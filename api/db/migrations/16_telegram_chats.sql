@@ -0,0 +1,9 @@
+-- Telegram chats linked to a club, so the bot reads and writes the club's data.
+create table "telegram_chats"
+(
+    chat_id INTEGER PRIMARY KEY,
+    club_id INT NOT NULL,
+    linked_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_telegram_chats_club_id ON telegram_chats(club_id);
@@ -0,0 +1,2 @@
+-- How far members should have read by the meeting, e.g. "Chapters 1-10".
+ALTER TABLE meetings ADD COLUMN reading_target TEXT;
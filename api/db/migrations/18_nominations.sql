@@ -0,0 +1,13 @@
+-- Books put forward for a club to read next.
+create table "nominations"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    club_id INT NOT NULL,
+    book_id INT NOT NULL,
+    nominated_by INT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (club_id) REFERENCES clubs(id) ON DELETE CASCADE,
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+    FOREIGN KEY (nominated_by) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE (club_id, book_id)
+);
@@ -0,0 +1,16 @@
+-- Goodreads exports identify books by ISBN, so imports can match them exactly.
+ALTER TABLE books ADD COLUMN isbn text;
+ALTER TABLE books ADD COLUMN isbn13 text;
+CREATE INDEX idx_books_isbn ON books(isbn);
+CREATE INDEX idx_books_isbn13 ON books(isbn13);
+
+ALTER TABLE has_read ADD COLUMN read_at DATE;
+-- 1 to 5 stars, NULL when the book wasn't rated.
+ALTER TABLE has_read ADD COLUMN rating INT;
+-- Comma separated shelf names.
+ALTER TABLE has_read ADD COLUMN shelves text;
+
+-- Importing again updates a read instead of adding another one.
+DELETE FROM has_read WHERE id NOT IN (SELECT MIN(id) FROM has_read GROUP BY user_id, book_id);
+DROP INDEX idx_has_read_user_book;
+CREATE UNIQUE INDEX idx_has_read_user_book ON has_read(user_id, book_id);
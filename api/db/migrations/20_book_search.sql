@@ -0,0 +1,27 @@
+-- Full-text index over the books, read through to the books table itself.
+-- Accents are folded, so "garcia marquez" finds "García Márquez".
+CREATE VIRTUAL TABLE books_fts USING fts5(
+    title,
+    author,
+    content = 'books',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO books_fts (books_fts) VALUES ('rebuild');
+
+-- Keeps the index in step with the books table.
+CREATE TRIGGER books_fts_insert AFTER INSERT ON books BEGIN
+    INSERT INTO books_fts (rowid, title, author) VALUES (new.id, new.title, new.author);
+END;
+
+CREATE TRIGGER books_fts_delete AFTER DELETE ON books BEGIN
+    INSERT INTO books_fts (books_fts, rowid, title, author)
+    VALUES ('delete', old.id, old.title, old.author);
+END;
+
+CREATE TRIGGER books_fts_update AFTER UPDATE OF title, author ON books BEGIN
+    INSERT INTO books_fts (books_fts, rowid, title, author)
+    VALUES ('delete', old.id, old.title, old.author);
+    INSERT INTO books_fts (rowid, title, author) VALUES (new.id, new.title, new.author);
+END;
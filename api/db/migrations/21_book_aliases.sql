@@ -0,0 +1,9 @@
+-- Ids of books merged into another, so links to them still find the book.
+create table "book_aliases"
+(
+    id INTEGER PRIMARY KEY,
+    book_id INT NOT NULL,
+    merged_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_book_aliases_book ON book_aliases(book_id);
@@ -0,0 +1,50 @@
+-- The people who wrote, translated or edited books. `books.author` stays as
+-- the byline shown with the book.
+create table "authors"
+(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(255) NOT NULL,
+    -- e.g. OL26320A
+    open_library_key text,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+CREATE INDEX idx_authors_name ON authors(name);
+CREATE UNIQUE INDEX idx_authors_open_library_key ON authors(open_library_key);
+
+create table "book_authors"
+(
+    book_id INT NOT NULL,
+    author_id INT NOT NULL,
+    -- One of author, translator and editor.
+    role text NOT NULL DEFAULT 'author',
+    -- Order of the credits on the book.
+    position INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (book_id, author_id, role),
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES authors(id) ON DELETE CASCADE
+);
+CREATE INDEX idx_book_authors_author ON book_authors(author_id);
+
+-- Every book starts out credited to the author in its byline. Bylines that
+-- only differ in case are the same author, spelled as in the oldest book, the
+-- same as the trigger below matches them.
+INSERT INTO authors (name)
+SELECT author FROM books
+WHERE id IN (
+    SELECT MIN(id) FROM books WHERE trim(author) != '' GROUP BY author COLLATE NOCASE
+)
+ORDER BY id;
+INSERT INTO book_authors (book_id, author_id)
+SELECT books.id, authors.id FROM books
+JOIN authors ON authors.name = books.author COLLATE NOCASE;
+
+-- Likewise new books, unless they were given credits already, e.g. by an import.
+CREATE TRIGGER books_credit_author AFTER INSERT ON books
+WHEN trim(new.author) != '' AND NOT EXISTS (SELECT 1 FROM book_authors WHERE book_id = new.id)
+BEGIN
+    INSERT INTO authors (name)
+    SELECT new.author
+    WHERE NOT EXISTS (SELECT 1 FROM authors WHERE name = new.author COLLATE NOCASE);
+    INSERT INTO book_authors (book_id, author_id)
+    SELECT new.id, id FROM authors WHERE name = new.author COLLATE NOCASE ORDER BY id LIMIT 1;
+END;
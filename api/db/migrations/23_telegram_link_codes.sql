@@ -0,0 +1,9 @@
+-- One-time codes a club admin hands to a Telegram chat, which links the chat to
+-- the club with `/link <code>`. Codes only live for an hour, so club_id isn't a
+-- foreign key; linking checks the club still exists.
+create table "telegram_link_codes"
+(
+    code TEXT PRIMARY KEY,
+    club_id INT NOT NULL,
+    expires_at DATETIME NOT NULL
+);
@@ -0,0 +1,4 @@
+-- `invite_received` was sent whenever someone was added to a club, invited or
+-- not, so it's now called `club_joined`.
+UPDATE notification_preferences SET event = 'club_joined' WHERE event = 'invite_received';
+UPDATE notification_outbox SET event = 'club_joined' WHERE event = 'invite_received';
@@ -0,0 +1,307 @@
+//! Operations behind the `bookclub-admin` binary, which works on the database
+//! directly instead of going through the API.
+
+mod dump;
+
+pub use crate::books::merge::{duplicate_books, merge_books, MergeReport};
+pub use dump::*;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use shared::{Club, CreateClubParams, CreateUserParams, GoodreadsImport, Membership, User};
+
+use crate::{books::goodreads, sqlite::Database, timezone::parse_timezone};
+
+/// A login session, without its token.
+#[derive(Debug, Clone, Serialize)]
+pub struct Session {
+    pub id: i64,
+    pub user_id: i64,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Rows removed by [`prune`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneReport {
+    pub sessions: u64,
+    pub notifications: u64,
+    pub webhook_deliveries: u64,
+}
+
+fn check_timezone(timezone: &str) -> Result<()> {
+    if let Err((_, message)) = parse_timezone(timezone) {
+        bail!(message);
+    }
+    Ok(())
+}
+
+pub async fn create_user(db: &Database, params: &CreateUserParams) -> Result<User> {
+    if let Some(timezone) = &params.timezone {
+        check_timezone(timezone)?;
+    }
+
+    Ok(sqlx::query_as(
+        r#"
+        INSERT INTO users (email, first_name, last_name, timezone)
+        VALUES (?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(&params.email)
+    .bind(&params.first_name)
+    .bind(&params.last_name)
+    .bind(&params.timezone)
+    .fetch_one(db.as_ref())
+    .await?)
+}
+
+pub async fn list_users(db: &Database) -> Result<Vec<User>> {
+    Ok(sqlx::query_as("SELECT * FROM users ORDER BY id")
+        .fetch_all(db.as_ref())
+        .await?)
+}
+
+pub async fn create_club(db: &Database, params: &CreateClubParams) -> Result<Club> {
+    let timezone = params.timezone.as_deref().unwrap_or("UTC");
+    check_timezone(timezone)?;
+
+    Ok(sqlx::query_as(
+        r#"
+        INSERT INTO clubs (name, description, timezone)
+        VALUES (?, ?, ?)
+        RETURNING id, name, description, timezone, created_at, updated_at
+        "#,
+    )
+    .bind(&params.name)
+    .bind(&params.description)
+    .bind(timezone)
+    .fetch_one(db.as_ref())
+    .await?)
+}
+
+pub async fn list_clubs(db: &Database) -> Result<Vec<Club>> {
+    Ok(sqlx::query_as(
+        "SELECT id, name, description, timezone, created_at, updated_at FROM clubs ORDER BY id",
+    )
+    .fetch_all(db.as_ref())
+    .await?)
+}
+
+/// Makes the user a member of the club with the given permission level,
+/// changing the level if they already are one.
+pub async fn grant(
+    db: &Database,
+    user_id: i64,
+    club_id: i64,
+    permission_level: i64,
+) -> Result<Membership> {
+    if !(0..=2).contains(&permission_level) {
+        bail!("Permission level must be between 0 and 2");
+    }
+    let user = sqlx::query_scalar::<_, i64>("SELECT id FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(db.as_ref())
+        .await?;
+    if user.is_none() {
+        bail!("User {user_id} not found");
+    }
+    let club = sqlx::query_scalar::<_, i64>("SELECT id FROM clubs WHERE id = ?")
+        .bind(club_id)
+        .fetch_optional(db.as_ref())
+        .await?;
+    if club.is_none() {
+        bail!("Club {club_id} not found");
+    }
+
+    Ok(sqlx::query_as(
+        r#"
+        INSERT INTO memberships (user_id, club_id, permission_level)
+        VALUES (?, ?, ?)
+        ON CONFLICT (user_id, club_id)
+        DO UPDATE SET permission_level = excluded.permission_level
+        RETURNING id, user_id, club_id, permission_level, created_at
+        "#,
+    )
+    .bind(user_id)
+    .bind(club_id)
+    .bind(permission_level)
+    .fetch_one(db.as_ref())
+    .await?)
+}
+
+/// Imports a Goodreads library export for the user, like the API's upload.
+pub async fn import_goodreads(
+    db: &Database,
+    user_id: i64,
+    csv: &str,
+    dry_run: bool,
+) -> Result<GoodreadsImport> {
+    let user = sqlx::query_scalar::<_, i64>("SELECT id FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(db.as_ref())
+        .await?;
+    if user.is_none() {
+        bail!("User {user_id} not found");
+    }
+    let rows = goodreads::parse(csv)?;
+
+    goodreads::import(db, user_id, &rows, dry_run).await
+}
+
+/// Sessions, newest first, optionally only those of one user.
+pub async fn list_sessions(db: &Database, user_id: Option<i64>) -> Result<Vec<Session>> {
+    let rows: Vec<(i64, i64, String, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT s.id, s.user_id, u.email, s.created_at, s.expires_at
+        FROM user_sessions s
+        JOIN users u ON u.id = s.user_id
+        WHERE ?1 IS NULL OR s.user_id = ?1
+        ORDER BY s.created_at DESC, s.id DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db.as_ref())
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, user_id, email, created_at, expires_at)| Session {
+            id,
+            user_id,
+            email,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap_or_default(),
+            expires_at: DateTime::from_timestamp(expires_at, 0).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Deletes expired sessions, and notifications and webhook deliveries that
+/// are done with and older than `keep_days`.
+pub async fn prune(db: &Database, keep_days: i64) -> Result<PruneReport> {
+    let now = Utc::now();
+    let cutoff = (now - Duration::days(keep_days)).naive_utc();
+    let mut tx = db.as_ref().begin().await?;
+
+    let sessions = sqlx::query("DELETE FROM user_sessions WHERE expires_at <= ?")
+        .bind(now.timestamp())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    let notifications =
+        sqlx::query("DELETE FROM notification_outbox WHERE status != 'pending' AND created_at < ?")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    let webhook_deliveries =
+        sqlx::query("DELETE FROM webhook_deliveries WHERE status != 'pending' AND created_at < ?")
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+    tx.commit().await?;
+
+    Ok(PruneReport {
+        sessions,
+        notifications,
+        webhook_deliveries,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{auth::session::create_session, tests::create_test_state};
+
+    fn user_params(email: &str) -> CreateUserParams {
+        CreateUserParams {
+            email: email.to_string(),
+            first_name: "Test".to_string(),
+            last_name: "User".to_string(),
+            timezone: None,
+        }
+    }
+
+    fn club_params() -> CreateClubParams {
+        CreateClubParams {
+            name: "Test Club".to_string(),
+            description: "Test Description".to_string(),
+            timezone: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_grant() {
+        let db = create_test_state().await.db;
+        let user = create_user(&db, &user_params("test@example.com"))
+            .await
+            .unwrap();
+        let club = create_club(&db, &club_params()).await.unwrap();
+        assert_eq!(club.timezone, "UTC");
+
+        let membership = grant(&db, user.id, club.id, 1).await.unwrap();
+        assert_eq!(membership.permission_level, 1);
+        let promoted = grant(&db, user.id, club.id, 2).await.unwrap();
+        assert_eq!(promoted.id, membership.id);
+        assert_eq!(promoted.permission_level, 2);
+
+        let error = grant(&db, user.id, 9999, 1).await.unwrap_err();
+        assert_eq!(error.to_string(), "Club 9999 not found");
+        let error = grant(&db, user.id, club.id, 3).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Permission level must be between 0 and 2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_user_checks_timezone() {
+        let db = create_test_state().await.db;
+        let params = CreateUserParams {
+            timezone: Some("Mars/Olympus_Mons".to_string()),
+            ..user_params("test@example.com")
+        };
+
+        let error = create_user(&db, &params).await.unwrap_err();
+        assert_eq!(error.to_string(), "Unknown timezone 'Mars/Olympus_Mons'");
+        assert!(list_users(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_removes_expired_sessions() {
+        let db = create_test_state().await.db;
+        let alice = create_user(&db, &user_params("alice@example.com"))
+            .await
+            .unwrap();
+        let bob = create_user(&db, &user_params("bob@example.com"))
+            .await
+            .unwrap();
+        create_session(db.as_ref(), alice.id).await.unwrap();
+        create_session(db.as_ref(), bob.id).await.unwrap();
+        sqlx::query("UPDATE user_sessions SET expires_at = 0 WHERE user_id = ?")
+            .bind(bob.id)
+            .execute(db.as_ref())
+            .await
+            .unwrap();
+
+        let sessions = list_sessions(&db, Some(bob.id)).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions[0].is_expired());
+
+        let report = prune(&db, 30).await.unwrap();
+        assert_eq!(report.sessions, 1);
+        let sessions = list_sessions(&db, None).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].email, "alice@example.com");
+    }
+}
@@ -1,11 +1,11 @@
 use anyhow::{Context, Result};
 
+use crate::{auth::session, users::User};
 use oauth2::{EndpointMaybeSet, EndpointNotSet, EndpointSet};
 use openidconnect::core::{
     CoreAuthDisplay, CoreClaimName, CoreClaimType, CoreClient, CoreClientAuthMethod, CoreGrantType,
     CoreIdTokenClaims, CoreIdTokenVerifier, CoreJsonWebKey, CoreJweContentEncryptionAlgorithm,
-    CoreJweKeyManagementAlgorithm, CoreResponseMode, CoreResponseType, CoreRevocableToken,
-    CoreSubjectIdentifierType,
+    CoreJweKeyManagementAlgorithm, CoreResponseMode, CoreResponseType, CoreSubjectIdentifierType,
 };
 use openidconnect::{
     AdditionalProviderMetadata, AuthenticationFlow, AuthorizationCode, ClientId, ClientSecret,
@@ -14,9 +14,6 @@ use openidconnect::{
 };
 use serde::{Deserialize, Serialize};
 use sqlx::Pool;
-use uuid::Uuid;
-
-use crate::users::User;
 
 // Teach openidconnect-rs about a Google custom extension to the OpenID Discovery response that we can use as the RFC
 // 7009 OAuth 2.0 Token Revocation endpoint. For more information about the Google specific Discovery response see the
@@ -207,26 +204,7 @@ impl Client {
             }
         };
 
-        // Create a session for the user
-        let session_token_p1 = Uuid::new_v4().to_string();
-        let session_token_p2 = Uuid::new_v4().to_string();
-        let session_token = [session_token_p1.as_str(), "_", session_token_p2.as_str()].concat();
-
-        let created_at = chrono::Utc::now().timestamp();
-        let expires_at = created_at + 60 * 60 * 24;
-
-        sqlx::query(
-            "INSERT INTO user_sessions
-            (session_token_p1, session_token_p2, user_id, created_at, expires_at)
-            VALUES (?, ?, ?, ?, ?);",
-        )
-        .bind(session_token_p1)
-        .bind(session_token_p2)
-        .bind(user.id)
-        .bind(created_at)
-        .bind(expires_at)
-        .execute(db_pool)
-        .await?;
+        let session_token = session::create_session(db_pool, user.id).await?;
 
         Ok((session_token, return_url))
     }
@@ -2,7 +2,7 @@ mod client;
 
 use std::collections::HashMap;
 
-use crate::{error::AppResult, sqlite::Database, AppState};
+use crate::{auth::session, error::AppResult, sqlite::Database, AppState};
 use axum::{
     debug_handler,
     extract::{Query, State},
@@ -53,7 +53,8 @@ pub async fn callback(
     let headers = axum::response::AppendHeaders([(
         axum::http::header::SET_COOKIE,
         format!(
-            "session_token={}; path=/; httponly; secure; samesite=strict",
+            "{}={}; path=/; httponly; secure; samesite=strict",
+            session::SESSION_COOKIE,
             session_token
         ),
     )]);
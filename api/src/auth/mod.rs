@@ -1,4 +1,5 @@
 pub mod google;
+pub mod session;
 
 use axum::{extract::FromRef, routing::get, Router};
 
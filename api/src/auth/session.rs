@@ -0,0 +1,132 @@
+use anyhow::Result;
+use axum::{
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sqlx::{Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::{
+    error::{error_response, AppError},
+    sqlite::Database,
+    users::User,
+};
+
+pub const SESSION_COOKIE: &str = "session_token";
+const SESSION_LIFETIME_SECS: i64 = 60 * 60 * 24;
+
+/// Creates a session for the user and returns the token to hand to the client.
+pub async fn create_session(db_pool: &Pool<Sqlite>, user_id: i64) -> Result<String> {
+    let session_token_p1 = Uuid::new_v4().to_string();
+    let session_token_p2 = Uuid::new_v4().to_string();
+    let session_token = [session_token_p1.as_str(), "_", session_token_p2.as_str()].concat();
+
+    let created_at = chrono::Utc::now().timestamp();
+    let expires_at = created_at + SESSION_LIFETIME_SECS;
+
+    sqlx::query(
+        "INSERT INTO user_sessions
+        (session_token_p1, session_token_p2, user_id, created_at, expires_at)
+        VALUES (?, ?, ?, ?, ?);",
+    )
+    .bind(session_token_p1)
+    .bind(session_token_p2)
+    .bind(user_id)
+    .bind(created_at)
+    .bind(expires_at)
+    .execute(db_pool)
+    .await?;
+
+    Ok(session_token)
+}
+
+/// The user owning the session token sent with the request, either in the
+/// session cookie or as an `Authorization: Bearer` token.
+///
+/// Use `Option<CurrentUser>` for routes that also serve anonymous requests.
+#[derive(Debug)]
+pub struct CurrentUser(pub User);
+
+impl CurrentUser {
+    async fn from_parts(parts: &Parts, db: &Database) -> Result<Option<Self>, AppError> {
+        let Some(token) = bearer_token(parts).or_else(|| session_cookie(parts)) else {
+            return Ok(None);
+        };
+        let Some((session_token_p1, session_token_p2)) = token.split_once('_') else {
+            return Ok(None);
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let user: Option<User> = sqlx::query_as(
+            r#"
+            SELECT users.*
+            FROM user_sessions
+            JOIN users ON users.id = user_sessions.user_id
+            WHERE session_token_p1 = ? AND session_token_p2 = ? AND expires_at > ?
+            "#,
+        )
+        .bind(session_token_p1)
+        .bind(session_token_p2)
+        .bind(now)
+        .fetch_optional(db.as_ref())
+        .await?;
+
+        Ok(user.map(CurrentUser))
+    }
+}
+
+/// Clients other than browsers send the session token in a header instead.
+pub(crate) fn bearer_token(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+fn session_cookie(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value)
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    Database: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let db = Database::from_ref(state);
+        match CurrentUser::from_parts(parts, &db).await {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(error_response(StatusCode::UNAUTHORIZED, "Not logged in")),
+            Err(e) => Err(e.into_response()),
+        }
+    }
+}
+
+impl<S> OptionalFromRequestParts<S> for CurrentUser
+where
+    Database: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        let db = Database::from_ref(state);
+        CurrentUser::from_parts(parts, &db).await
+    }
+}
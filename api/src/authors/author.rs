@@ -0,0 +1,55 @@
+use sqlx::SqliteConnection;
+
+use crate::error::AppResult;
+
+pub use shared::{
+    Author, AuthorBook, AuthorParams, AuthorRole, AuthorStats, ClubAuthorStats, Credit,
+    SetCreditsParams,
+};
+
+/// Database access for [`Author`].
+pub trait AuthorExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+}
+
+impl AuthorExt for Author {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+        let author = sqlx::query_as!(
+            Author,
+            r#"SELECT id AS "id!", name, open_library_key FROM authors WHERE id = ?"#,
+            id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(author)
+    }
+}
+
+/// The people credited on the book, in order.
+pub async fn credits(book_id: i64, db: &mut SqliteConnection) -> AppResult<Vec<Credit>> {
+    sqlx::query!(
+        r#"
+        SELECT authors.id AS "id!", authors.name, authors.open_library_key, book_authors.role
+        FROM book_authors
+        JOIN authors ON authors.id = book_authors.author_id
+        WHERE book_authors.book_id = ?
+        ORDER BY book_authors.position, authors.name
+        "#,
+        book_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(Credit {
+            author: Author {
+                id: row.id,
+                name: row.name,
+                open_library_key: row.open_library_key,
+            },
+            role: row.role.parse().map_err(anyhow::Error::from)?,
+        })
+    })
+    .collect()
+}
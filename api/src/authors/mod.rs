@@ -0,0 +1,450 @@
+mod author;
+
+pub use author::*;
+
+use crate::{
+    books::{Book, BookExt},
+    error::{error_response, AppResult},
+    pagination::{self, PageParams},
+    sqlite::Database,
+};
+use axum::{
+    debug_handler,
+    extract::{OriginalUri, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// Adds the author. An author with the same Open Library key is returned
+/// instead of adding them twice.
+#[debug_handler]
+pub async fn create_author(
+    State(db): State<Database>,
+    Json(AuthorParams {
+        name,
+        open_library_key,
+    }): Json<AuthorParams>,
+) -> AppResult<Response> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            "Authors need a name",
+        ));
+    }
+    let mut tx = db.as_ref().begin().await?;
+
+    if let Some(key) = &open_library_key {
+        let existing = sqlx::query_as!(
+            Author,
+            r#"SELECT id AS "id!", name, open_library_key FROM authors WHERE open_library_key = ?"#,
+            key
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(author) = existing {
+            return Ok(Json(author).into_response());
+        }
+    }
+
+    let id = sqlx::query!(
+        "INSERT INTO authors (name, open_library_key) VALUES (?, ?) RETURNING id",
+        name,
+        open_library_key
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .id;
+    tx.commit().await?;
+
+    let author = Author {
+        id,
+        name: name.to_string(),
+        open_library_key,
+    };
+    Ok((StatusCode::CREATED, Json(author)).into_response())
+}
+
+#[debug_handler]
+pub async fn get_authors(
+    State(db): State<Database>,
+    Query(params): Query<PageParams>,
+    OriginalUri(uri): OriginalUri,
+) -> AppResult<Response> {
+    let (limit, offset) = pagination::bounds(&params);
+    let limit = limit + 1;
+    let authors = sqlx::query_as!(
+        Author,
+        r#"
+        SELECT id AS "id!", name, open_library_key
+        FROM authors
+        ORDER BY name, id
+        LIMIT ? OFFSET ?
+        "#,
+        limit,
+        offset
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    Ok(pagination::page(authors, &params, &uri))
+}
+
+#[debug_handler]
+pub async fn get_author_by_id(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    match Author::from_id(id, &mut conn).await? {
+        Some(author) => Ok(Json(author).into_response()),
+        None => Ok(error_response(StatusCode::NOT_FOUND, "Author not found")),
+    }
+}
+
+/// The books the author is credited on, by title.
+#[debug_handler]
+pub async fn get_author_books(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    if Author::from_id(id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Author not found"));
+    }
+
+    let books = sqlx::query!(
+        r#"
+        SELECT books.id AS "id!", books.title, books.author, book_authors.role
+        FROM book_authors
+        JOIN books ON books.id = book_authors.book_id
+        WHERE book_authors.author_id = ?
+        ORDER BY books.title, books.id
+        "#,
+        id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(AuthorBook {
+            book: Book {
+                title: row.title,
+                author: row.author,
+                id: row.id,
+            },
+            role: row.role.parse().map_err(anyhow::Error::from)?,
+        })
+    })
+    .collect::<AppResult<Vec<_>>>()?;
+
+    Ok(Json(books).into_response())
+}
+
+/// How many people read the author and which clubs met about their books.
+#[debug_handler]
+pub async fn get_author_stats(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    let Some(author) = Author::from_id(id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Author not found"));
+    };
+
+    let books = sqlx::query_scalar!(
+        "SELECT COUNT(DISTINCT book_id) FROM book_authors WHERE author_id = ?",
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let reads = sqlx::query!(
+        r#"
+        SELECT COUNT(DISTINCT user_id) AS "readers!: i64",
+               AVG(rating) AS "average_rating: f64"
+        FROM has_read
+        WHERE book_id IN (SELECT book_id FROM book_authors WHERE author_id = ?)
+        "#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let clubs = sqlx::query!(
+        r#"
+        SELECT clubs.id AS "club_id!",
+               clubs.name AS club_name,
+               COUNT(DISTINCT meetings.id) AS "meetings!: i64",
+               COUNT(DISTINCT meetings.book_id) AS "books!: i64",
+               MAX(meetings.date) AS "latest_meeting!: chrono::NaiveDateTime"
+        FROM meetings
+        JOIN clubs ON clubs.id = meetings.club_id
+        WHERE meetings.book_id IN (SELECT book_id FROM book_authors WHERE author_id = ?)
+        GROUP BY clubs.id
+        ORDER BY 3 DESC, clubs.name
+        "#,
+        id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| ClubAuthorStats {
+        club_id: row.club_id,
+        club_name: row.club_name,
+        meetings: row.meetings,
+        books: row.books,
+        latest_meeting: row.latest_meeting.and_utc(),
+    })
+    .collect();
+
+    Ok(Json(AuthorStats {
+        author,
+        books: books.into(),
+        readers: reads.readers,
+        average_rating: reads.average_rating,
+        clubs,
+    })
+    .into_response())
+}
+
+#[debug_handler]
+pub async fn get_book_credits(
+    State(db): State<Database>,
+    Path(book_id): Path<i64>,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    let Some(book) = Book::from_id(book_id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Book not found"));
+    };
+
+    Ok(Json(credits(book.id, &mut conn).await?).into_response())
+}
+
+/// Replaces the book's credits. Unless none of them are authors, the book's
+/// byline becomes its authors' names.
+#[debug_handler]
+pub async fn set_book_credits(
+    State(db): State<Database>,
+    Path(book_id): Path<i64>,
+    Json(SetCreditsParams { credits: params }): Json<SetCreditsParams>,
+) -> AppResult<Response> {
+    let mut tx = db.as_ref().begin().await?;
+    let Some(book) = Book::from_id(book_id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Book not found"));
+    };
+
+    sqlx::query!("DELETE FROM book_authors WHERE book_id = ?", book.id)
+        .execute(&mut *tx)
+        .await?;
+    let mut authors = vec![];
+    for (position, credit) in params.iter().enumerate() {
+        let Some(author) = Author::from_id(credit.author_id, &mut tx).await? else {
+            return Ok(error_response(StatusCode::NOT_FOUND, "Author not found"));
+        };
+        let role = credit.role.as_str();
+        let position = position as i64;
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO book_authors (book_id, author_id, role, position)
+            VALUES (?, ?, ?, ?)
+            "#,
+            book.id,
+            author.id,
+            role,
+            position
+        )
+        .execute(&mut *tx)
+        .await?;
+        if credit.role == AuthorRole::Author && !authors.contains(&author.name) {
+            authors.push(author.name);
+        }
+    }
+    if !authors.is_empty() {
+        let byline = authors.join(", ");
+        sqlx::query!("UPDATE books SET author = ? WHERE id = ?", byline, book.id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    let credits = credits(book.id, &mut tx).await?;
+    tx.commit().await?;
+
+    Ok(Json(credits).into_response())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::{
+        books::{test::create_test_book, BookParams},
+        clubs::test::create_test_club,
+        meetings::test::create_test_meeting,
+        tests::{create_test_server, create_test_server_with_state},
+        users::{test::create_user, CreateUserParams},
+    };
+    use axum_test::TestServer;
+    use shared::CreditParams;
+
+    pub async fn create_author(server: &TestServer, name: &str) -> Author {
+        let response = server
+            .post("/authors")
+            .json(&AuthorParams {
+                name: name.to_string(),
+                open_library_key: None,
+            })
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        response.json()
+    }
+
+    #[tokio::test]
+    async fn test_book_credits() {
+        let server = create_test_server().await;
+        let book: Book = server
+            .post("/books/create")
+            .json(&BookParams {
+                title: "Good Omens".to_string(),
+                author: "Neil Gaiman".to_string(),
+            })
+            .await
+            .json();
+
+        // New books are credited to the author in their byline.
+        let credits: Vec<Credit> = server
+            .get(&format!("/books/{}/authors", book.id))
+            .await
+            .json();
+        assert_eq!(credits.len(), 1);
+        let gaiman = credits[0].author.clone();
+        assert_eq!(gaiman.name, "Neil Gaiman");
+        assert_eq!(credits[0].role, AuthorRole::Author);
+        let sandman: Book = server
+            .post("/books/create")
+            .json(&BookParams {
+                title: "The Sandman".to_string(),
+                author: "neil gaiman".to_string(),
+            })
+            .await
+            .json();
+
+        let pratchett = create_author(&server, "Terry Pratchett").await;
+        let translator = create_author(&server, "Patrick Couton").await;
+        let response = server
+            .put(&format!("/books/{}/authors", book.id))
+            .json(&SetCreditsParams {
+                credits: vec![
+                    CreditParams {
+                        author_id: pratchett.id,
+                        role: AuthorRole::Author,
+                    },
+                    CreditParams {
+                        author_id: gaiman.id,
+                        role: AuthorRole::Author,
+                    },
+                    CreditParams {
+                        author_id: translator.id,
+                        role: AuthorRole::Translator,
+                    },
+                ],
+            })
+            .await;
+        response.assert_status_ok();
+        let credits: Vec<Credit> = response.json();
+        assert_eq!(credits.len(), 3);
+        assert_eq!(credits[2].role, AuthorRole::Translator);
+        let updated: Book = server.get(&format!("/books/get/{}", book.id)).await.json();
+        assert_eq!(updated.author, "Terry Pratchett, Neil Gaiman");
+
+        let books: Vec<AuthorBook> = server
+            .get(&format!("/authors/{}/books", gaiman.id))
+            .await
+            .json();
+        let ids = books.iter().map(|book| book.book.id).collect::<Vec<_>>();
+        assert_eq!(ids, [book.id, sandman.id]);
+        let books: Vec<AuthorBook> = server
+            .get(&format!("/authors/{}/books", translator.id))
+            .await
+            .json();
+        assert_eq!(books[0].role, AuthorRole::Translator);
+
+        let response = server
+            .put(&format!("/books/{}/authors", book.id))
+            .json(&SetCreditsParams {
+                credits: vec![CreditParams {
+                    author_id: 9999,
+                    role: AuthorRole::Editor,
+                }],
+            })
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_create_author_with_open_library_key() {
+        let server = create_test_server().await;
+        let params = AuthorParams {
+            name: "J.R.R. Tolkien".to_string(),
+            open_library_key: Some("OL26320A".to_string()),
+        };
+
+        let response = server.post("/authors").json(&params).await;
+        response.assert_status(StatusCode::CREATED);
+        let author: Author = response.json();
+        let response = server.post("/authors").json(&params).await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Author>().id, author.id);
+
+        let fetched: Author = server.get(&format!("/authors/{}", author.id)).await.json();
+        assert_eq!(fetched.open_library_key.as_deref(), Some("OL26320A"));
+        server
+            .get("/authors/9999")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_author_stats() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        create_test_meeting(&server, club.id, book.id).await;
+        create_test_meeting(&server, club.id, book.id).await;
+        for (email, rating) in [("a@example.com", 2), ("b@example.com", 5)] {
+            let user = create_user(
+                &server,
+                CreateUserParams {
+                    email: email.to_string(),
+                    first_name: "Test".to_string(),
+                    last_name: "Reader".to_string(),
+                    timezone: None,
+                },
+            )
+            .await;
+            sqlx::query!(
+                "INSERT INTO has_read (user_id, book_id, rating) VALUES (?, ?, ?)",
+                user.id,
+                book.id,
+                rating
+            )
+            .execute(state.db.as_ref())
+            .await
+            .unwrap();
+        }
+        let credits: Vec<Credit> = server
+            .get(&format!("/books/{}/authors", book.id))
+            .await
+            .json();
+
+        let stats: AuthorStats = server
+            .get(&format!("/authors/{}/stats", credits[0].author.id))
+            .await
+            .json();
+        assert_eq!(stats.author.name, book.author);
+        assert_eq!(stats.books, 1);
+        assert_eq!(stats.readers, 2);
+        assert_eq!(stats.average_rating, Some(3.5));
+        assert_eq!(stats.clubs.len(), 1);
+        assert_eq!(stats.clubs[0].club_id, club.id);
+        assert_eq!(stats.clubs[0].meetings, 2);
+        assert_eq!(stats.clubs[0].books, 1);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::{self, Database};
+
+const PREFIX: &str = "bookclub-";
+const EXTENSION: &str = "db";
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub directory: String,
+    /// Hours between scheduled backups, 0 to only back up when asked to.
+    pub interval_hours: u64,
+    /// How many backups to keep, the oldest are removed first.
+    pub keep: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            directory: "backups".to_string(),
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+/// A backup file written by [`Backups::create`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Writes consistent snapshots of the database while it is in use.
+#[derive(Clone)]
+pub struct Backups {
+    pub directory: PathBuf,
+    pub interval: Option<Duration>,
+    pub keep: usize,
+}
+
+impl Backups {
+    pub fn new(settings: &Settings) -> Backups {
+        Backups {
+            directory: PathBuf::from(&settings.directory),
+            interval: (settings.interval_hours > 0)
+                .then(|| Duration::from_secs(settings.interval_hours * 60 * 60)),
+            keep: settings.keep.max(1),
+        }
+    }
+
+    /// Snapshots the database with `VACUUM INTO`, which sees a single
+    /// transaction, then removes the backups beyond `keep`.
+    pub async fn create(&self, db: &Database) -> Result<Backup> {
+        fs::create_dir_all(&self.directory)
+            .with_context(|| format!("Creating {}", self.directory.display()))?;
+        let name = format!(
+            "{PREFIX}{}.{EXTENSION}",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        );
+        let path = self.directory.join(name);
+
+        sqlx::query("VACUUM INTO ?")
+            .bind(path.to_string_lossy())
+            .execute(db.as_ref())
+            .await?;
+        self.rotate()?;
+
+        Ok(Backup {
+            size: fs::metadata(&path)?.len(),
+            path,
+        })
+    }
+
+    /// The backups in the directory, newest first.
+    pub fn list(&self) -> Result<Vec<Backup>> {
+        if !self.directory.exists() {
+            return Ok(vec![]);
+        }
+        let mut backups = vec![];
+        for entry in fs::read_dir(&self.directory)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(PREFIX) && name.ends_with(&format!(".{EXTENSION}")) {
+                backups.push(Backup {
+                    path: entry.path(),
+                    size: entry.metadata()?.len(),
+                });
+            }
+        }
+        // The names sort by the time they were taken.
+        backups.sort_by(|a, b| b.path.cmp(&a.path));
+
+        Ok(backups)
+    }
+
+    fn rotate(&self) -> Result<()> {
+        for backup in self.list()?.into_iter().skip(self.keep) {
+            tracing::info!("Removing old backup {}", backup.path.display());
+            fs::remove_file(&backup.path)?;
+        }
+        Ok(())
+    }
+
+    /// Backs up the database every `interval` until stopped.
+    pub async fn run(self, db: Database) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let mut interval = tokio::time::interval(interval);
+        // The first tick completes immediately, skip it so starting the
+        // server doesn't take a backup.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match self.create(&db).await {
+                Ok(backup) => tracing::info!("Backed up to {}", backup.path.display()),
+                Err(e) => tracing::error!("Error backing up the database: {:?}", e),
+            }
+        }
+    }
+}
+
+/// Checks that the backup is intact and that this build can run on it,
+/// returning its schema version.
+pub async fn validate(backup: &Path) -> Result<i64> {
+    if !backup.is_file() {
+        bail!("{} is not a file", backup.display());
+    }
+    let db = Database::open_read_only(backup).await?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(db.as_ref())
+        .await
+        .context("Reading the backup")?;
+    if integrity != "ok" {
+        bail!("The backup is corrupt: {integrity}");
+    }
+
+    let version = db.schema_version().await?;
+    let latest = Database::latest_migration();
+    if version == 0 {
+        bail!("The backup has no migrations, it isn't a bookclub database");
+    }
+    if version > latest {
+        bail!("The backup is at schema version {version} but this build only knows up to {latest}");
+    }
+    db.as_ref().close().await;
+
+    Ok(version)
+}
+
+/// Replaces the database with the backup, after validating it, and migrates
+/// it to this build's schema. The database it replaces is kept next to it
+/// with a `.pre-restore` suffix. The server must be stopped while restoring.
+pub async fn restore(backup: &Path, settings: &sqlite::Settings) -> Result<i64> {
+    validate(backup).await?;
+    let Some(target) = settings.path() else {
+        bail!("Can't restore into an in-memory database");
+    };
+
+    if target.exists() {
+        let previous = with_suffix(&target, ".pre-restore");
+        if previous.exists() {
+            fs::remove_file(&previous)?;
+        }
+        let db = Database::open(settings).await?;
+        sqlx::query("VACUUM INTO ?")
+            .bind(previous.to_string_lossy())
+            .execute(db.as_ref())
+            .await?;
+        db.as_ref().close().await;
+    }
+
+    // Copied next to the target first, so the swap itself is a rename.
+    let incoming = with_suffix(&target, ".restoring");
+    fs::copy(backup, &incoming)?;
+    for suffix in ["-wal", "-shm"] {
+        let file = with_suffix(&target, suffix);
+        if file.exists() {
+            fs::remove_file(file)?;
+        }
+    }
+    fs::rename(&incoming, &target)?;
+
+    let db = Database::new(settings).await?;
+    let version = db.schema_version().await?;
+    db.as_ref().close().await;
+
+    Ok(version)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A directory of its own for each test.
+    fn test_directory() -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("bookclub-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    async fn file_database(directory: &Path) -> sqlite::Settings {
+        let settings = sqlite::Settings {
+            url: format!("sqlite://{}", directory.join("bookclub.db").display()),
+        };
+        Database::new(&settings).await.unwrap();
+        settings
+    }
+
+    async fn user_count(db: &Database) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(db.as_ref())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backups_rotate() {
+        let directory = test_directory();
+        let settings = file_database(&directory).await;
+        let db = Database::new(&settings).await.unwrap();
+        let backups = Backups {
+            directory: directory.join("backups"),
+            interval: None,
+            keep: 2,
+        };
+
+        let first = backups.create(&db).await.unwrap();
+        backups.create(&db).await.unwrap();
+        let last = backups.create(&db).await.unwrap();
+
+        let listed = backups.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].path, last.path);
+        assert!(!first.path.exists());
+        assert_eq!(
+            validate(&last.path).await.unwrap(),
+            Database::latest_migration()
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let directory = test_directory();
+        let settings = file_database(&directory).await;
+        let db = Database::new(&settings).await.unwrap();
+        sqlx::query(
+            "INSERT INTO users (email, first_name, last_name) VALUES ('a@example.com', 'A', 'B')",
+        )
+        .execute(db.as_ref())
+        .await
+        .unwrap();
+        let backups = Backups {
+            directory: directory.join("backups"),
+            interval: None,
+            keep: 2,
+        };
+        let backup = backups.create(&db).await.unwrap();
+        sqlx::query("DELETE FROM users")
+            .execute(db.as_ref())
+            .await
+            .unwrap();
+        db.as_ref().close().await;
+
+        let version = restore(&backup.path, &settings).await.unwrap();
+        assert_eq!(version, Database::latest_migration());
+        let db = Database::new(&settings).await.unwrap();
+        assert_eq!(user_count(&db).await, 1);
+        let previous = Database::open_read_only(&directory.join("bookclub.db.pre-restore"))
+            .await
+            .unwrap();
+        assert_eq!(user_count(&previous).await, 0);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_newer_schema() {
+        let directory = test_directory();
+        let settings = file_database(&directory).await;
+        let db = Database::new(&settings).await.unwrap();
+        let backup = Backups {
+            directory: directory.join("backups"),
+            interval: None,
+            keep: 1,
+        }
+        .create(&db)
+        .await
+        .unwrap();
+        let newer = Database::open(&sqlite::Settings {
+            url: format!("sqlite://{}", backup.path.display()),
+        })
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES (9999, 'from the future', 1, x'00', 0)
+            "#,
+        )
+        .execute(newer.as_ref())
+        .await
+        .unwrap();
+        newer.as_ref().close().await;
+
+        let error = restore(&backup.path, &settings).await.unwrap_err();
+        assert!(error.to_string().contains("schema version 9999"));
+        assert!(!directory.join("bookclub.db.pre-restore").exists());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,378 @@
+//! Operates a bookclub instance by working on its database directly. Reads the
+//! same configuration as the server, so `SQLITE.URL` picks the database.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use api::{
+    admin::{self, Dump},
+    backup::{self, Backups},
+    load_config,
+    sqlite::{self, Database},
+};
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use shared::{BookMatch, CreateClubParams, CreateUserParams};
+
+#[derive(Parser)]
+#[command(name = "bookclub-admin", about = "Operate a bookclub instance")]
+struct Cli {
+    /// Print results as JSON instead of text.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The parts of the server's settings the admin binary uses, so the server's
+/// secrets aren't needed.
+#[derive(Deserialize)]
+struct Settings {
+    sqlite: sqlite::Settings,
+    #[serde(default)]
+    backup: backup::Settings,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and list users.
+    #[command(subcommand)]
+    User(UserCommand),
+    /// Create and list clubs.
+    #[command(subcommand)]
+    Club(ClubCommand),
+    /// Find and merge books entered more than once.
+    #[command(subcommand)]
+    Book(BookCommand),
+    /// Make a user a member of a club, or change their permission level.
+    Grant {
+        #[arg(long)]
+        user: i64,
+        #[arg(long)]
+        club: i64,
+        /// 0 for a member, 1 for a moderator, 2 for an admin.
+        #[arg(long, default_value_t = 0)]
+        level: i64,
+    },
+    /// List login sessions, newest first.
+    Sessions {
+        #[arg(long)]
+        user: Option<i64>,
+    },
+    /// Apply pending migrations.
+    Migrate,
+    /// Write every table as JSON, to a file or stdout.
+    Export {
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Load an export into a freshly migrated database.
+    Import { file: PathBuf },
+    /// Record the read shelf of a Goodreads library export as a user's reads.
+    Goodreads {
+        #[arg(long)]
+        user: i64,
+        file: PathBuf,
+        /// Show what would be imported without changing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Snapshot the database into the backup directory, removing old backups.
+    Backup,
+    /// List the backups in the backup directory, newest first.
+    Backups,
+    /// Replace the database with a backup. Stop the server first.
+    Restore { file: PathBuf },
+    /// Delete expired sessions and old notifications and webhook deliveries.
+    Prune {
+        #[arg(long, default_value_t = 30)]
+        keep_days: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum UserCommand {
+    Create {
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        first_name: String,
+        #[arg(long)]
+        last_name: String,
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+    List,
+}
+
+#[derive(Subcommand)]
+enum ClubCommand {
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long)]
+        timezone: Option<String>,
+    },
+    List,
+}
+
+#[derive(Subcommand)]
+enum BookCommand {
+    /// List groups of books that look like the same book.
+    Duplicates,
+    /// Move everything about the duplicates onto the book and delete them.
+    /// Their ids keep finding the book.
+    Merge {
+        #[arg(long)]
+        into: i64,
+        #[arg(required = true)]
+        duplicates: Vec<i64>,
+    },
+}
+
+/// Prints `value` as JSON, or as the text `text` gives when not asked for JSON.
+fn print<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T) -> String) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    } else {
+        println!("{}", text(value));
+    }
+    Ok(())
+}
+
+fn lines<T>(items: &[T], line: impl Fn(&T) -> String) -> String {
+    items.iter().map(line).collect::<Vec<_>>().join("\n")
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let settings = load_config().try_deserialize::<Settings>()?;
+    let backups = Backups::new(&settings.backup);
+    let json = cli.json;
+
+    // These work on the database file as it is, without migrating it first.
+    match cli.command {
+        Command::Migrate => {
+            let db = Database::open(&settings.sqlite).await?;
+            let applied = db.migrate().await?;
+            let version = db.schema_version().await?;
+            print(json, &applied, |applied| match applied.len() {
+                0 => format!("Already at schema version {version}"),
+                n => format!("Applied {n} migrations, now at schema version {version}"),
+            })
+        }
+        Command::Backups => print(json, &backups.list()?, |list| {
+            lines(list, |backup| {
+                format!("{}\t{} bytes", backup.path.display(), backup.size)
+            })
+        }),
+        Command::Restore { file } => {
+            let version = backup::restore(&file, &settings.sqlite).await?;
+            print(json, &version, |version| {
+                format!(
+                    "Restored {}, now at schema version {version}",
+                    file.display()
+                )
+            })
+        }
+        command => {
+            let db = Database::new(&settings.sqlite).await?;
+            run(command, &db, &backups, json).await
+        }
+    }
+}
+
+async fn run(command: Command, db: &Database, backups: &Backups, json: bool) -> Result<()> {
+    match command {
+        Command::User(UserCommand::Create {
+            email,
+            first_name,
+            last_name,
+            timezone,
+        }) => {
+            let params = CreateUserParams {
+                email,
+                first_name,
+                last_name,
+                timezone,
+            };
+            let user = admin::create_user(db, &params).await?;
+            print(json, &user, |user| {
+                format!("Created user {} <{}>", user.id, user.email)
+            })
+        }
+        Command::User(UserCommand::List) => {
+            let users = admin::list_users(db).await?;
+            print(json, &users, |users| {
+                lines(users, |user| {
+                    format!(
+                        "{}\t{}\t{} {}\t{}",
+                        user.id,
+                        user.email,
+                        user.first_name,
+                        user.last_name,
+                        user.timezone.as_deref().unwrap_or("-")
+                    )
+                })
+            })
+        }
+        Command::Club(ClubCommand::Create {
+            name,
+            description,
+            timezone,
+        }) => {
+            let params = CreateClubParams {
+                name,
+                description,
+                timezone,
+            };
+            let club = admin::create_club(db, &params).await?;
+            print(json, &club, |club| {
+                format!("Created club {} '{}'", club.id, club.name)
+            })
+        }
+        Command::Club(ClubCommand::List) => {
+            let clubs = admin::list_clubs(db).await?;
+            print(json, &clubs, |clubs| {
+                lines(clubs, |club| {
+                    format!("{}\t{}\t{}", club.id, club.name, club.timezone)
+                })
+            })
+        }
+        Command::Book(BookCommand::Duplicates) => {
+            let groups = admin::duplicate_books(db).await?;
+            print(json, &groups, |groups| {
+                groups
+                    .iter()
+                    .map(|books| {
+                        lines(books, |book| {
+                            format!("{}\t{}\t{}", book.id, book.title, book.author)
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            })
+        }
+        Command::Book(BookCommand::Merge { into, duplicates }) => {
+            let report = admin::merge_books(db, into, &duplicates).await?;
+            print(json, &report, |report| {
+                format!(
+                    "Merged {} books into {} '{}', moving {} meetings, {} reads and {} nominations",
+                    report.merged.len(),
+                    report.book.id,
+                    report.book.title,
+                    report.meetings,
+                    report.reads,
+                    report.nominations
+                )
+            })
+        }
+        Command::Grant { user, club, level } => {
+            let membership = admin::grant(db, user, club, level).await?;
+            print(json, &membership, |membership| {
+                format!(
+                    "User {} is now level {} in club {}",
+                    membership.user_id, membership.permission_level, membership.club_id
+                )
+            })
+        }
+        Command::Sessions { user } => {
+            let sessions = admin::list_sessions(db, user).await?;
+            print(json, &sessions, |sessions| {
+                lines(sessions, |session| {
+                    format!(
+                        "{}\t{}\t{}\t{}{}",
+                        session.id,
+                        session.email,
+                        session.created_at.format("%Y-%m-%d %H:%M"),
+                        session.expires_at.format("%Y-%m-%d %H:%M"),
+                        if session.is_expired() {
+                            " (expired)"
+                        } else {
+                            ""
+                        }
+                    )
+                })
+            })
+        }
+        Command::Export { output } => {
+            let dump = admin::export(db).await?;
+            let contents = serde_json::to_string_pretty(&dump)?;
+            match output {
+                Some(path) => {
+                    fs::write(&path, contents)?;
+                    eprintln!("Exported {} rows to {}", dump.row_count(), path.display());
+                }
+                None => println!("{contents}"),
+            }
+            Ok(())
+        }
+        Command::Import { file } => {
+            let dump: Dump = serde_json::from_str(&fs::read_to_string(file)?)?;
+            let rows = admin::import(db, &dump).await?;
+            print(json, &rows, |rows| format!("Imported {rows} rows"))
+        }
+        Command::Goodreads {
+            user,
+            file,
+            dry_run,
+        } => {
+            let csv = fs::read_to_string(file)?;
+            let report = admin::import_goodreads(db, user, &csv, dry_run).await?;
+            print(json, &report, |report| {
+                let reads = lines(&report.reads, |read| {
+                    let matched_by = match read.matched_by {
+                        BookMatch::Isbn => "isbn",
+                        BookMatch::TitleAndAuthor => "title",
+                        BookMatch::Created => "new",
+                    };
+                    format!(
+                        "{matched_by}\t{} by {}\t{}\t{}",
+                        read.title,
+                        read.author,
+                        read.read_at
+                            .map_or("-".to_string(), |date| date.to_string()),
+                        read.rating
+                            .map_or("-".to_string(), |rating| rating.to_string())
+                    )
+                });
+                let verb = if report.dry_run {
+                    "Would import"
+                } else {
+                    "Imported"
+                };
+                format!(
+                    "{reads}\n{verb} {} reads, creating {} books. Skipped {} unread books",
+                    report.reads.len(),
+                    report.books_created,
+                    report.skipped
+                )
+            })
+        }
+        Command::Prune { keep_days } => {
+            let report = admin::prune(db, keep_days).await?;
+            print(json, &report, |report| {
+                format!(
+                    "Removed {} sessions, {} notifications and {} webhook deliveries",
+                    report.sessions, report.notifications, report.webhook_deliveries
+                )
+            })
+        }
+        Command::Backup => {
+            let backup = backups.create(db).await?;
+            print(json, &backup, |backup| {
+                format!(
+                    "Backed up to {} ({} bytes)",
+                    backup.path.display(),
+                    backup.size
+                )
+            })
+        }
+        Command::Migrate | Command::Backups | Command::Restore { .. } => {
+            unreachable!("handled in main")
+        }
+    }
+}
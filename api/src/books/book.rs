@@ -1,24 +1,36 @@
-use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqliteConnection};
+use sqlx::SqliteConnection;
 
 use crate::error::AppResult;
 
-#[derive(Debug, FromRow, Serialize, Deserialize)]
-pub struct Book {
-    pub title: String,
-    pub author: String,
-    pub id: i64,
+pub use shared::{Book, BookMatch};
+
+/// Database access for [`Book`].
+pub trait BookExt: Sized {
+    /// Also finds books by the ids of the books merged into them, so the
+    /// book's own id may differ from `id`.
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    /// Finds the book by ISBN, then by title and author, creating it if
+    /// neither matches. Returns its id.
+    async fn find_or_create(
+        title: &str,
+        author: &str,
+        isbn: Option<&str>,
+        isbn13: Option<&str>,
+        db: &mut SqliteConnection,
+    ) -> AppResult<(i64, BookMatch)>;
 }
 
-impl Book {
-    pub async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+impl BookExt for Book {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
         let book = sqlx::query_as!(
             Book,
             r#"
             SELECT title, author, id
             FROM books
-            WHERE id = ?
+            WHERE id = COALESCE((SELECT book_id FROM book_aliases WHERE id = ?), ?)
             "#,
+            id,
             id
         )
         .fetch_optional(db)
@@ -26,4 +38,61 @@ impl Book {
 
         Ok(book)
     }
+
+    async fn find_or_create(
+        title: &str,
+        author: &str,
+        isbn: Option<&str>,
+        isbn13: Option<&str>,
+        db: &mut SqliteConnection,
+    ) -> AppResult<(i64, BookMatch)> {
+        let by_isbn = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM books WHERE isbn13 = ? OR isbn = ? ORDER BY id LIMIT 1"#,
+            isbn13,
+            isbn
+        )
+        .fetch_optional(&mut *db)
+        .await?;
+        if let Some(id) = by_isbn {
+            return Ok((id, BookMatch::Isbn));
+        }
+
+        let by_title = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM books
+            WHERE lower(title) = lower(?) AND lower(author) = lower(?)
+            ORDER BY id
+            LIMIT 1
+            "#,
+            title,
+            author
+        )
+        .fetch_optional(&mut *db)
+        .await?;
+        if let Some(id) = by_title {
+            // So the next lookup matches it by ISBN.
+            sqlx::query!(
+                "UPDATE books SET isbn = COALESCE(isbn, ?), isbn13 = COALESCE(isbn13, ?) WHERE id = ?",
+                isbn,
+                isbn13,
+                id
+            )
+            .execute(&mut *db)
+            .await?;
+            return Ok((id, BookMatch::TitleAndAuthor));
+        }
+
+        let id = sqlx::query!(
+            "INSERT INTO books (title, author, isbn, isbn13) VALUES (?, ?, ?, ?) RETURNING id",
+            title,
+            author,
+            isbn,
+            isbn13
+        )
+        .fetch_one(&mut *db)
+        .await?
+        .id;
+
+        Ok((id, BookMatch::Created))
+    }
 }
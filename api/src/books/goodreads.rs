@@ -0,0 +1,289 @@
+use crate::{
+    books::{Book, BookExt},
+    error::{error_response, AppResult},
+    sqlite::Database,
+};
+use anyhow::Result;
+use axum::{
+    debug_handler,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use shared::{BookMatch, GoodreadsImport, GoodreadsImportParams, GoodreadsRead};
+
+/// The columns of a Goodreads library export that the import uses.
+#[derive(Debug, Deserialize)]
+pub struct Row {
+    #[serde(rename = "Title")]
+    title: String,
+    #[serde(rename = "Author")]
+    author: String,
+    /// Written as `="0441013597"`, so spreadsheets keep the leading zeros.
+    #[serde(rename = "ISBN", default)]
+    isbn: String,
+    #[serde(rename = "ISBN13", default)]
+    isbn13: String,
+    /// 0 when the book wasn't rated.
+    #[serde(rename = "My Rating", default)]
+    rating: String,
+    /// e.g. `2020/05/17`, empty if the date wasn't recorded.
+    #[serde(rename = "Date Read", default)]
+    date_read: String,
+    /// The user's own shelves, e.g. `favorites, sci-fi`.
+    #[serde(rename = "Bookshelves", default)]
+    bookshelves: String,
+    /// One of `read`, `currently-reading` and `to-read`.
+    #[serde(rename = "Exclusive Shelf")]
+    exclusive_shelf: String,
+}
+
+impl Row {
+    /// The title without the series Goodreads appends, e.g. `Dune (Dune, #1)`.
+    fn title(&self) -> &str {
+        let title = self.title.trim();
+        match title.rsplit_once(" (") {
+            Some((title, series)) if series.ends_with(')') && series.contains('#') => title,
+            _ => title,
+        }
+    }
+
+    fn rating(&self) -> Option<i64> {
+        self.rating
+            .trim()
+            .parse()
+            .ok()
+            .filter(|rating| (1..=5).contains(rating))
+    }
+
+    fn read_at(&self) -> Option<NaiveDate> {
+        let date = self.date_read.trim();
+        NaiveDate::parse_from_str(date, "%Y/%m/%d")
+            .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+            .ok()
+    }
+
+    fn shelves(&self) -> Vec<String> {
+        self.bookshelves
+            .split(',')
+            .map(str::trim)
+            .filter(|shelf| !shelf.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+fn clean_isbn(isbn: &str) -> Option<String> {
+    let isbn: String = isbn
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == 'X')
+        .collect();
+    (!isbn.is_empty()).then_some(isbn)
+}
+
+pub fn parse(csv: &str) -> Result<Vec<Row>, csv::Error> {
+    csv::Reader::from_reader(csv.as_bytes())
+        .deserialize()
+        .collect()
+}
+
+/// Records the books on the read shelf as read by the user, with their read
+/// dates, ratings and shelves. Books read before are updated. A dry run makes
+/// the same changes and rolls them back.
+pub async fn import(
+    db: &Database,
+    user_id: i64,
+    rows: &[Row],
+    dry_run: bool,
+) -> Result<GoodreadsImport> {
+    let mut report = GoodreadsImport {
+        dry_run,
+        books_created: 0,
+        reads: vec![],
+        skipped: 0,
+    };
+    let mut tx = db.as_ref().begin().await?;
+
+    for row in rows {
+        if row.exclusive_shelf.trim() != "read" {
+            report.skipped += 1;
+            continue;
+        }
+
+        let isbn = clean_isbn(&row.isbn);
+        let isbn13 = clean_isbn(&row.isbn13);
+        let (book_id, matched_by) = Book::find_or_create(
+            row.title(),
+            row.author.trim(),
+            isbn.as_deref(),
+            isbn13.as_deref(),
+            &mut tx,
+        )
+        .await?;
+        let read = GoodreadsRead {
+            title: row.title().to_string(),
+            author: row.author.trim().to_string(),
+            book_id: (!dry_run || matched_by != BookMatch::Created).then_some(book_id),
+            matched_by,
+            read_at: row.read_at(),
+            rating: row.rating(),
+            shelves: row.shelves(),
+        };
+        let shelves = (!read.shelves.is_empty()).then(|| read.shelves.join(","));
+        sqlx::query!(
+            r#"
+            INSERT INTO has_read (user_id, book_id, read_at, rating, shelves)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (user_id, book_id) DO UPDATE SET
+                read_at = excluded.read_at,
+                rating = excluded.rating,
+                shelves = excluded.shelves
+            "#,
+            user_id,
+            book_id,
+            read.read_at,
+            read.rating,
+            shelves
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if matched_by == BookMatch::Created {
+            report.books_created += 1;
+        }
+        report.reads.push(read);
+    }
+
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+
+    Ok(report)
+}
+
+/// Imports the Goodreads library export CSV sent as the body.
+#[debug_handler]
+pub async fn import_goodreads(
+    State(db): State<Database>,
+    Path(user_id): Path<i64>,
+    Query(params): Query<GoodreadsImportParams>,
+    body: String,
+) -> AppResult<Response> {
+    let user = sqlx::query!("SELECT id FROM users WHERE id = ?", user_id)
+        .fetch_optional(db.as_ref())
+        .await?;
+    if user.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "User not found"));
+    }
+    let rows = match parse(&body) {
+        Ok(rows) => rows,
+        Err(e) => {
+            return Ok(error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid Goodreads export: {e}"),
+            ))
+        }
+    };
+
+    let report = import(&db, user_id, &rows, params.dry_run).await?;
+
+    Ok(Json(report).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        tests::{create_test_server, create_test_server_with_state},
+        users::test::create_test_user,
+    };
+
+    const EXPORT: &str = r#"Book Id,Title,Author,Author l-f,Additional Authors,ISBN,ISBN13,My Rating,Average Rating,Publisher,Binding,Number of Pages,Year Published,Original Publication Year,Date Read,Date Added,Bookshelves,Bookshelves with positions,Exclusive Shelf,My Review,Spoiler,Private Notes,Read Count,Owned Copies
+44767458,"Dune (Dune, #1)",Frank Herbert,"Herbert, Frank",,"=""0441013597""","=""9780441013593""",5,4.25,Ace,Paperback,658,2005,1965,2020/05/17,2020/04/01,"favorites, sci-fi","favorites (#1), sci-fi (#3)",read,,,,1,0
+1,Test Book,test author,"author, test",,"=""""","=""""",0,3.00,,,,,,,2021/01/01,,,read,,,,1,0
+2,Unread,Someone,"Someone",,"=""""","=""""",0,3.00,,,,,,,2021/01/01,,,to-read,,,,0,0
+"#;
+
+    #[test]
+    fn test_parse() {
+        let rows = parse(EXPORT).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].title(), "Dune");
+        assert_eq!(clean_isbn(&rows[0].isbn).as_deref(), Some("0441013597"));
+        assert_eq!(clean_isbn(&rows[1].isbn), None);
+        assert_eq!(rows[0].rating(), Some(5));
+        assert_eq!(rows[1].rating(), None);
+        assert_eq!(rows[0].read_at(), NaiveDate::from_ymd_opt(2020, 5, 17));
+        assert_eq!(rows[1].read_at(), None);
+        assert_eq!(rows[0].shelves(), vec!["favorites", "sci-fi"]);
+    }
+
+    #[tokio::test]
+    async fn test_import_goodreads() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let book = crate::books::test::create_test_book(&server).await;
+        let path = format!("/users/{}/goodreads-import", user.id);
+
+        let response = server
+            .post(&path)
+            .add_query_param("dry_run", true)
+            .text(EXPORT)
+            .await;
+        response.assert_status_ok();
+        let preview: GoodreadsImport = response.json();
+        assert_eq!(preview.books_created, 1);
+        assert_eq!(preview.skipped, 1);
+        assert_eq!(preview.reads[0].matched_by, BookMatch::Created);
+        assert_eq!(preview.reads[0].book_id, None);
+        assert_eq!(preview.reads[1].matched_by, BookMatch::TitleAndAuthor);
+        assert_eq!(preview.reads[1].book_id, Some(book.id));
+        let books: Vec<shared::Book> = server.get("/books/list").await.json();
+        assert_eq!(books.len(), 1);
+
+        let report: GoodreadsImport = server.post(&path).text(EXPORT).await.json();
+        assert_eq!(report.books_created, 1);
+        let dune = report.reads[0].book_id.unwrap();
+        let book: shared::Book = server.get(&format!("/books/get/{dune}")).await.json();
+        assert_eq!(book.title, "Dune");
+
+        // Importing again matches by ISBN and updates the reads in place.
+        let report: GoodreadsImport = server.post(&path).text(EXPORT).await.json();
+        assert_eq!(report.books_created, 0);
+        assert_eq!(report.reads[0].matched_by, BookMatch::Isbn);
+        let books: Vec<shared::Book> = server.get("/books/list").await.json();
+        assert_eq!(books.len(), 2);
+        let reads = sqlx::query!(
+            "SELECT rating FROM has_read WHERE user_id = ? ORDER BY id",
+            user.id
+        )
+        .fetch_all(state.db.as_ref())
+        .await
+        .unwrap();
+        assert_eq!(reads.len(), 2);
+        assert_eq!(reads[0].rating, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_import_invalid_csv() {
+        let server = create_test_server().await;
+        let user = create_test_user(&server).await;
+
+        let response = server
+            .post(&format!("/users/{}/goodreads-import", user.id))
+            .text("Title,Author\nDune")
+            .await;
+        assert_eq!(response.status_code(), 400);
+
+        let response = server
+            .post("/users/9999/goodreads-import")
+            .text(EXPORT)
+            .await;
+        assert_eq!(response.status_code(), 404);
+    }
+}
@@ -0,0 +1,412 @@
+//! Finds books entered more than once and merges them into one.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::{books::Book, sqlite::Database};
+
+/// What [`merge_books`] moved onto the book the others were merged into.
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeReport {
+    pub book: Book,
+    pub merged: Vec<i64>,
+    pub meetings: u64,
+    pub reads: u64,
+    pub nominations: u64,
+}
+
+/// The lowercased words of `text`, without punctuation.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// The title without its subtitle, series or leading article, e.g. `hobbit`
+/// for `The Hobbit: or There and Back Again`.
+fn title_key(title: &str) -> String {
+    let title = title.split([':', '(']).next().unwrap_or(title);
+    let words = words(title).collect::<Vec<_>>();
+    let words = match words.first().map(String::as_str) {
+        Some("the" | "a" | "an") if words.len() > 1 => &words[1..],
+        _ => &words[..],
+    };
+    words.join(" ")
+}
+
+/// The author's names without initials, which are written too many ways to
+/// compare, so `J.R.R. Tolkien` and `Tolkien, J. R. R.` are both `tolkien`.
+fn author_names(author: &str) -> BTreeSet<String> {
+    words(author)
+        .filter(|word| word.chars().count() > 2)
+        .collect()
+}
+
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+fn join(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (root(parents, a), root(parents, b));
+    parents[a.max(b)] = a.min(b);
+}
+
+/// Groups of books that look like the same book, oldest first: those sharing
+/// an ISBN, and those with the same title whose authors share a name.
+pub async fn duplicate_books(db: &Database) -> Result<Vec<Vec<Book>>> {
+    let rows =
+        sqlx::query!(r#"SELECT id AS "id!", title, author, isbn, isbn13 FROM books ORDER BY id"#)
+            .fetch_all(db.as_ref())
+            .await?;
+    let mut parents = (0..rows.len()).collect::<Vec<_>>();
+
+    let mut by_isbn = HashMap::new();
+    let mut by_title = HashMap::<_, Vec<_>>::new();
+    for (i, row) in rows.iter().enumerate() {
+        for isbn in [&row.isbn, &row.isbn13].into_iter().flatten() {
+            match by_isbn.get(isbn.as_str()) {
+                Some(&first) => join(&mut parents, first, i),
+                None => {
+                    by_isbn.insert(isbn.as_str(), i);
+                }
+            }
+        }
+        let key = title_key(&row.title);
+        if !key.is_empty() {
+            by_title.entry(key).or_default().push(i);
+        }
+    }
+    for books in by_title.values() {
+        let names = books
+            .iter()
+            .map(|&i| author_names(&rows[i].author))
+            .collect::<Vec<_>>();
+        for a in 0..books.len() {
+            for b in a + 1..books.len() {
+                if !names[a].is_disjoint(&names[b]) {
+                    join(&mut parents, books[a], books[b]);
+                }
+            }
+        }
+    }
+
+    let mut groups = BTreeMap::<_, Vec<_>>::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        groups.entry(root(&mut parents, i)).or_default().push(Book {
+            title: row.title,
+            author: row.author,
+            id: row.id,
+        });
+    }
+
+    Ok(groups
+        .into_values()
+        .filter(|books| books.len() > 1)
+        .collect())
+}
+
+/// Moves the meetings, reads, nominations and credits of the `duplicates` onto
+/// the book and deletes them, all in one transaction. Their ids become aliases of
+/// the book, so [`BookExt::from_id`](crate::books::BookExt::from_id) still finds it by them.
+pub async fn merge_books(db: &Database, book_id: i64, duplicates: &[i64]) -> Result<MergeReport> {
+    if duplicates.contains(&book_id) {
+        bail!("Can't merge book {book_id} into itself");
+    }
+    let mut tx = db.as_ref().begin().await?;
+    // Not `from_id`, which would take an alias for the book it stands for.
+    let book = sqlx::query_as!(
+        Book,
+        "SELECT title, author, id FROM books WHERE id = ?",
+        book_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(book) = book else {
+        bail!("Book {book_id} not found");
+    };
+    let mut report = MergeReport {
+        book,
+        merged: vec![],
+        meetings: 0,
+        reads: 0,
+        nominations: 0,
+    };
+
+    for &duplicate in duplicates {
+        let found = sqlx::query!("SELECT id FROM books WHERE id = ?", duplicate)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if found.is_none() {
+            bail!("Book {duplicate} not found");
+        }
+
+        report.meetings += sqlx::query!(
+            "UPDATE meetings SET book_id = ? WHERE book_id = ?",
+            book_id,
+            duplicate
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // A user who read both keeps one read, filled in from the other.
+        sqlx::query!(
+            r#"
+            UPDATE has_read SET
+                read_at = COALESCE(read_at, (
+                    SELECT d.read_at FROM has_read d
+                    WHERE d.user_id = has_read.user_id AND d.book_id = ?1
+                )),
+                rating = COALESCE(rating, (
+                    SELECT d.rating FROM has_read d
+                    WHERE d.user_id = has_read.user_id AND d.book_id = ?1
+                )),
+                shelves = COALESCE(shelves, (
+                    SELECT d.shelves FROM has_read d
+                    WHERE d.user_id = has_read.user_id AND d.book_id = ?1
+                ))
+            WHERE book_id = ?2
+            "#,
+            duplicate,
+            book_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        report.reads += sqlx::query!(
+            "UPDATE OR IGNORE has_read SET book_id = ? WHERE book_id = ?",
+            book_id,
+            duplicate
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query!("DELETE FROM has_read WHERE book_id = ?", duplicate)
+            .execute(&mut *tx)
+            .await?;
+
+        // Likewise a club that nominated both keeps one nomination.
+        report.nominations += sqlx::query!(
+            "UPDATE OR IGNORE nominations SET book_id = ? WHERE book_id = ?",
+            book_id,
+            duplicate
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query!("DELETE FROM nominations WHERE book_id = ?", duplicate)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE books SET
+                isbn = COALESCE(isbn, (SELECT isbn FROM books WHERE id = ?1)),
+                isbn13 = COALESCE(isbn13, (SELECT isbn13 FROM books WHERE id = ?1))
+            WHERE id = ?2
+            "#,
+            duplicate,
+            book_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE book_aliases SET book_id = ? WHERE book_id = ?",
+            book_id,
+            duplicate
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "INSERT INTO book_aliases (id, book_id) VALUES (?, ?)",
+            duplicate,
+            book_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        // Credits both books share are already on the book, the others are
+        // added to it.
+        sqlx::query!(
+            "UPDATE OR IGNORE book_authors SET book_id = ? WHERE book_id = ?",
+            book_id,
+            duplicate
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM books WHERE id = ?", duplicate)
+            .execute(&mut *tx)
+            .await?;
+
+        report.merged.push(duplicate);
+    }
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        books::BookParams, clubs::test::create_test_club, meetings::test::create_test_meeting,
+        tests::create_test_server_with_state, users::test::create_test_user,
+    };
+    use axum::http::StatusCode;
+    use shared::{Meeting, NominateParams};
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(title_key("The Hobbit: or There and Back Again"), "hobbit");
+        assert_eq!(title_key("the hobbit (Middle-earth, #0)"), "hobbit");
+        assert_eq!(title_key("The"), "the");
+        assert_eq!(
+            author_names("J.R.R. Tolkien"),
+            author_names("Tolkien, J. R. R.")
+        );
+        assert!(author_names("Anne Tolkien").contains("tolkien"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_books() {
+        let (server, state) = create_test_server_with_state().await;
+        let mut ids = vec![];
+        for (title, author) in [
+            ("The Hobbit", "J.R.R. Tolkien"),
+            ("Hobbit", "Tolkien, J. R. R."),
+            ("The Hobbit: or There and Back Again", "JRR Tolkien"),
+            ("The Hobbit", "Someone Else"),
+        ] {
+            let book: Book = server
+                .post("/books/create")
+                .json(&BookParams {
+                    title: title.to_string(),
+                    author: author.to_string(),
+                })
+                .await
+                .json();
+            ids.push(book.id);
+        }
+
+        let groups = duplicate_books(&state.db).await.unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = groups[0].iter().map(|book| book.id).collect::<Vec<_>>();
+        assert_eq!(group, ids[..3]);
+
+        let club = create_test_club(&server).await;
+        let user = create_test_user(&server).await;
+        let meeting: Meeting = create_test_meeting(&server, club.id, ids[1]).await;
+        for (book_id, rating) in [(ids[0], None), (ids[1], Some(4))] {
+            sqlx::query!(
+                "INSERT INTO has_read (user_id, book_id, rating) VALUES (?, ?, ?)",
+                user.id,
+                book_id,
+                rating
+            )
+            .execute(state.db.as_ref())
+            .await
+            .unwrap();
+        }
+        for &book_id in &ids[..3] {
+            server
+                .post(&format!("/clubs/{}/nominations", club.id))
+                .json(&NominateParams { book_id })
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+
+        let report = merge_books(&state.db, ids[0], &ids[1..3]).await.unwrap();
+        assert_eq!(report.book.id, ids[0]);
+        assert_eq!(report.merged, ids[1..3]);
+        assert_eq!(report.meetings, 1);
+        assert_eq!(report.reads, 0);
+        assert_eq!(report.nominations, 0);
+
+        let book_id = sqlx::query_scalar!("SELECT book_id FROM meetings WHERE id = ?", meeting.id)
+            .fetch_one(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(book_id, Some(ids[0]));
+        let ratings = sqlx::query_scalar!("SELECT rating FROM has_read WHERE user_id = ?", user.id)
+            .fetch_all(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(ratings, [Some(4)]);
+        let nominations = sqlx::query_scalar!("SELECT COUNT(*) FROM nominations")
+            .fetch_one(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(nominations, 1);
+
+        // The merged ids still find the book, even after merging it again.
+        merge_books(&state.db, ids[3], &ids[..1]).await.unwrap();
+        let book: Book = server.get(&format!("/books/get/{}", ids[1])).await.json();
+        assert_eq!(book.id, ids[3]);
+
+        let error = merge_books(&state.db, ids[3], &ids[1..2])
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), format!("Book {} not found", ids[1]));
+    }
+
+    #[tokio::test]
+    async fn test_merge_books_moves_credits() {
+        let (server, state) = create_test_server_with_state().await;
+        let mut ids = vec![];
+        for author in ["Frank Herbert", "frank herbert"] {
+            let book: Book = server
+                .post("/books/create")
+                .json(&BookParams {
+                    title: "Dune".to_string(),
+                    author: author.to_string(),
+                })
+                .await
+                .json();
+            ids.push(book.id);
+        }
+        let translator = sqlx::query_scalar!(
+            r#"INSERT INTO authors (name) VALUES ('Jakob Schmidt') RETURNING id AS "id!""#
+        )
+        .fetch_one(state.db.as_ref())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO book_authors (book_id, author_id, role) VALUES (?, ?, 'translator')",
+            ids[1],
+            translator
+        )
+        .execute(state.db.as_ref())
+        .await
+        .unwrap();
+
+        merge_books(&state.db, ids[0], &ids[1..]).await.unwrap();
+
+        // Both bylines are the same author, who stays credited once.
+        let credits = sqlx::query!(
+            r#"
+            SELECT authors.name, book_authors.role
+            FROM book_authors JOIN authors ON authors.id = book_authors.author_id
+            WHERE book_authors.book_id = ?
+            ORDER BY authors.id
+            "#,
+            ids[0]
+        )
+        .fetch_all(state.db.as_ref())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|credit| (credit.name, credit.role))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            credits,
+            [
+                ("Frank Herbert".to_string(), "author".to_string()),
+                ("Jakob Schmidt".to_string(), "translator".to_string()),
+            ]
+        );
+    }
+}
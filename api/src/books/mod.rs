@@ -1,25 +1,25 @@
 mod book;
+pub mod goodreads;
+pub mod merge;
 
 pub use book::*;
+pub use shared::{BookParams, BookSearchHit, FindBookParams};
 use sqlx::Row;
 
-use crate::error::AppResult;
+use crate::error::{error_response, AppResult};
 use axum::{
     debug_handler,
-    extract::{Path, Query, State},
+    extract::{OriginalUri, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
-use serde::{Deserialize, Serialize};
 
-use crate::sqlite::Database;
+use crate::{
+    pagination::{self, PageParams},
+    sqlite::Database,
+};
 
-#[derive(Deserialize, Serialize)]
-pub struct BookParams {
-    title: String,
-    author: String,
-}
 #[debug_handler]
 pub async fn create_book(
     State(db): State<Database>,
@@ -42,14 +42,22 @@ pub async fn create_book(
 }
 
 #[debug_handler]
-pub async fn get_books(State(db): State<Database>) -> AppResult<Json<Vec<Book>>> {
+pub async fn get_books(
+    State(db): State<Database>,
+    Query(params): Query<PageParams>,
+    OriginalUri(uri): OriginalUri,
+) -> AppResult<Response> {
+    let (limit, offset) = pagination::bounds(&params);
     let books = sqlx::query(
         r#"
         SELECT title, author, id
         FROM books
         ORDER BY id
+        LIMIT ? OFFSET ?
         "#,
     )
+    .bind(limit + 1)
+    .bind(offset)
     .fetch_all(db.as_ref())
     .await?
     .into_iter()
@@ -60,63 +68,149 @@ pub async fn get_books(State(db): State<Database>) -> AppResult<Json<Vec<Book>>>
     })
     .collect::<Vec<_>>();
 
-    Ok(Json(books))
+    Ok(pagination::page(books, &params, &uri))
 }
 
 #[debug_handler]
 pub async fn get_book_by_id(
     State(db): State<Database>,
     Path(id): Path<i64>,
-) -> AppResult<Json<Book>> {
-    let book = sqlx::query_as!(Book, "SELECT title, author, id FROM books WHERE id = ?", id)
-        .fetch_one(db.as_ref())
-        .await?;
-    Ok(Json(book))
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    match Book::from_id(id, &mut conn).await? {
+        Some(book) => Ok(Json(book).into_response()),
+        None => Ok(error_response(StatusCode::NOT_FOUND, "Book not found")),
+    }
 }
 
-#[derive(Deserialize)]
-pub struct FindBookParams {
-    title: Option<String>,
-    author: Option<String>,
+/// How many books a search returns unless asked for fewer.
+const MAX_RESULTS: usize = 50;
+
+/// The words of `text` as an FTS5 query, each matching as a prefix.
+fn search_terms(text: &str) -> Option<String> {
+    let terms = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{term}\"*"))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
 }
 
+/// The FTS5 query for the search, `None` if it has no words to look for.
+fn match_expression(params: &FindBookParams) -> Option<String> {
+    let expression = [
+        params.q.as_deref().and_then(search_terms),
+        params
+            .title
+            .as_deref()
+            .and_then(search_terms)
+            .map(|terms| format!("title : ({terms})")),
+        params
+            .author
+            .as_deref()
+            .and_then(search_terms)
+            .map(|terms| format!("author : ({terms})")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>();
+    (!expression.is_empty()).then(|| expression.join(" AND "))
+}
+
+/// Private use characters FTS5 wraps the matches in, so they can't be mistaken
+/// for anything in a title while it's escaped.
+const MATCH_START: char = '\u{E000}';
+const MATCH_END: char = '\u{E001}';
+
+/// HTML-escapes the text FTS5 highlighted, then marks the matches with `<mark>`.
+fn highlight(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace(MATCH_START, "<mark>")
+        .replace(MATCH_END, "</mark>")
+}
+
+/// Books matching the search, ranked with titles counting for more than
+/// authors.
 #[debug_handler]
 pub async fn find_books(
     Query(params): Query<FindBookParams>,
     State(db): State<Database>,
 ) -> Response {
-    if params.title.is_none() && params.author.is_none() {
-        return (StatusCode::BAD_REQUEST, "No search parameters provided").into_response();
-    }
+    let Some(expression) = match_expression(&params) else {
+        return error_response(StatusCode::BAD_REQUEST, "No search parameters provided");
+    };
+    let limit = params.limit.unwrap_or(MAX_RESULTS).min(MAX_RESULTS) as i64;
 
-    let db_result = sqlx::query_as!(
-        Book,
-        "SELECT title, author, id FROM books WHERE title = ? OR author = ?",
-        params.title,
-        params.author
+    let db_result = sqlx::query(
+        r#"
+        SELECT
+            books.id,
+            books.title,
+            books.author,
+            highlight(books_fts, 0, ?1, ?2) AS highlighted_title,
+            highlight(books_fts, 1, ?1, ?2) AS highlighted_author,
+            bm25(books_fts, 2.0, 1.0) AS score
+        FROM books_fts
+        JOIN books ON books.id = books_fts.rowid
+        WHERE books_fts MATCH ?3
+        ORDER BY score, books.id
+        LIMIT ?4
+        "#,
     )
+    .bind(MATCH_START.to_string())
+    .bind(MATCH_END.to_string())
+    .bind(expression)
+    .bind(limit)
     .fetch_all(db.as_ref())
     .await;
 
     match db_result {
-        Ok(books) => {
-            if books.is_empty() {
-                (StatusCode::NOT_FOUND, "No books found").into_response()
-            } else {
-                (StatusCode::OK, Json(books)).into_response()
-            }
+        Ok(rows) if rows.is_empty() => error_response(StatusCode::NOT_FOUND, "No books found"),
+        Ok(rows) => {
+            let hits = rows
+                .into_iter()
+                .map(|row| BookSearchHit {
+                    book: Book {
+                        title: row.get("title"),
+                        author: row.get("author"),
+                        id: row.get("id"),
+                    },
+                    highlighted_title: highlight(row.get("highlighted_title")),
+                    highlighted_author: highlight(row.get("highlighted_author")),
+                    score: row.get("score"),
+                })
+                .collect::<Vec<_>>();
+            (StatusCode::OK, Json(hits)).into_response()
         }
         Err(e) => {
             tracing::error!("Error fetching books: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Error fetching books").into_response()
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Error fetching books")
         }
     }
 }
 
 #[cfg(test)]
-mod test {
+pub mod test {
     use super::*;
     use crate::tests::create_test_server;
+    use axum_test::TestServer;
+
+    pub async fn create_test_book(server: &TestServer) -> Book {
+        let response = server
+            .post("/books/create")
+            .json(&BookParams {
+                title: "Test Book".to_string(),
+                author: "Test Author".to_string(),
+            })
+            .await;
+        response.assert_status_ok();
+        response.json()
+    }
+
     // Test creating a new book
     #[tokio::test]
     async fn test_create_book() {
@@ -207,4 +301,98 @@ mod test {
             .await;
         assert_eq!(response.status_code(), 404);
     }
+
+    #[test]
+    fn test_match_expression() {
+        let params = FindBookParams {
+            q: Some("Great \"Gatsby\"".to_string()),
+            author: Some("F. Scott".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            match_expression(&params).as_deref(),
+            Some(r#""Great"* "Gatsby"* AND author : ("F"* "Scott"*)"#)
+        );
+        let params = FindBookParams {
+            q: Some(" - ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(match_expression(&params), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_books() {
+        let server = create_test_server().await;
+        for (title, author) in [
+            ("The Great Gatsby", "F. Scott Fitzgerald"),
+            ("Cien años de soledad", "Gabriel García Márquez"),
+            ("Tender Is the Night", "F. Scott Fitzgerald"),
+        ] {
+            server
+                .post("/books/create")
+                .json(&BookParams {
+                    title: title.to_string(),
+                    author: author.to_string(),
+                })
+                .await
+                .assert_status_ok();
+        }
+
+        let hits: Vec<BookSearchHit> = server
+            .get("/books/search")
+            .add_query_param("q", "gats")
+            .await
+            .json();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].book.title, "The Great Gatsby");
+        assert_eq!(hits[0].highlighted_title, "The Great <mark>Gatsby</mark>");
+
+        let hits: Vec<BookSearchHit> = server
+            .get("/books/search")
+            .add_query_param("q", "garcia marquez")
+            .await
+            .json();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            hits[0].highlighted_author,
+            "Gabriel <mark>García</mark> <mark>Márquez</mark>"
+        );
+
+        let hits: Vec<BookSearchHit> = server
+            .get("/books/search")
+            .add_query_param("author", "fitzgerald")
+            .await
+            .json();
+        assert_eq!(hits.len(), 2);
+
+        let response = server
+            .get("/books/search")
+            .add_query_param("q", "   ")
+            .await;
+        assert_eq!(response.status_code(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_search_highlights_are_escaped() {
+        let server = create_test_server().await;
+        server
+            .post("/books/create")
+            .json(&BookParams {
+                title: "<script>alert(1)</script> & Gatsby".to_string(),
+                author: "O'Brien".to_string(),
+            })
+            .await
+            .assert_status_ok();
+
+        let hits: Vec<BookSearchHit> = server
+            .get("/books/search")
+            .add_query_param("q", "gatsby")
+            .await
+            .json();
+        assert_eq!(
+            hits[0].highlighted_title,
+            "&lt;script&gt;alert(1)&lt;/script&gt; &amp; <mark>Gatsby</mark>"
+        );
+        assert_eq!(hits[0].highlighted_author, "O&#39;Brien");
+    }
 }
@@ -0,0 +1,142 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+
+const PRODUCT_ID: &str = "-//bookclub//calendar//EN";
+const UID_DOMAIN: &str = "bookclub";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+// RFC 5545 lines should not be longer than 75 octets, excluding the line break.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Meetings don't record an end time, so every event gets the same length.
+const DEFAULT_MEETING_HOURS: i64 = 2;
+
+#[derive(Debug)]
+pub struct Event {
+    pub meeting_id: i64,
+    pub start: NaiveDateTime,
+    pub summary: String,
+    pub description: String,
+    pub location: Option<String>,
+}
+
+impl Event {
+    /// Derived from the meeting id only, so calendar apps keep treating an
+    /// edited meeting as the same event.
+    pub fn uid(&self) -> String {
+        format!("meeting-{}@{UID_DOMAIN}", self.meeting_id)
+    }
+}
+
+#[derive(Debug)]
+pub struct Calendar {
+    pub name: String,
+    pub events: Vec<Event>,
+}
+
+impl Calendar {
+    pub fn render(&self) -> String {
+        let stamp = Utc::now().naive_utc().format(DATE_TIME_FORMAT).to_string();
+
+        let mut out = String::new();
+        push_line(&mut out, "BEGIN:VCALENDAR");
+        push_line(&mut out, "VERSION:2.0");
+        push_line(&mut out, &format!("PRODID:{PRODUCT_ID}"));
+        push_line(&mut out, "CALSCALE:GREGORIAN");
+        push_line(&mut out, "METHOD:PUBLISH");
+        push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(&self.name)));
+
+        for event in &self.events {
+            let end = event.start + Duration::hours(DEFAULT_MEETING_HOURS);
+
+            push_line(&mut out, "BEGIN:VEVENT");
+            push_line(&mut out, &format!("UID:{}", event.uid()));
+            push_line(&mut out, &format!("DTSTAMP:{stamp}"));
+            push_line(
+                &mut out,
+                &format!("DTSTART:{}", event.start.format(DATE_TIME_FORMAT)),
+            );
+            push_line(&mut out, &format!("DTEND:{}", end.format(DATE_TIME_FORMAT)));
+            push_line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape(&event.description)),
+            );
+            if let Some(location) = &event.location {
+                push_line(&mut out, &format!("LOCATION:{}", escape(location)));
+            }
+            push_line(&mut out, "END:VEVENT");
+        }
+
+        push_line(&mut out, "END:VCALENDAR");
+        out
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folding it onto continuation lines when it is too long.
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            // The leading space of a continuation line counts towards its length.
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_long_lines_are_folded() {
+        let mut out = String::new();
+        push_line(&mut out, &"x".repeat(100));
+
+        let lines = out.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines[0].len(), MAX_LINE_OCTETS);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines[1].len(), 100 - MAX_LINE_OCTETS + 1);
+    }
+
+    #[test]
+    fn test_render_event() {
+        let calendar = Calendar {
+            name: "Test Club".to_string(),
+            events: vec![Event {
+                meeting_id: 7,
+                start: NaiveDate::from_ymd_opt(2030, 1, 9)
+                    .unwrap()
+                    .and_hms_opt(19, 0, 0)
+                    .unwrap(),
+                summary: "Test Club: Dune".to_string(),
+                description: "Reading Dune by Frank Herbert".to_string(),
+                location: Some("12 Elm Street, Springfield".to_string()),
+            }],
+        };
+
+        let ics = calendar.render();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:meeting-7@bookclub\r\n"));
+        assert!(ics.contains("DTSTART:20300109T190000Z\r\n"));
+        assert!(ics.contains("DTEND:20300109T210000Z\r\n"));
+        assert!(ics.contains("LOCATION:12 Elm Street\\, Springfield\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,324 @@
+mod ics;
+
+pub use ics::*;
+pub use shared::CalendarToken;
+
+use crate::{
+    auth::session::CurrentUser,
+    error::{error_response, AppResult},
+    sqlite::Database,
+};
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDateTime;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+struct FeedMeeting {
+    id: i64,
+    date: NaiveDateTime,
+    title: Option<String>,
+    author: Option<String>,
+    club_name: String,
+    address: Option<String>,
+    video_url: Option<String>,
+}
+
+impl From<FeedMeeting> for Event {
+    fn from(meeting: FeedMeeting) -> Self {
+        let (summary, description) = match (meeting.title, meeting.author) {
+            (Some(title), Some(author)) => (
+                format!("{}: {}", meeting.club_name, title),
+                format!("Reading {title} by {author}"),
+            ),
+            // Meetings generated from a series don't have a book picked yet.
+            _ => (
+                format!("{}: book to be decided", meeting.club_name),
+                "The book for this meeting hasn't been picked yet".to_string(),
+            ),
+        };
+
+        // Feeds are only served to club members, so private venues are included.
+        let location = meeting.address.or(meeting.video_url);
+
+        Event {
+            meeting_id: meeting.id,
+            start: meeting.date,
+            summary,
+            description,
+            location,
+        }
+    }
+}
+
+/// Issues a new calendar feed token for the user, replacing any previous one so
+/// that a leaked feed URL can be revoked. Users can only issue their own.
+#[debug_handler]
+pub async fn create_calendar_token(
+    State(db): State<Database>,
+    Path(user_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    if user.id != user_id {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "You can only issue your own calendar token",
+        ));
+    }
+
+    let token = Uuid::new_v4().simple().to_string();
+    let calendar_token = sqlx::query_as!(
+        CalendarToken,
+        r#"
+        INSERT INTO calendar_tokens (user_id, token)
+        VALUES (?, ?)
+        ON CONFLICT (user_id) DO UPDATE
+        SET token = excluded.token, created_at = CURRENT_TIMESTAMP
+        RETURNING user_id AS "user_id!", token AS "token!", created_at AS "created_at!"
+        "#,
+        user_id,
+        token
+    )
+    .fetch_one(db.as_ref())
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(calendar_token)).into_response())
+}
+
+/// Every meeting of every club the token's owner is a member of.
+#[debug_handler]
+pub async fn get_user_feed(
+    State(db): State<Database>,
+    Path(token): Path<String>,
+) -> AppResult<Response> {
+    let Some(user_id) = user_for_token(db.as_ref(), &token).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Calendar not found"));
+    };
+
+    let meetings = sqlx::query_as!(
+        FeedMeeting,
+        r#"
+        SELECT meetings.id AS "id!", meetings.date, books.title AS "title?",
+               books.author AS "author?", clubs.name AS club_name,
+               venues.address AS "address?", venues.video_url AS "video_url?"
+        FROM meetings
+        LEFT JOIN books ON books.id = meetings.book_id
+        LEFT JOIN venues ON venues.id = meetings.venue_id
+        JOIN clubs ON clubs.id = meetings.club_id
+        JOIN memberships ON memberships.club_id = meetings.club_id
+        WHERE memberships.user_id = ?
+        ORDER BY meetings.date
+        "#,
+        user_id
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    let calendar = Calendar {
+        name: "Book Club".to_string(),
+        events: meetings.into_iter().map(Event::from).collect(),
+    };
+
+    Ok(calendar_response(calendar))
+}
+
+/// The meetings of a single club, only available to its members.
+#[debug_handler]
+pub async fn get_club_feed(
+    State(db): State<Database>,
+    Path((token, club_id)): Path<(String, i64)>,
+) -> AppResult<Response> {
+    let Some(user_id) = user_for_token(db.as_ref(), &token).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Calendar not found"));
+    };
+
+    let club = sqlx::query!(
+        r#"
+        SELECT clubs.name
+        FROM clubs
+        JOIN memberships ON memberships.club_id = clubs.id
+        WHERE clubs.id = ? AND memberships.user_id = ?
+        "#,
+        club_id,
+        user_id
+    )
+    .fetch_optional(db.as_ref())
+    .await?;
+    // Clubs the user doesn't belong to are reported the same as missing ones.
+    let Some(club) = club else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Calendar not found"));
+    };
+
+    let meetings = sqlx::query_as!(
+        FeedMeeting,
+        r#"
+        SELECT meetings.id AS "id!", meetings.date, books.title AS "title?",
+               books.author AS "author?", clubs.name AS club_name,
+               venues.address AS "address?", venues.video_url AS "video_url?"
+        FROM meetings
+        LEFT JOIN books ON books.id = meetings.book_id
+        LEFT JOIN venues ON venues.id = meetings.venue_id
+        JOIN clubs ON clubs.id = meetings.club_id
+        WHERE meetings.club_id = ?
+        ORDER BY meetings.date
+        "#,
+        club_id
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    let calendar = Calendar {
+        name: club.name,
+        events: meetings.into_iter().map(Event::from).collect(),
+    };
+
+    Ok(calendar_response(calendar))
+}
+
+async fn user_for_token(db: &SqlitePool, token: &str) -> AppResult<Option<i64>> {
+    let user_id = sqlx::query_scalar!("SELECT user_id FROM calendar_tokens WHERE token = ?", token)
+        .fetch_optional(db)
+        .await?;
+
+    Ok(user_id)
+}
+
+fn calendar_response(calendar: Calendar) -> Response {
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar.render(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::books::test::create_test_book;
+    use crate::clubs::memberships::CreateMembershipParams;
+    use crate::clubs::test::create_test_club;
+    use crate::meetings::test::create_test_meeting;
+    use crate::tests::create_test_server_with_state;
+    use crate::users::test::create_test_user;
+    use crate::AppState;
+    use axum_test::TestServer;
+
+    async fn create_token(server: &TestServer, state: &AppState, user_id: i64) -> CalendarToken {
+        let session = create_session(state.db.as_ref(), user_id).await.unwrap();
+        let response = server
+            .post(&format!("/users/{}/calendar-token", user_id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={session}"))
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        response.json()
+    }
+
+    #[tokio::test]
+    async fn test_user_feed() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+        let token = create_token(&server, &state, user.id).await;
+
+        let response = server
+            .get(&format!("/calendar/{}/feed.ics", token.token))
+            .await;
+        response.assert_status(StatusCode::OK);
+        assert_eq!(
+            response.header(header::CONTENT_TYPE),
+            "text/calendar; charset=utf-8"
+        );
+        let ics = response.text();
+        assert!(ics.contains(&format!("UID:meeting-{}@bookclub", meeting.id)));
+        assert!(ics.contains(&format!("SUMMARY:{}: {}", club.name, book.title)));
+
+        let response = server
+            .get(&format!(
+                "/calendar/{}/clubs/{}/feed.ics",
+                token.token, club.id
+            ))
+            .await;
+        response.assert_status(StatusCode::OK);
+        assert!(response.text().contains("BEGIN:VEVENT"));
+    }
+
+    #[tokio::test]
+    async fn test_club_feed_requires_membership() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let club = create_test_club(&server).await;
+        let token = create_token(&server, &state, user.id).await;
+
+        let response = server
+            .get(&format!(
+                "/calendar/{}/clubs/{}/feed.ics",
+                token.token, club.id
+            ))
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rotated_token_is_revoked() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let old = create_token(&server, &state, user.id).await;
+        let new = create_token(&server, &state, user.id).await;
+        assert_ne!(old.token, new.token);
+
+        let response = server
+            .get(&format!("/calendar/{}/feed.ics", old.token))
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+
+        let response = server
+            .get(&format!("/calendar/{}/feed.ics", new.token))
+            .await;
+        response.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_calendar_token_requires_the_user() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+
+        let response = server
+            .post(&format!("/users/{}/calendar-token", user.id))
+            .await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+
+        let other = crate::users::test::create_user(
+            &server,
+            crate::users::CreateUserParams {
+                email: "other@example.com".to_string(),
+                first_name: "Other".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        let session = create_session(state.db.as_ref(), other.id).await.unwrap();
+        let response = server
+            .post(&format!("/users/{}/calendar-token", user.id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={session}"))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,177 @@
+//! Runs the client crate against the API, to keep the two in step.
+
+use axum_test::TestServer;
+use chrono::{TimeZone, Utc};
+use client::{Auth, Client, Error};
+use shared::{
+    Book, BookParams, CreateClubParams, CreateMeetingParams, CreateMembershipParams,
+    CreateUserParams, FindUserParams, VenueParams,
+};
+
+use crate::{auth::session::create_session, tests::create_http_test_server};
+
+fn client(server: &TestServer) -> Client {
+    let url = server
+        .server_address()
+        .expect("Server should listen on a port");
+    Client::new(url.as_str()).unwrap()
+}
+
+fn club_params() -> CreateClubParams {
+    CreateClubParams {
+        name: "Test Club".to_string(),
+        description: "Test Description".to_string(),
+        timezone: Some("Europe/Berlin".to_string()),
+    }
+}
+
+fn user_params() -> CreateUserParams {
+    CreateUserParams {
+        email: "test@example.com".to_string(),
+        first_name: "Test".to_string(),
+        last_name: "User".to_string(),
+        timezone: None,
+    }
+}
+
+#[tokio::test]
+async fn test_client_round_trip() {
+    let (server, _) = create_http_test_server().await;
+    let api = client(&server);
+
+    assert_eq!(api.hello().await.unwrap(), "Hello, World!");
+
+    let user = api.create_user(&user_params()).await.unwrap();
+    let club = api.create_club(&club_params()).await.unwrap();
+    let book = api
+        .create_book(&BookParams {
+            title: "Dune".to_string(),
+            author: "Frank Herbert".to_string(),
+        })
+        .await
+        .unwrap();
+    api.create_membership(&CreateMembershipParams {
+        user_id: user.id,
+        club_id: club.id,
+        permission_level: 1,
+    })
+    .await
+    .unwrap();
+
+    let found = api
+        .find_users(&FindUserParams {
+            email: Some(user.email.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(api.club(club.id).await.unwrap().name, "Test Club");
+    let members = api.club_members(club.id).await.unwrap();
+    assert_eq!(members[0].user_id, user.id);
+
+    let api = api.with_timezone("Europe/Berlin");
+    let meeting = api
+        .create_meeting(&CreateMeetingParams {
+            club_id: club.id,
+            book_id: Some(book.id),
+            date: Utc.with_ymd_and_hms(2030, 1, 9, 18, 0, 0).unwrap(),
+            venue_id: None,
+            location: None,
+            reading_target: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(meeting.date.to_rfc3339(), "2030-01-09T19:00:00+01:00");
+    let next = api.next_meeting(club.id).await.unwrap();
+    assert_eq!(next.id, meeting.id);
+
+    api.delete_meeting(meeting.id).await.unwrap();
+    assert!(api.meeting(meeting.id).await.unwrap_err().is_not_found());
+}
+
+#[tokio::test]
+async fn test_client_maps_error_responses() {
+    let (server, _) = create_http_test_server().await;
+    let api = client(&server);
+
+    match api.user(9999).await {
+        Err(Error::NotFound(message)) => assert_eq!(message, "User not found"),
+        other => panic!("Expected a not found error, got {other:?}"),
+    }
+
+    let params = CreateClubParams {
+        timezone: Some("Mars/Olympus_Mons".to_string()),
+        ..club_params()
+    };
+    match api.create_club(&params).await {
+        Err(Error::BadRequest(message)) => {
+            assert_eq!(message, "Unknown timezone 'Mars/Olympus_Mons'")
+        }
+        other => panic!("Expected a bad request error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_client_follows_pagination() {
+    let (server, state) = create_http_test_server().await;
+    for i in 0..250 {
+        sqlx::query("INSERT INTO books (title, author) VALUES (?, ?)")
+            .bind(format!("Book {i}"))
+            .bind("Author")
+            .execute(state.db.as_ref())
+            .await
+            .unwrap();
+    }
+
+    let response = server.get("/books/list").add_query_param("limit", 2).await;
+    assert_eq!(
+        response.header("link"),
+        "</books/list?limit=2&offset=2>; rel=\"next\""
+    );
+
+    let books = client(&server).books().await.unwrap();
+    assert_eq!(books.len(), 250);
+    assert_eq!(books[249].title, "Book 249");
+    // Without a limit or offset the list isn't paged.
+    let response = server.get("/books/list").await;
+    assert!(response.maybe_header("link").is_none());
+    assert_eq!(response.json::<Vec<Book>>().len(), 250);
+}
+
+#[tokio::test]
+async fn test_client_authenticates_with_session_or_token() {
+    let (server, state) = create_http_test_server().await;
+    let api = client(&server);
+    let user = api.create_user(&user_params()).await.unwrap();
+    let club = api.create_club(&club_params()).await.unwrap();
+    api.create_membership(&CreateMembershipParams {
+        user_id: user.id,
+        club_id: club.id,
+        permission_level: 1,
+    })
+    .await
+    .unwrap();
+    let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+    let venue = api
+        .clone()
+        .with_auth(Auth::Token(token.clone()))
+        .create_venue(
+            club.id,
+            &VenueParams {
+                name: Some("Anna's place".to_string()),
+                address: Some("12 Elm Street".to_string()),
+                private: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    // Only members see the address of a private venue.
+    assert_eq!(api.venue(venue.id).await.unwrap().address, None);
+    for auth in [Auth::Session(token.clone()), Auth::Token(token.clone())] {
+        let venue = api.clone().with_auth(auth).venue(venue.id).await.unwrap();
+        assert_eq!(venue.address.as_deref(), Some("12 Elm Street"));
+    }
+}
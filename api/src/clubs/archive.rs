@@ -0,0 +1,834 @@
+use std::collections::HashMap;
+
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use shared::{
+    ArchivedBook, ArchivedClub, ArchivedMeeting, ArchivedMember, ArchivedNomination, ArchivedRead,
+    ArchivedVenue, ClubArchive, ImportedClub, CLUB_ARCHIVE_VERSION,
+};
+use sqlx::SqliteConnection;
+
+use crate::{
+    auth::session::CurrentUser,
+    books::{Book, BookExt},
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    sqlite::Database,
+    timezone::parse_timezone,
+};
+
+const PERMISSION_NAMES: [&str; 3] = ["member", "moderator", "admin"];
+
+/// Reads everything the club has recorded.
+pub async fn load(club: &Club, db: &mut SqliteConnection) -> AppResult<ClubArchive> {
+    let members = sqlx::query_as!(
+        ArchivedMember,
+        r#"
+        SELECT users.email AS "email!", users.first_name AS "first_name!",
+               users.last_name AS "last_name!", users.timezone,
+               memberships.permission_level, memberships.created_at AS joined_at
+        FROM memberships
+        JOIN users ON users.id = memberships.user_id
+        WHERE memberships.club_id = ?
+        ORDER BY memberships.created_at, memberships.id
+        "#,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let books = sqlx::query_as!(
+        ArchivedBook,
+        r#"
+        SELECT id AS "id!", title, author, isbn, isbn13
+        FROM books
+        WHERE id IN (SELECT book_id FROM meetings WHERE club_id = ?)
+           OR id IN (SELECT book_id FROM nominations WHERE club_id = ?)
+        ORDER BY id
+        "#,
+        club.id,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let venues = sqlx::query_as!(
+        ArchivedVenue,
+        r#"
+        SELECT id AS "id!", name, address, directions, video_url, private, saved
+        FROM venues
+        WHERE club_id = ?
+        ORDER BY id
+        "#,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT id, date, book_id, venue_id, reading_target
+        FROM meetings
+        WHERE club_id = ?
+        ORDER BY date
+        "#,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+    let mut meetings = vec![];
+    for row in rows {
+        let attendees = sqlx::query_scalar!(
+            r#"
+            SELECT users.email AS "email!"
+            FROM attendance
+            JOIN users ON users.id = attendance.user_id
+            WHERE attendance.meeting_id = ?
+            ORDER BY users.email
+            "#,
+            row.id
+        )
+        .fetch_all(&mut *db)
+        .await?;
+        meetings.push(ArchivedMeeting {
+            date: row.date.and_utc(),
+            book_id: row.book_id,
+            venue_id: row.venue_id,
+            reading_target: row.reading_target,
+            attendees,
+        });
+    }
+
+    let nominations = sqlx::query_as!(
+        ArchivedNomination,
+        r#"
+        SELECT nominations.book_id, users.email AS "nominated_by?", nominations.created_at
+        FROM nominations
+        LEFT JOIN users ON users.id = nominations.nominated_by
+        WHERE nominations.club_id = ?
+        ORDER BY nominations.created_at, nominations.id
+        "#,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let reads = sqlx::query_as!(
+        ArchivedRead,
+        r#"
+        SELECT users.email AS "email!", has_read.book_id,
+               has_read.read_at AS "read_at: NaiveDate", has_read.rating
+        FROM has_read
+        JOIN users ON users.id = has_read.user_id
+        JOIN memberships ON memberships.user_id = has_read.user_id
+        WHERE memberships.club_id = ?
+          AND (has_read.book_id IN (SELECT book_id FROM meetings WHERE club_id = ?)
+               OR has_read.book_id IN (SELECT book_id FROM nominations WHERE club_id = ?))
+        ORDER BY has_read.book_id, users.email
+        "#,
+        club.id,
+        club.id,
+        club.id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    Ok(ClubArchive {
+        version: CLUB_ARCHIVE_VERSION,
+        exported_at: Utc::now().naive_utc(),
+        club: ArchivedClub {
+            name: club.name.clone(),
+            description: club.description.clone(),
+            timezone: club.timezone.clone(),
+            created_at: club.created_at,
+        },
+        members,
+        books,
+        venues,
+        meetings,
+        nominations,
+        reads,
+    })
+}
+
+/// Renders the archive as a Markdown club history, with times in the club's
+/// timezone.
+pub fn history(archive: &ClubArchive) -> String {
+    let tz: Tz = archive.club.timezone.parse().unwrap_or(Tz::UTC);
+    let names: HashMap<&str, String> = archive
+        .members
+        .iter()
+        .map(|member| {
+            let name = format!("{} {}", member.first_name, member.last_name);
+            (member.email.as_str(), name)
+        })
+        .collect();
+    let name = |email: &str| names.get(email).cloned().unwrap_or(email.to_string());
+    let books: HashMap<i64, &ArchivedBook> =
+        archive.books.iter().map(|book| (book.id, book)).collect();
+    let book = |id: Option<i64>| {
+        id.and_then(|id| books.get(&id))
+            .map(|book| format!("{} by {}", book.title, book.author))
+    };
+
+    let mut markdown = format!("# {}\n\n", archive.club.name);
+    if !archive.club.description.is_empty() {
+        markdown += &format!("{}\n\n", archive.club.description);
+    }
+    markdown += &format!(
+        "Founded {}, meeting in {}. Exported {}.\n",
+        archive.club.created_at.format("%-d %B %Y"),
+        archive.club.timezone,
+        archive.exported_at.format("%-d %B %Y"),
+    );
+
+    markdown += "\n## Members\n\n";
+    for member in &archive.members {
+        let level = usize::try_from(member.permission_level)
+            .ok()
+            .and_then(|level| PERMISSION_NAMES.get(level))
+            .unwrap_or(&"member");
+        markdown += &format!(
+            "- {} {} ({level}), joined {}\n",
+            member.first_name,
+            member.last_name,
+            member.joined_at.format("%-d %B %Y")
+        );
+    }
+
+    markdown += "\n## Meetings\n";
+    for meeting in &archive.meetings {
+        let date = meeting.date.with_timezone(&tz).format("%-d %B %Y, %H:%M");
+        let mut details = vec![];
+        if let Some(reading_target) = &meeting.reading_target {
+            details.push(format!("Reading target: {reading_target}"));
+        }
+        let venue = meeting
+            .venue_id
+            .and_then(|id| archive.venues.iter().find(|venue| venue.id == id));
+        if let Some(venue) = venue {
+            let location = [&venue.name, &venue.address, &venue.video_url]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            details.push(format!("Location: {}", location.join(", ")));
+        }
+        if !meeting.attendees.is_empty() {
+            let attendees = meeting.attendees.iter().map(|email| name(email));
+            details.push(format!(
+                "Attended: {}",
+                attendees.collect::<Vec<_>>().join(", ")
+            ));
+        }
+        let ratings = archive
+            .reads
+            .iter()
+            .filter(|read| Some(read.book_id) == meeting.book_id)
+            .filter_map(|read| Some(format!("{} {}/5", name(&read.email), read.rating?)))
+            .collect::<Vec<_>>();
+        if !ratings.is_empty() {
+            details.push(format!("Ratings: {}", ratings.join(", ")));
+        }
+
+        match book(meeting.book_id) {
+            Some(book) => markdown += &format!("\n### {date}: {book}\n"),
+            None => markdown += &format!("\n### {date}\n"),
+        }
+        if !details.is_empty() {
+            markdown += "\n";
+            for detail in details {
+                markdown += &format!("- {detail}\n");
+            }
+        }
+    }
+
+    if !archive.nominations.is_empty() {
+        markdown += "\n## Nominations\n\n";
+        for nomination in &archive.nominations {
+            let Some(book) = book(Some(nomination.book_id)) else {
+                continue;
+            };
+            markdown += &format!("- {book}");
+            if let Some(email) = &nomination.nominated_by {
+                markdown += &format!(", nominated by {}", name(email));
+            }
+            markdown += &format!(" on {}\n", nomination.created_at.format("%-d %B %Y"));
+        }
+    }
+
+    markdown
+}
+
+/// Why the archive can't be imported, if it can't.
+fn validate(archive: &ClubArchive) -> Option<String> {
+    if archive.version != CLUB_ARCHIVE_VERSION {
+        return Some(format!(
+            "Unsupported archive version {}, expected {CLUB_ARCHIVE_VERSION}",
+            archive.version
+        ));
+    }
+    if let Err((_, message)) = parse_timezone(&archive.club.timezone) {
+        return Some(message);
+    }
+    if let Some(member) = archive
+        .members
+        .iter()
+        .find(|member| !(0..=2).contains(&member.permission_level))
+    {
+        return Some(format!("Invalid permission level for {}", member.email));
+    }
+    None
+}
+
+/// Creates a club from the archive, returning its id and the emails of the
+/// members that were skipped. Members without an account are created; members
+/// with one, other than the importer, are skipped along with their reads and
+/// attendance, since an archive can name anyone. Books are matched like any
+/// other import. People the archive mentions who aren't members are left out.
+pub async fn restore(
+    archive: &ClubArchive,
+    importer_id: i64,
+    db: &mut SqliteConnection,
+) -> AppResult<(i64, Vec<String>)> {
+    let now = Utc::now().naive_utc();
+    let club_id = sqlx::query!(
+        r#"
+        INSERT INTO clubs (name, description, timezone, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+        archive.club.name,
+        archive.club.description,
+        archive.club.timezone,
+        archive.club.created_at,
+        now
+    )
+    .fetch_one(&mut *db)
+    .await?
+    .id;
+
+    let mut users = HashMap::new();
+    let mut skipped = vec![];
+    for member in &archive.members {
+        let existing = sqlx::query_scalar!(
+            r#"SELECT id AS "id!" FROM users WHERE email = ?"#,
+            member.email
+        )
+        .fetch_optional(&mut *db)
+        .await?;
+        let user_id = match existing {
+            Some(user_id) if user_id == importer_id => user_id,
+            Some(_) => {
+                skipped.push(member.email.clone());
+                continue;
+            }
+            None => {
+                let timezone = member
+                    .timezone
+                    .as_deref()
+                    .filter(|timezone| parse_timezone(timezone).is_ok());
+                sqlx::query_scalar!(
+                    r#"
+                    INSERT INTO users (email, first_name, last_name, timezone)
+                    VALUES (?, ?, ?, ?)
+                    RETURNING id AS "id!"
+                    "#,
+                    member.email,
+                    member.first_name,
+                    member.last_name,
+                    timezone
+                )
+                .fetch_one(&mut *db)
+                .await?
+            }
+        };
+        sqlx::query!(
+            r#"
+            INSERT INTO memberships (user_id, club_id, permission_level, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (user_id, club_id) DO NOTHING
+            "#,
+            user_id,
+            club_id,
+            member.permission_level,
+            member.joined_at
+        )
+        .execute(&mut *db)
+        .await?;
+        users.insert(member.email.as_str(), user_id);
+    }
+    // Whoever imports the club can manage it.
+    sqlx::query!(
+        r#"
+        INSERT INTO memberships (user_id, club_id, permission_level)
+        VALUES (?, ?, 2)
+        ON CONFLICT (user_id, club_id) DO UPDATE SET permission_level = 2
+        "#,
+        importer_id,
+        club_id
+    )
+    .execute(&mut *db)
+    .await?;
+
+    let mut books = HashMap::new();
+    for book in &archive.books {
+        let (book_id, _) = Book::find_or_create(
+            &book.title,
+            &book.author,
+            book.isbn.as_deref(),
+            book.isbn13.as_deref(),
+            &mut *db,
+        )
+        .await?;
+        books.insert(book.id, book_id);
+    }
+
+    let mut venues = HashMap::new();
+    for venue in &archive.venues {
+        let venue_id = sqlx::query!(
+            r#"
+            INSERT INTO venues (club_id, name, address, directions, video_url, private, saved)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+            club_id,
+            venue.name,
+            venue.address,
+            venue.directions,
+            venue.video_url,
+            venue.private,
+            venue.saved
+        )
+        .fetch_one(&mut *db)
+        .await?
+        .id;
+        venues.insert(venue.id, venue_id);
+    }
+
+    for meeting in &archive.meetings {
+        let date = meeting.date.naive_utc();
+        let book_id = meeting.book_id.and_then(|id| books.get(&id));
+        let venue_id = meeting.venue_id.and_then(|id| venues.get(&id));
+        let meeting_id = sqlx::query!(
+            r#"
+            INSERT INTO meetings (date, book_id, club_id, venue_id, reading_target)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+            date,
+            book_id,
+            club_id,
+            venue_id,
+            meeting.reading_target
+        )
+        .fetch_one(&mut *db)
+        .await?
+        .id;
+        for user_id in meeting
+            .attendees
+            .iter()
+            .filter_map(|email| users.get(email.as_str()))
+        {
+            sqlx::query!(
+                "INSERT INTO attendance (user_id, meeting_id) VALUES (?, ?)",
+                user_id,
+                meeting_id
+            )
+            .execute(&mut *db)
+            .await?;
+        }
+    }
+
+    for nomination in &archive.nominations {
+        let Some(book_id) = books.get(&nomination.book_id) else {
+            continue;
+        };
+        let nominated_by = nomination
+            .nominated_by
+            .as_deref()
+            .and_then(|email| users.get(email));
+        sqlx::query!(
+            r#"
+            INSERT INTO nominations (club_id, book_id, nominated_by, created_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (club_id, book_id) DO NOTHING
+            "#,
+            club_id,
+            book_id,
+            nominated_by,
+            nomination.created_at
+        )
+        .execute(&mut *db)
+        .await?;
+    }
+
+    for read in &archive.reads {
+        let (Some(user_id), Some(book_id)) =
+            (users.get(read.email.as_str()), books.get(&read.book_id))
+        else {
+            continue;
+        };
+        // Reads the user already has on this instance are kept.
+        sqlx::query!(
+            r#"
+            INSERT INTO has_read (user_id, book_id, read_at, rating)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (user_id, book_id) DO NOTHING
+            "#,
+            user_id,
+            book_id,
+            read.read_at,
+            read.rating
+        )
+        .execute(&mut *db)
+        .await?;
+    }
+
+    Ok((club_id, skipped))
+}
+
+/// Loads the club if the user is one of its admins, or answers why not.
+async fn club_for_admin(
+    club_id: i64,
+    user_id: i64,
+    db: &mut SqliteConnection,
+) -> AppResult<Result<Club, Response>> {
+    let Some(club) = Club::from_id(club_id, &mut *db).await? else {
+        return Ok(Err(error_response(StatusCode::NOT_FOUND, "Club not found")));
+    };
+    let permission_level = sqlx::query_scalar!(
+        "SELECT permission_level FROM memberships WHERE user_id = ? AND club_id = ?",
+        user_id,
+        club_id
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+    if permission_level != Some(2) {
+        return Ok(Err(error_response(
+            StatusCode::FORBIDDEN,
+            "Only club admins can export the club",
+        )));
+    }
+
+    Ok(Ok(club))
+}
+
+/// The club's archive as JSON, for club admins.
+#[debug_handler]
+pub async fn export_archive(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    let club = match club_for_admin(club_id, user.id, &mut conn).await? {
+        Ok(club) => club,
+        Err(response) => return Ok(response),
+    };
+
+    Ok(Json(load(&club, &mut conn).await?).into_response())
+}
+
+/// The club's archive as a Markdown history, for club admins.
+#[debug_handler]
+pub async fn export_history(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<Response> {
+    let mut conn = db.as_ref().acquire().await?;
+    let club = match club_for_admin(club_id, user.id, &mut conn).await? {
+        Ok(club) => club,
+        Err(response) => return Ok(response),
+    };
+    let archive = load(&club, &mut conn).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+        history(&archive),
+    )
+        .into_response())
+}
+
+/// Creates a club from an archive exported by this or another instance. The
+/// importing user becomes one of its admins. Members who already have an
+/// account aren't added, and are listed in the response.
+#[debug_handler]
+pub async fn import_archive(
+    State(db): State<Database>,
+    CurrentUser(user): CurrentUser,
+    Json(archive): Json<ClubArchive>,
+) -> AppResult<Response> {
+    if let Some(message) = validate(&archive) {
+        return Ok(error_response(StatusCode::BAD_REQUEST, message));
+    }
+
+    let mut tx = db.as_ref().begin().await?;
+    let (club_id, skipped_members) = restore(&archive, user.id, &mut tx).await?;
+    let club = Club::from_id(club_id, &mut tx)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Imported club {club_id} not found"))?;
+    tx.commit().await?;
+
+    let imported = ImportedClub {
+        club,
+        skipped_members,
+    };
+    Ok((StatusCode::CREATED, Json(imported)).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        auth::session::{create_session, SESSION_COOKIE},
+        books::test::create_test_book,
+        clubs::{memberships::CreateMembershipParams, test::create_test_club},
+        meetings::test::create_test_meeting,
+        tests::create_test_server_with_state,
+        users::test::create_test_user,
+    };
+    use chrono::NaiveDateTime;
+    use shared::Meeting;
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let user = create_test_user(&server).await;
+        let book = create_test_book(&server).await;
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 2,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+        let meeting: Meeting = create_test_meeting(&server, club.id, book.id).await;
+        sqlx::query!(
+            "INSERT INTO attendance (user_id, meeting_id) VALUES (?, ?)",
+            user.id,
+            meeting.id
+        )
+        .execute(state.db.as_ref())
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO has_read (user_id, book_id, rating) VALUES (?, ?, 4)",
+            user.id,
+            book.id
+        )
+        .execute(state.db.as_ref())
+        .await
+        .unwrap();
+        let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let cookie = format!("{SESSION_COOKIE}={token}");
+
+        let response = server
+            .get(&format!("/clubs/{}/archive", club.id))
+            .add_header("cookie", cookie.clone())
+            .await;
+        response.assert_status_ok();
+        let archive: ClubArchive = response.json();
+        assert_eq!(archive.version, CLUB_ARCHIVE_VERSION);
+        assert_eq!(archive.members[0].email, user.email);
+        assert_eq!(archive.meetings[0].book_id, Some(book.id));
+        assert_eq!(archive.meetings[0].attendees, vec![user.email.clone()]);
+        assert_eq!(archive.reads[0].rating, Some(4));
+
+        let response = server
+            .get(&format!("/clubs/{}/archive.md", club.id))
+            .add_header("cookie", cookie.clone())
+            .await;
+        response.assert_status_ok();
+        let markdown = response.text();
+        assert!(markdown.starts_with(&format!("# {}\n", club.name)));
+        assert!(markdown.contains("### 9 January 2030, 19:00: Test Book by Test Author"));
+        assert!(markdown.contains("- Ratings: Test User 4/5"));
+
+        let response = server
+            .post("/clubs/import")
+            .add_header("cookie", cookie.clone())
+            .json(&archive)
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let ImportedClub {
+            club: imported,
+            skipped_members,
+        } = response.json();
+        assert!(skipped_members.is_empty());
+        assert_ne!(imported.id, club.id);
+        assert_eq!(imported.name, club.name);
+
+        // Same users and books, so the new club's archive only differs in ids.
+        let response = server
+            .get(&format!("/clubs/{}/archive", imported.id))
+            .add_header("cookie", cookie)
+            .await;
+        let reimported: ClubArchive = response.json();
+        assert_eq!(reimported.members.len(), 1);
+        assert_eq!(reimported.books[0].id, book.id);
+        assert_eq!(reimported.meetings[0].date, archive.meetings[0].date);
+        assert_eq!(reimported.meetings[0].attendees, vec![user.email]);
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_existing_accounts() {
+        let (server, state) = create_test_server_with_state().await;
+        let importer = create_test_user(&server).await;
+        let other = crate::users::test::create_user(
+            &server,
+            crate::users::CreateUserParams {
+                email: "other@example.com".to_string(),
+                first_name: "Other".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        let token = create_session(state.db.as_ref(), importer.id)
+            .await
+            .unwrap();
+        let member = |email: &str, permission_level| ArchivedMember {
+            email: email.to_string(),
+            first_name: "Archived".to_string(),
+            last_name: "Member".to_string(),
+            timezone: None,
+            permission_level,
+            joined_at: NaiveDateTime::default(),
+        };
+        let read = |email: &str| ArchivedRead {
+            email: email.to_string(),
+            book_id: 1,
+            read_at: None,
+            rating: Some(1),
+        };
+        let archive = ClubArchive {
+            version: CLUB_ARCHIVE_VERSION,
+            exported_at: NaiveDateTime::default(),
+            club: ArchivedClub {
+                name: "Imported Club".to_string(),
+                description: String::new(),
+                timezone: "UTC".to_string(),
+                created_at: NaiveDateTime::default(),
+            },
+            members: vec![
+                member(&other.email, 2),
+                member("new@example.com", 0),
+                member(&importer.email, 0),
+            ],
+            books: vec![ArchivedBook {
+                id: 1,
+                title: "Archived Book".to_string(),
+                author: "Archived Author".to_string(),
+                isbn: None,
+                isbn13: None,
+            }],
+            venues: vec![],
+            meetings: vec![ArchivedMeeting {
+                date: Utc::now(),
+                book_id: Some(1),
+                venue_id: None,
+                reading_target: None,
+                attendees: vec![other.email.clone(), "new@example.com".to_string()],
+            }],
+            nominations: vec![],
+            reads: vec![read(&other.email), read("new@example.com")],
+        };
+
+        let response = server
+            .post("/clubs/import")
+            .add_header("cookie", format!("{SESSION_COOKIE}={token}"))
+            .json(&archive)
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let imported: ImportedClub = response.json();
+        assert_eq!(imported.skipped_members, vec![other.email]);
+
+        let members = sqlx::query_scalar!(
+            r#"
+            SELECT users.email AS "email!"
+            FROM memberships
+            JOIN users ON users.id = memberships.user_id
+            WHERE memberships.club_id = ?
+            ORDER BY users.email
+            "#,
+            imported.club.id
+        )
+        .fetch_all(state.db.as_ref())
+        .await
+        .unwrap();
+        assert_eq!(members, vec!["new@example.com", importer.email.as_str()]);
+
+        let reads = sqlx::query_scalar!("SELECT user_id FROM has_read WHERE user_id = ?", other.id)
+            .fetch_all(state.db.as_ref())
+            .await
+            .unwrap();
+        assert!(reads.is_empty());
+        let attended = sqlx::query_scalar!(
+            "SELECT meeting_id FROM attendance WHERE user_id = ?",
+            other.id
+        )
+        .fetch_all(state.db.as_ref())
+        .await
+        .unwrap();
+        assert!(attended.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_requires_admin() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let user = create_test_user(&server).await;
+        let path = format!("/clubs/{}/archive", club.id);
+
+        server.get(&path).await.assert_status_unauthorized();
+
+        let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let response = server
+            .get(&path)
+            .add_header("cookie", format!("{SESSION_COOKIE}={token}"))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_import_checks_version() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let archive = ClubArchive {
+            version: CLUB_ARCHIVE_VERSION + 1,
+            exported_at: NaiveDateTime::default(),
+            club: ArchivedClub {
+                name: "Test Club".to_string(),
+                description: String::new(),
+                timezone: "UTC".to_string(),
+                created_at: NaiveDateTime::default(),
+            },
+            members: vec![],
+            books: vec![],
+            venues: vec![],
+            meetings: vec![],
+            nominations: vec![],
+            reads: vec![],
+        };
+
+        let response = server
+            .post("/clubs/import")
+            .add_header("cookie", format!("{SESSION_COOKIE}={token}"))
+            .json(&archive)
+            .await;
+        response.assert_status_bad_request();
+    }
+}
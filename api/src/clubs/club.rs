@@ -1,24 +1,23 @@
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqliteConnection};
+use chrono_tz::Tz;
+use sqlx::SqliteConnection;
 
 use crate::error::AppResult;
 
-#[derive(Debug, FromRow, Serialize, Deserialize)]
-pub struct Club {
-    pub id: i64,
-    pub name: String,
-    pub description: String,
-    pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime,
+pub use shared::Club;
+
+/// Database access for [`Club`].
+pub trait ClubExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    fn tz(&self) -> Tz;
 }
 
-impl Club {
-    pub async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+impl ClubExt for Club {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
         let club = sqlx::query_as!(
             Club,
             r#"
-            SELECT id, name, description, created_at, updated_at
+            SELECT id, name, description, timezone, created_at, updated_at
             FROM clubs
             WHERE id = ?
             "#,
@@ -29,4 +28,9 @@ impl Club {
 
         Ok(club)
     }
+
+    fn tz(&self) -> Tz {
+        // Timezones are validated before they are stored.
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
 }
@@ -1,20 +1,23 @@
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, SqliteConnection};
-
-use crate::error::AppResult;
-
-#[derive(Debug, FromRow, Serialize, Deserialize)]
-pub struct Membership {
-    pub id: i64,
-    pub user_id: i64,
-    pub club_id: i64,
-    pub permission_level: i64,
-    pub created_at: NaiveDateTime,
+use sqlx::SqliteConnection;
+
+use crate::{auth::session::CurrentUser, error::AppResult};
+
+pub use shared::Membership;
+
+/// Database access for [`Membership`].
+pub trait MembershipExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    /// Whether the logged in user, if any, belongs to the club.
+    async fn is_member(
+        user: Option<&CurrentUser>,
+        club_id: i64,
+        db: &mut SqliteConnection,
+    ) -> AppResult<bool>;
 }
 
-impl Membership {
-    pub async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+impl MembershipExt for Membership {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
         let membership = sqlx::query_as!(
             Membership,
             r#"
@@ -29,4 +32,23 @@ impl Membership {
 
         Ok(membership)
     }
+
+    async fn is_member(
+        user: Option<&CurrentUser>,
+        club_id: i64,
+        db: &mut SqliteConnection,
+    ) -> AppResult<bool> {
+        let Some(CurrentUser(user)) = user else {
+            return Ok(false);
+        };
+        let membership = sqlx::query!(
+            "SELECT id FROM memberships WHERE user_id = ? AND club_id = ?",
+            user.id,
+            club_id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(membership.is_some())
+    }
 }
@@ -1,25 +1,23 @@
 mod membership;
 
 pub use membership::*;
+pub use shared::{CreateMembershipParams, Member};
 
-use crate::{error::AppResult, sqlite::Database};
+use crate::{
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    notifications::{self, NotificationEvent},
+    pagination::{self, PageParams},
+    sqlite::Database,
+    webhooks::{self, WebhookEvent},
+};
 use axum::{
     debug_handler,
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::{Deserialize, Serialize};
-
-use crate::AppState;
-
-#[derive(Deserialize, Serialize)]
-pub struct CreateMembershipParams {
-    user_id: i64,
-    club_id: i64,
-    permission_level: i32,
-}
 
 #[debug_handler]
 pub async fn create_membership(
@@ -32,13 +30,18 @@ pub async fn create_membership(
 ) -> AppResult<impl IntoResponse> {
     // Validate permission level
     if !(0..=2).contains(&permission_level) {
-        return Ok((
+        return Ok(error_response(
             StatusCode::BAD_REQUEST,
             "Permission level must be between 0 and 2",
-        )
-            .into_response());
+        ));
     }
 
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(club) = Club::from_id(club_id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    };
+
     let id = sqlx::query!(
         r#"
         INSERT INTO memberships (user_id, club_id, permission_level)
@@ -49,10 +52,20 @@ pub async fn create_membership(
         club_id,
         permission_level
     )
-    .fetch_one(db.as_ref())
+    .fetch_one(&mut *tx)
     .await?
     .id;
 
+    notifications::enqueue(
+        &mut tx,
+        user_id,
+        NotificationEvent::ClubJoined,
+        &format!("You've joined {}", club.name),
+        &format!("You are now a member of {}. Welcome!", club.name),
+        None,
+    )
+    .await?;
+
     let membership = sqlx::query_as!(
         Membership,
         r#"
@@ -62,8 +75,16 @@ pub async fn create_membership(
         "#,
         id
     )
-    .fetch_one(db.as_ref())
+    .fetch_one(&mut *tx)
+    .await?;
+    webhooks::enqueue(
+        &mut tx,
+        club_id,
+        WebhookEvent::MembershipCreated,
+        &membership,
+    )
     .await?;
+    tx.commit().await?;
 
     Ok((StatusCode::CREATED, Json(membership)).into_response())
 }
@@ -73,37 +94,58 @@ pub async fn delete_membership(
     State(db): State<Database>,
     Path(id): Path<i64>,
 ) -> AppResult<impl IntoResponse> {
-    let result = sqlx::query!(
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(membership) = Membership::from_id(id, &mut tx).await? else {
+        return Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Membership not found",
+        ));
+    };
+    sqlx::query!(
         r#"
         DELETE FROM memberships
         WHERE id = ?
         "#,
         id
     )
-    .execute(db.as_ref())
+    .execute(&mut *tx)
     .await?;
-
-    if result.rows_affected() == 0 {
-        return Ok((StatusCode::NOT_FOUND, "Membership not found").into_response());
-    }
+    webhooks::enqueue(
+        &mut tx,
+        membership.club_id,
+        WebhookEvent::MembershipDeleted,
+        &membership,
+    )
+    .await?;
+    tx.commit().await?;
 
     Ok((StatusCode::OK, "Membership deleted successfully").into_response())
 }
 
 #[debug_handler]
-pub async fn get_memberships(State(db): State<Database>) -> AppResult<Json<Vec<Membership>>> {
+pub async fn get_memberships(
+    State(db): State<Database>,
+    Query(params): Query<PageParams>,
+    OriginalUri(uri): OriginalUri,
+) -> AppResult<Response> {
+    let (limit, offset) = pagination::bounds(&params);
+    let fetch = limit + 1;
     let memberships = sqlx::query_as!(
         Membership,
         r#"
         SELECT id, user_id, club_id, permission_level, created_at
         FROM memberships
         ORDER BY id
-        "#
+        LIMIT ? OFFSET ?
+        "#,
+        fetch,
+        offset
     )
     .fetch_all(db.as_ref())
     .await?;
 
-    Ok(Json(memberships))
+    Ok(pagination::page(memberships, &params, &uri))
 }
 
 #[debug_handler]
@@ -111,22 +153,46 @@ pub async fn get_membership_by_id(
     State(db): State<Database>,
     Path(id): Path<i64>,
 ) -> AppResult<impl IntoResponse> {
-    let membership = sqlx::query_as!(
-        Membership,
+    let mut conn = db.as_ref().acquire().await?;
+
+    match Membership::from_id(id, &mut conn).await? {
+        Some(m) => Ok(Json(m).into_response()),
+        None => Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Membership not found",
+        )),
+    }
+}
+
+#[debug_handler]
+pub async fn get_club_members(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    if Club::from_id(club_id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+
+    let members = sqlx::query_as!(
+        Member,
         r#"
-        SELECT id, user_id, club_id, permission_level, created_at
+        SELECT users.id AS "user_id!", users.first_name AS "first_name!",
+               users.last_name AS "last_name!",
+               memberships.permission_level AS "permission_level!",
+               memberships.created_at AS "joined_at!"
         FROM memberships
-        WHERE id = ?
+        JOIN users ON users.id = memberships.user_id
+        WHERE memberships.club_id = ?
+        ORDER BY users.first_name, users.last_name
         "#,
-        id
+        club_id
     )
-    .fetch_optional(db.as_ref())
+    .fetch_all(&mut *conn)
     .await?;
 
-    match membership {
-        Some(m) => Ok(Json(m).into_response()),
-        None => Ok((StatusCode::NOT_FOUND, "Membership not found").into_response()),
-    }
+    Ok(Json(members).into_response())
 }
 
 #[cfg(test)]
@@ -191,4 +257,29 @@ mod test {
         let response = server.get(&format!("/memberships/{}", membership.id)).await;
         assert_eq!(response.status_code(), 404);
     }
+
+    #[tokio::test]
+    async fn test_get_club_members() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let user = create_test_user(&server).await;
+
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 2,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let response = server.get(&format!("/clubs/{}/members", club.id)).await;
+        response.assert_status(StatusCode::OK);
+        let members: Vec<Member> = response.json();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].user_id, user.id);
+        assert_eq!(members[0].first_name, user.first_name);
+        assert_eq!(members[0].permission_level, 2);
+    }
 }
@@ -1,42 +1,54 @@
+pub mod archive;
 mod club;
 pub mod memberships;
+pub mod nominations;
+pub mod venues;
 
 pub use club::*;
+pub use shared::{CreateClubParams, UpdateClubParams};
 use sqlx::Row;
 
-use crate::error::AppResult;
+use crate::error::{error_response, AppResult};
 use axum::{
     debug_handler,
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use chrono_tz::Tz;
 
-use crate::sqlite::Database;
-
-#[derive(Deserialize, Serialize)]
-pub struct CreateClubParams {
-    name: String,
-    description: String,
-}
+use crate::{
+    pagination::{self, PageParams},
+    sqlite::Database,
+    timezone::parse_timezone,
+};
 
 #[debug_handler]
 pub async fn create_club(
     State(db): State<Database>,
-    Json(CreateClubParams { name, description }): Json<CreateClubParams>,
+    Json(CreateClubParams {
+        name,
+        description,
+        timezone,
+    }): Json<CreateClubParams>,
 ) -> AppResult<impl IntoResponse> {
+    let timezone = timezone.unwrap_or_else(|| Tz::UTC.name().to_string());
+    if let Err((status, message)) = parse_timezone(&timezone) {
+        return Ok(error_response(status, message));
+    }
+
     let now = Utc::now().naive_utc();
     let id = sqlx::query!(
         r#"
-        INSERT INTO clubs (name, description, created_at, updated_at)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO clubs (name, description, timezone, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
         RETURNING id
         "#,
         name,
         description,
+        timezone,
         now,
         now
     )
@@ -47,7 +59,7 @@ pub async fn create_club(
     let club = sqlx::query_as!(
         Club,
         r#"
-        SELECT id, name, description, created_at, updated_at
+        SELECT id, name, description, timezone, created_at, updated_at
         FROM clubs WHERE id = ?
         "#,
         id
@@ -58,18 +70,16 @@ pub async fn create_club(
     Ok((StatusCode::CREATED, Json(club)).into_response())
 }
 
-#[derive(Deserialize, Serialize)]
-pub struct UpdateClubParams {
-    name: Option<String>,
-    description: Option<String>,
-}
-
 #[debug_handler]
 pub async fn update_club(
     State(db): State<Database>,
     Path(id): Path<i64>,
     Json(params): Json<UpdateClubParams>,
-) -> AppResult<Json<Club>> {
+) -> AppResult<impl IntoResponse> {
+    if let Some(Err((status, message))) = params.timezone.as_deref().map(parse_timezone) {
+        return Ok(error_response(status, message));
+    }
+
     let now = Utc::now().naive_utc();
 
     let mut query = sqlx::QueryBuilder::new(
@@ -86,6 +96,10 @@ pub async fn update_club(
         separated.push("description = ");
         separated.push_bind_unseparated(description);
     }
+    if let Some(timezone) = params.timezone {
+        separated.push("timezone = ");
+        separated.push_bind_unseparated(timezone);
+    }
     separated.push("updated_at = ");
     separated.push_bind_unseparated(now);
     query.push(" WHERE id = ");
@@ -97,7 +111,7 @@ pub async fn update_club(
     let club = sqlx::query_as!(
         Club,
         r#"
-        SELECT id, name, description, created_at, updated_at
+        SELECT id, name, description, timezone, created_at, updated_at
         FROM clubs WHERE id = ?
         "#,
         id
@@ -105,18 +119,26 @@ pub async fn update_club(
     .fetch_one(db.as_ref())
     .await?;
 
-    Ok(Json(club))
+    Ok(Json(club).into_response())
 }
 
 #[debug_handler]
-pub async fn get_clubs(State(db): State<Database>) -> AppResult<Json<Vec<Club>>> {
+pub async fn get_clubs(
+    State(db): State<Database>,
+    Query(params): Query<PageParams>,
+    OriginalUri(uri): OriginalUri,
+) -> AppResult<Response> {
+    let (limit, offset) = pagination::bounds(&params);
     let clubs = sqlx::query(
         r#"
-        SELECT id, name, description, created_at, updated_at
+        SELECT id, name, description, timezone, created_at, updated_at
         FROM clubs
         ORDER BY id
+        LIMIT ? OFFSET ?
         "#,
     )
+    .bind(limit + 1)
+    .bind(offset)
     .fetch_all(db.as_ref())
     .await?
     .into_iter()
@@ -124,12 +146,13 @@ pub async fn get_clubs(State(db): State<Database>) -> AppResult<Json<Vec<Club>>>
         id: row.get("id"),
         name: row.get("name"),
         description: row.get("description"),
+        timezone: row.get("timezone"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     })
     .collect::<Vec<_>>();
 
-    Ok(Json(clubs))
+    Ok(pagination::page(clubs, &params, &uri))
 }
 
 #[debug_handler]
@@ -139,7 +162,7 @@ pub async fn get_club_by_id(
 ) -> AppResult<impl IntoResponse> {
     let club = sqlx::query_as!(
         Club,
-        "SELECT id, name, description, created_at, updated_at FROM clubs WHERE id = ?",
+        "SELECT id, name, description, timezone, created_at, updated_at FROM clubs WHERE id = ?",
         id
     )
     .fetch_optional(db.as_ref())
@@ -147,7 +170,7 @@ pub async fn get_club_by_id(
 
     match club {
         Some(club) => Ok(Json(club).into_response()),
-        None => Ok((StatusCode::NOT_FOUND, "Club not found").into_response()),
+        None => Ok(error_response(StatusCode::NOT_FOUND, "Club not found")),
     }
 }
 
@@ -166,7 +189,11 @@ pub async fn delete_club(
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::clubs::memberships::CreateMembershipParams;
     use crate::tests::create_test_server;
+    use crate::users::test::create_test_user;
+    use crate::AppState;
     use axum_test::TestServer;
 
     pub async fn create_club(server: &TestServer, club: CreateClubParams) -> Club {
@@ -181,11 +208,34 @@ pub mod test {
             CreateClubParams {
                 name: "Test Club".to_string(),
                 description: "Test Description".to_string(),
+                timezone: None,
             },
         )
         .await
     }
 
+    /// Makes a new user an admin of the clubs, returning their session cookie.
+    pub async fn create_club_admin(
+        server: &TestServer,
+        state: &AppState,
+        club_ids: &[i64],
+    ) -> String {
+        let user = create_test_user(server).await;
+        for &club_id in club_ids {
+            server
+                .post("/memberships")
+                .json(&CreateMembershipParams {
+                    user_id: user.id,
+                    club_id,
+                    permission_level: 2,
+                })
+                .await
+                .assert_status(StatusCode::CREATED);
+        }
+        let session = create_session(state.db.as_ref(), user.id).await.unwrap();
+        format!("{SESSION_COOKIE}={session}")
+    }
+
     #[tokio::test]
     async fn test_create_club() {
         let server = create_test_server().await;
@@ -194,12 +244,28 @@ pub mod test {
             CreateClubParams {
                 name: "Test Club".to_string(),
                 description: "Test Description".to_string(),
+                timezone: None,
             },
         )
         .await;
 
         assert_eq!(club.name, "Test Club");
         assert_eq!(club.description, "Test Description");
+        assert_eq!(club.timezone, "UTC");
+    }
+
+    #[tokio::test]
+    async fn test_create_club_unknown_timezone() {
+        let server = create_test_server().await;
+        let response = server
+            .post("/clubs")
+            .json(&CreateClubParams {
+                name: "Test Club".to_string(),
+                description: "Test Description".to_string(),
+                timezone: Some("Europe/Atlantis".to_string()),
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
@@ -227,6 +293,7 @@ pub mod test {
             .json(&UpdateClubParams {
                 name: Some("Updated Club".to_string()),
                 description: Some("Updated Description".to_string()),
+                timezone: Some("America/New_York".to_string()),
             })
             .await;
 
@@ -234,6 +301,7 @@ pub mod test {
         let updated_club: Club = response.json();
         assert_eq!(updated_club.name, "Updated Club");
         assert_eq!(updated_club.description, "Updated Description");
+        assert_eq!(updated_club.timezone, "America/New_York");
     }
 
     #[tokio::test]
@@ -0,0 +1,136 @@
+mod nomination;
+
+pub use nomination::*;
+pub use shared::NominateParams;
+
+use crate::{
+    auth::session::CurrentUser,
+    books::{Book, BookExt},
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    sqlite::Database,
+};
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+/// Nominates the book for the club. Nominating a book twice returns the
+/// existing nomination.
+#[debug_handler]
+pub async fn create_nomination(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    user: Option<CurrentUser>,
+    Json(NominateParams { book_id }): Json<NominateParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    if Club::from_id(club_id, &mut tx).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+    let Some(book) = Book::from_id(book_id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Book not found"));
+    };
+    let book_id = book.id;
+    if let Some(nomination) = Nomination::find(club_id, book_id, &mut tx).await? {
+        return Ok(Json(nomination).into_response());
+    }
+
+    let nominated_by = user.map(|CurrentUser(user)| user.id);
+    sqlx::query!(
+        "INSERT INTO nominations (club_id, book_id, nominated_by) VALUES (?, ?, ?)",
+        club_id,
+        book_id,
+        nominated_by
+    )
+    .execute(&mut *tx)
+    .await?;
+    let nomination = Nomination::find(club_id, book_id, &mut tx).await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(nomination)).into_response())
+}
+
+#[debug_handler]
+pub async fn get_club_nominations(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    if Club::from_id(club_id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+
+    let nominations = sqlx::query_as!(
+        Nomination,
+        r#"
+        SELECT nominations.id AS "id!", nominations.club_id, nominations.book_id,
+               books.title, books.author, nominations.nominated_by, nominations.created_at
+        FROM nominations
+        JOIN books ON books.id = nominations.book_id
+        WHERE nominations.club_id = ?
+        ORDER BY nominations.created_at, nominations.id
+        "#,
+        club_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(nominations).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::books::test::create_test_book;
+    use crate::clubs::test::create_test_club;
+    use crate::tests::create_test_server;
+
+    #[tokio::test]
+    async fn test_nominate_book() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+
+        let response = server
+            .post(&format!("/clubs/{}/nominations", club.id))
+            .json(&NominateParams { book_id: book.id })
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let nomination: Nomination = response.json();
+        assert_eq!(nomination.title, book.title);
+        assert_eq!(nomination.nominated_by, None);
+
+        // A second nomination of the same book is the same nomination.
+        let response = server
+            .post(&format!("/clubs/{}/nominations", club.id))
+            .json(&NominateParams { book_id: book.id })
+            .await;
+        response.assert_status(StatusCode::OK);
+        let again: Nomination = response.json();
+        assert_eq!(again.id, nomination.id);
+
+        let nominations: Vec<Nomination> = server
+            .get(&format!("/clubs/{}/nominations", club.id))
+            .await
+            .json();
+        assert_eq!(nominations.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_nominate_unknown_book() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        server
+            .post(&format!("/clubs/{}/nominations", club.id))
+            .json(&NominateParams { book_id: 999 })
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+}
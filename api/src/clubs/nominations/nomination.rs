@@ -0,0 +1,37 @@
+use sqlx::SqliteConnection;
+
+use crate::error::AppResult;
+
+pub use shared::Nomination;
+
+/// Database access for [`Nomination`].
+pub trait NominationExt: Sized {
+    async fn find(club_id: i64, book_id: i64, db: &mut SqliteConnection)
+        -> AppResult<Option<Self>>;
+}
+
+impl NominationExt for Nomination {
+    async fn find(
+        club_id: i64,
+        book_id: i64,
+        db: &mut SqliteConnection,
+    ) -> AppResult<Option<Self>> {
+        let nomination = sqlx::query_as!(
+            Nomination,
+            r#"
+            SELECT nominations.id AS "id!", nominations.club_id, nominations.book_id,
+                   books.title, books.author, nominations.nominated_by,
+                   nominations.created_at
+            FROM nominations
+            JOIN books ON books.id = nominations.book_id
+            WHERE nominations.club_id = ? AND nominations.book_id = ?
+            "#,
+            club_id,
+            book_id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(nomination)
+    }
+}
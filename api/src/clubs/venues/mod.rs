@@ -0,0 +1,284 @@
+mod venue;
+
+pub use venue::*;
+
+use crate::{
+    auth::session::CurrentUser,
+    clubs::memberships::{Membership, MembershipExt},
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    sqlite::Database,
+};
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+#[debug_handler]
+pub async fn create_venue(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    user: CurrentUser,
+    Json(params): Json<VenueParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    if Club::from_id(club_id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+    if !Membership::is_member(Some(&user), club_id, &mut conn).await? {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "Only club members can save venues",
+        ));
+    }
+    if params.name.is_none() {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            "Saved venues need a name",
+        ));
+    }
+
+    match params.insert(club_id, true, &mut conn).await? {
+        Ok(venue) => Ok((StatusCode::CREATED, Json(venue)).into_response()),
+        Err(e) => Ok(error_response(StatusCode::BAD_REQUEST, e)),
+    }
+}
+
+#[debug_handler]
+pub async fn get_club_venues(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    user: Option<CurrentUser>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+    let is_member = Membership::is_member(user.as_ref(), club_id, &mut conn).await?;
+
+    let venues = sqlx::query_as!(
+        Venue,
+        r#"
+        SELECT id AS "id!", club_id, name, address, directions, video_url, private, saved,
+               created_at
+        FROM venues
+        WHERE club_id = ? AND saved
+        ORDER BY name
+        "#,
+        club_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|venue| venue.visible_to(is_member))
+    .collect::<Vec<_>>();
+
+    Ok(Json(venues))
+}
+
+#[debug_handler]
+pub async fn get_venue_by_id(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    user: Option<CurrentUser>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    let Some(venue) = Venue::from_id(id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Venue not found"));
+    };
+    let is_member = Membership::is_member(user.as_ref(), venue.club_id, &mut conn).await?;
+
+    Ok(Json(venue.visible_to(is_member)).into_response())
+}
+
+/// Removes the venue from the club's list. Meetings that took place there keep
+/// their location.
+#[debug_handler]
+pub async fn delete_venue(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    user: CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(venue) = Venue::from_id(id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Venue not found"));
+    };
+    if !Membership::is_member(Some(&user), venue.club_id, &mut tx).await? {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "Only club members can remove venues",
+        ));
+    }
+
+    let result = sqlx::query!("UPDATE venues SET saved = 0 WHERE id = ? AND saved", id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Venue not found"));
+    }
+    sqlx::query!(
+        r#"
+        DELETE FROM venues
+        WHERE id = ? AND NOT EXISTS (SELECT 1 FROM meetings WHERE venue_id = venues.id)
+        "#,
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::clubs::test::{create_club_admin, create_test_club};
+    use crate::tests::create_test_server_with_state;
+    use crate::users::test::create_user;
+    use crate::users::CreateUserParams;
+    use axum_test::TestServer;
+
+    /// Saves a venue as the club member logged in with `cookie`.
+    pub async fn create_test_venue(
+        server: &TestServer,
+        cookie: &str,
+        club_id: i64,
+        private: bool,
+    ) -> Venue {
+        let response = server
+            .post(&format!("/clubs/{club_id}/venues"))
+            .add_header("cookie", cookie.to_string())
+            .json(&VenueParams {
+                name: Some("Anna's place".to_string()),
+                address: Some("12 Elm Street".to_string()),
+                directions: Some("Ring twice, third floor".to_string()),
+                video_url: None,
+                private,
+            })
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        response.json()
+    }
+
+    #[tokio::test]
+    async fn test_saved_venues() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let venue = create_test_venue(&server, &cookie, club.id, false).await;
+        assert!(venue.saved);
+
+        let venues: Vec<Venue> = server
+            .get(&format!("/clubs/{}/venues", club.id))
+            .await
+            .json();
+        assert_eq!(venues.len(), 1);
+        assert_eq!(venues[0].address.as_deref(), Some("12 Elm Street"));
+
+        server
+            .delete(&format!("/venues/{}", venue.id))
+            .add_header("cookie", cookie)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        let venues: Vec<Venue> = server
+            .get(&format!("/clubs/{}/venues", club.id))
+            .await
+            .json();
+        assert!(venues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_private_venue_hidden_from_non_members() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let venue = create_test_venue(&server, &cookie, club.id, true).await;
+
+        let venue: Venue = server.get(&format!("/venues/{}", venue.id)).await.json();
+        assert_eq!(venue.name.as_deref(), Some("Anna's place"));
+        assert_eq!(venue.address, None);
+        assert_eq!(venue.directions, None);
+    }
+
+    #[tokio::test]
+    async fn test_create_venue_requires_location() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+
+        let response = server
+            .post(&format!("/clubs/{}/venues", club.id))
+            .add_header("cookie", cookie.clone())
+            .json(&VenueParams {
+                name: Some("Nowhere".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post(&format!("/clubs/{}/venues", club.id))
+            .add_header("cookie", cookie)
+            .json(&VenueParams {
+                name: Some("Call".to_string()),
+                video_url: Some("javascript:alert(1)".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_venue_writes_require_a_member() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let venue = create_test_venue(&server, &cookie, club.id, false).await;
+
+        let outsider = create_user(
+            &server,
+            CreateUserParams {
+                email: "outsider@example.com".to_string(),
+                first_name: "Out".to_string(),
+                last_name: "Sider".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        let session = create_session(state.db.as_ref(), outsider.id)
+            .await
+            .unwrap();
+        let outsider = format!("{SESSION_COOKIE}={session}");
+        let params = VenueParams {
+            name: Some("Corner café".to_string()),
+            address: Some("1 Main Street".to_string()),
+            ..Default::default()
+        };
+
+        server
+            .post(&format!("/clubs/{}/venues", club.id))
+            .json(&params)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .post(&format!("/clubs/{}/venues", club.id))
+            .add_header("cookie", outsider.clone())
+            .json(&params)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        server
+            .delete(&format!("/venues/{}", venue.id))
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .delete(&format!("/venues/{}", venue.id))
+            .add_header("cookie", outsider)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,104 @@
+use sqlx::SqliteConnection;
+
+use crate::error::AppResult;
+
+pub use shared::{Venue, VenueParams};
+
+/// Database access for [`Venue`].
+pub trait VenueExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    /// Strips the address, directions and video link of a private venue unless
+    /// the requester belongs to the club.
+    fn visible_to(self, is_member: bool) -> Self;
+}
+
+impl VenueExt for Venue {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+        let venue = sqlx::query_as!(
+            Venue,
+            r#"
+            SELECT id, club_id, name, address, directions, video_url, private, saved,
+                   created_at
+            FROM venues
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(venue)
+    }
+
+    fn visible_to(self, is_member: bool) -> Self {
+        if !self.private || is_member {
+            return self;
+        }
+        Venue {
+            address: None,
+            directions: None,
+            video_url: None,
+            ..self
+        }
+    }
+}
+
+/// Database access for [`VenueParams`].
+pub trait VenueParamsExt {
+    /// Stores the venue for the club. Unsaved venues are one-off meeting
+    /// locations that don't show up in the club's list.
+    async fn insert(
+        &self,
+        club_id: i64,
+        saved: bool,
+        db: &mut SqliteConnection,
+    ) -> AppResult<Result<Venue, &'static str>>;
+}
+
+impl VenueParamsExt for VenueParams {
+    async fn insert(
+        &self,
+        club_id: i64,
+        saved: bool,
+        db: &mut SqliteConnection,
+    ) -> AppResult<Result<Venue, &'static str>> {
+        if let Err(e) = validate(self) {
+            return Ok(Err(e));
+        }
+
+        let venue = sqlx::query_as!(
+            Venue,
+            r#"
+            INSERT INTO venues (club_id, name, address, directions, video_url, private, saved)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            RETURNING id AS "id!", club_id AS "club_id!", name, address, directions,
+                      video_url, private AS "private!", saved AS "saved!",
+                      created_at AS "created_at!"
+            "#,
+            club_id,
+            self.name,
+            self.address,
+            self.directions,
+            self.video_url,
+            self.private,
+            saved
+        )
+        .fetch_one(db)
+        .await?;
+
+        Ok(Ok(venue))
+    }
+}
+
+fn validate(params: &VenueParams) -> Result<(), &'static str> {
+    if params.address.is_none() && params.video_url.is_none() {
+        return Err("A venue needs an address or a video link");
+    }
+    if let Some(url) = &params.video_url {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err("Video link must be an http(s) URL");
+        }
+    }
+    Ok(())
+}
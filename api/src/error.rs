@@ -1,7 +1,9 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
+use shared::ErrorResponse;
 
 // Make our own error that wraps `anyhow::Error`.
 #[derive(Debug)]
@@ -11,20 +13,36 @@ pub type AppResult<T> = Result<T, AppError>;
 // Tell axum how to convert `AppError` into a response.
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (
+        error_response(
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Something went wrong: {}", self.0),
         )
-            .into_response()
     }
 }
 
+/// Every error the API answers with has an [`ErrorResponse`] body, so clients
+/// can tell what went wrong without parsing text.
+pub fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    let body = ErrorResponse {
+        status: status.as_u16(),
+        message: message.into(),
+    };
+    (status, Json(body)).into_response()
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         Self(err)
     }
 }
 
+// For code shared with the admin binary, which works with `anyhow` errors.
+impl From<AppError> for anyhow::Error {
+    fn from(err: AppError) -> Self {
+        err.0
+    }
+}
+
 // Implement conversion from specific error types
 impl From<sqlx::Error> for AppError {
     fn from(err: sqlx::Error) -> Self {
@@ -0,0 +1,300 @@
+pub mod admin;
+mod auth;
+mod authors;
+pub mod backup;
+mod books;
+mod calendar;
+#[cfg(test)]
+mod client_tests;
+mod clubs;
+mod error;
+mod meetings;
+mod notifications;
+mod open_library;
+mod pagination;
+mod retry;
+pub mod settings;
+pub mod sqlite;
+mod telegram;
+mod timezone;
+mod users;
+mod webhooks;
+
+use config::{Config, Environment};
+use open_library::OpenLibraryClient;
+use settings::Settings;
+
+use anyhow::Result;
+
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
+    Router,
+};
+
+#[derive(Clone)]
+struct AppState {
+    db: sqlite::Database,
+    open_lib_client: OpenLibraryClient,
+    google_client: auth::google::Client,
+    notifier: notifications::Notifier,
+    webhooks: webhooks::Dispatcher,
+    backups: backup::Backups,
+    telegram: telegram::Settings,
+}
+
+async fn create_state(config: Config) -> Result<AppState> {
+    let settings = config.try_deserialize::<Settings>()?;
+    let db = sqlite::Database::new(&settings.sqlite).await?;
+
+    let google_client =
+        auth::google::Client::new("http://127.0.0.1:3000".into(), settings.google_auth).await?;
+    let open_lib_client = OpenLibraryClient::new(reqwest::Client::new(), settings.open_library);
+    let notifier = notifications::Notifier::new(&settings.notifications)?;
+    let webhooks = webhooks::Dispatcher::new(&settings.webhooks)?;
+    let backups = backup::Backups::new(&settings.backup);
+    let telegram = settings.telegram;
+
+    Ok(AppState {
+        db,
+        open_lib_client,
+        google_client,
+        notifier,
+        webhooks,
+        backups,
+        telegram,
+    })
+}
+
+/// The server's configuration: `config/default.json`, overridden by the
+/// build's mode and then by environment variables such as `SQLITE.URL`.
+pub fn load_config() -> Config {
+    #[cfg(debug_assertions)]
+    let mode_config = option_env!("CONFIG_DEBUG");
+
+    #[cfg(not(debug_assertions))]
+    let mode_config = option_env!("CONFIG_RELEASE");
+
+    let mut config_builder = config::Config::builder().add_source(config::File::from_str(
+        env!("CONFIG_DEFAULT"),
+        config::FileFormat::Json,
+    ));
+
+    if let Some(mode_config) = mode_config {
+        config_builder = config_builder.add_source(config::File::from_str(
+            mode_config,
+            config::FileFormat::Json,
+        ));
+    }
+
+    config_builder = config_builder.add_source(Environment::default().separator("."));
+
+    config_builder.build().expect("Failed to build config")
+}
+
+pub async fn create_app(config: Config) -> Result<Router> {
+    let app_state = create_state(config).await?;
+    tokio::spawn(app_state.notifier.clone().run(app_state.db.clone()));
+    tokio::spawn(app_state.webhooks.clone().run(app_state.db.clone()));
+    tokio::spawn(app_state.backups.clone().run(app_state.db.clone()));
+
+    Ok(create_router(app_state))
+}
+
+fn create_router(app_state: AppState) -> Router {
+    Router::new()
+        .route("/hi", get(|| async { "Hello, World!" }))
+        .route("/open-library/search", get(open_library::search_book))
+        .route("/open-library/results", get(open_library::search_books))
+        .route("/open-library/works/{id}", get(open_library::get_work))
+        .route("/books/create", post(books::create_book))
+        .route("/books/list", get(books::get_books))
+        .route("/books/get/{id}", get(books::get_book_by_id))
+        .route("/books/search", get(books::find_books))
+        .route("/books/{id}/authors", get(authors::get_book_credits))
+        .route("/books/{id}/authors", put(authors::set_book_credits))
+        .route("/authors", post(authors::create_author))
+        .route("/authors/list", get(authors::get_authors))
+        .route("/authors/{id}", get(authors::get_author_by_id))
+        .route("/authors/{id}/books", get(authors::get_author_books))
+        .route("/authors/{id}/stats", get(authors::get_author_stats))
+        .route("/users/create", post(users::create_user))
+        .route("/users/list", get(users::get_users))
+        .route("/users/{id}", get(users::get_user_by_id))
+        .route("/users/{id}", put(users::update_user))
+        .route("/users/{id}", delete(users::delete_user))
+        .route("/users/search", get(users::find_users))
+        .route(
+            "/users/{id}/goodreads-import",
+            post(books::goodreads::import_goodreads)
+                // Exports cover years of reading.
+                .layer(DefaultBodyLimit::max(16 * 1024 * 1024)),
+        )
+        .route("/clubs", post(clubs::create_club))
+        .route("/clubs/list", get(clubs::get_clubs))
+        .route("/clubs/{id}", get(clubs::get_club_by_id))
+        .route("/clubs/{id}", put(clubs::update_club))
+        .route("/clubs/{id}", delete(clubs::delete_club))
+        .route("/clubs/{id}/archive", get(clubs::archive::export_archive))
+        .route(
+            "/clubs/{id}/archive.md",
+            get(clubs::archive::export_history),
+        )
+        .route("/clubs/import", post(clubs::archive::import_archive))
+        .route("/memberships", post(clubs::memberships::create_membership))
+        .route("/memberships", get(clubs::memberships::get_memberships))
+        .route(
+            "/memberships/{id}",
+            get(clubs::memberships::get_membership_by_id),
+        )
+        .route(
+            "/memberships/{id}",
+            delete(clubs::memberships::delete_membership),
+        )
+        .route("/clubs/{id}/venues", post(clubs::venues::create_venue))
+        .route("/clubs/{id}/venues", get(clubs::venues::get_club_venues))
+        .route(
+            "/clubs/{id}/nominations",
+            post(clubs::nominations::create_nomination),
+        )
+        .route(
+            "/clubs/{id}/nominations",
+            get(clubs::nominations::get_club_nominations),
+        )
+        .route("/venues/{id}", get(clubs::venues::get_venue_by_id))
+        .route("/venues/{id}", delete(clubs::venues::delete_venue))
+        .route("/clubs/{id}/webhooks", post(webhooks::create_webhook))
+        .route("/clubs/{id}/webhooks", get(webhooks::get_club_webhooks))
+        .route("/webhooks/{id}", delete(webhooks::delete_webhook))
+        .route(
+            "/webhooks/{id}/deliveries",
+            get(webhooks::get_webhook_deliveries),
+        )
+        .route("/meetings", post(meetings::create_meeting))
+        .route("/meetings/{id}", get(meetings::get_meeting_by_id))
+        .route("/meetings/{id}", put(meetings::update_meeting))
+        .route("/meetings/{id}", delete(meetings::delete_meeting))
+        .route("/clubs/{id}/meetings", get(meetings::get_club_meetings))
+        .route("/clubs/{id}/meetings/next", get(meetings::get_next_meeting))
+        .route(
+            "/clubs/{id}/members",
+            get(clubs::memberships::get_club_members),
+        )
+        .route("/clubs/{id}/series", post(meetings::series::create_series))
+        .route("/clubs/{id}/series", get(meetings::series::get_club_series))
+        .route(
+            "/meeting-series/{id}/expand",
+            post(meetings::series::expand_series),
+        )
+        .route(
+            "/meeting-series/{id}",
+            delete(meetings::series::delete_series),
+        )
+        .route(
+            "/users/{id}/notifications",
+            get(notifications::get_user_notifications),
+        )
+        .route(
+            "/users/{id}/notification-preferences",
+            get(notifications::get_notification_preferences),
+        )
+        .route(
+            "/users/{id}/notification-preferences",
+            put(notifications::update_notification_preferences),
+        )
+        .route(
+            "/users/{id}/calendar-token",
+            post(calendar::create_calendar_token),
+        )
+        .route("/calendar/{token}/feed.ics", get(calendar::get_user_feed))
+        .route(
+            "/calendar/{token}/clubs/{id}/feed.ics",
+            get(calendar::get_club_feed),
+        )
+        .route(
+            "/clubs/{id}/telegram-link-code",
+            post(telegram::create_link_code),
+        )
+        .route("/telegram/chats", get(telegram::get_chats))
+        .route("/telegram/chats/{chat_id}", get(telegram::get_chat))
+        .route("/telegram/chats/{chat_id}", put(telegram::link_chat))
+        .route("/telegram/chats/{chat_id}", delete(telegram::unlink_chat))
+        .route("/telegram/chats/{chat_id}/votes", post(telegram::open_vote))
+        .route(
+            "/telegram/chats/{chat_id}/votes/closed",
+            post(telegram::close_vote),
+        )
+        .nest("/auth", auth::router())
+        .with_state(app_state)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use axum_test::TestServer;
+    use tracing_test::traced_test;
+
+    /// The bot key test servers accept from the Telegram bot.
+    pub const TEST_BOT_KEY: &str = "test-bot-key";
+
+    pub async fn create_test_server() -> TestServer {
+        create_test_server_with_state().await.0
+    }
+
+    /// Also hands back the state, for tests that need to set up data the API
+    /// can't create, such as sessions.
+    pub async fn create_test_server_with_state() -> (TestServer, AppState) {
+        let state = create_test_state().await;
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        (server, state)
+    }
+
+    /// Serves the API on a local port, for clients making real HTTP requests.
+    pub async fn create_http_test_server() -> (TestServer, AppState) {
+        let state = create_test_state().await;
+        let server = TestServer::builder()
+            .http_transport()
+            .build(create_router(state.clone()))
+            .unwrap();
+        (server, state)
+    }
+
+    pub async fn create_test_state() -> AppState {
+        let default_config = env!("CONFIG_DEFAULT");
+        let mode_config = option_env!("CONFIG_TEST");
+
+        let mut config_builder = Config::builder().add_source(config::File::from_str(
+            default_config,
+            config::FileFormat::Json,
+        ));
+
+        if let Some(mode_config) = mode_config {
+            config_builder = config_builder.add_source(config::File::from_str(
+                mode_config,
+                config::FileFormat::Json,
+            ));
+        }
+        config_builder = config_builder
+            .set_override("sqlite.url", "sqlite::memory:")
+            .expect("Failed to set override")
+            .set_override("notifications.transport", "stub")
+            .expect("Failed to set override")
+            .set_override("telegram.bot_key", TEST_BOT_KEY)
+            .expect("Failed to set override")
+            .set_override("webhooks.allow_private_targets", true)
+            .expect("Failed to set override");
+
+        let config = config_builder.build().expect("Failed to build config");
+        create_state(config).await.unwrap()
+    }
+
+    // Test the hello world endpoint
+    #[tokio::test]
+    #[traced_test]
+    async fn test_hello_endpoint() {
+        let server = create_test_server().await;
+        let response = server.get("/hi").await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.text(), "Hello, World!");
+    }
+}
@@ -1,112 +1,19 @@
-mod auth;
-mod books;
-mod clubs;
-mod error;
-mod open_library;
-mod settings;
-mod sqlite;
-mod users;
-
-use config::{Config, Environment};
-use error::AppResult;
-use open_library::OpenLibraryClient;
-use settings::Settings;
-
 use anyhow::Result;
-use tokio::{net::TcpListener, time::Instant};
-
-use axum::{
-    routing::{delete, get, post, put},
-    serve, Router,
-};
-use tokio::signal;
+use api::{create_app, load_config};
+use axum::serve;
+use tokio::{net::TcpListener, signal, time::Instant};
 use tracing::{info, warn};
-use tracing_subscriber::EnvFilter;
-
-#[derive(Clone)]
-struct AppState {
-    db: sqlite::Database,
-    open_lib_client: OpenLibraryClient,
-    google_client: auth::google::Client,
-}
-
-async fn create_app(config: Config) -> Result<Router> {
-    let settings = config.try_deserialize::<Settings>()?;
-    let db = sqlite::Database::new(&settings.sqlite).await?;
-
-    let google_client =
-        auth::google::Client::new("http://127.0.0.1:3000".into(), settings.google_auth).await?;
-    let open_lib_client = OpenLibraryClient::new(reqwest::Client::new(), settings.open_library);
-    let app_state = AppState {
-        db,
-        open_lib_client,
-        google_client,
-    };
-
-    let app = Router::new()
-        .route("/hi", get(|| async { "Hello, World!" }))
-        .route("/open-library/search", get(open_library::search_book))
-        .route("/books/create", post(books::create_book))
-        .route("/books/list", get(books::get_books))
-        .route("/books/get/{id}", get(books::get_book_by_id))
-        .route("/books/search", get(books::find_books))
-        .route("/users/create", post(users::create_user))
-        .route("/users/list", get(users::get_users))
-        .route("/users/{id}", get(users::get_user_by_id))
-        .route("/users/{id}", put(users::update_user))
-        .route("/users/{id}", delete(users::delete_user))
-        .route("/users/search", get(users::find_users))
-        .route("/clubs", post(clubs::create_club))
-        .route("/clubs/list", get(clubs::get_clubs))
-        .route("/clubs/{id}", get(clubs::get_club_by_id))
-        .route("/clubs/{id}", put(clubs::update_club))
-        .route("/clubs/{id}", delete(clubs::delete_club))
-        .route("/memberships", post(clubs::memberships::create_membership))
-        .route("/memberships", get(clubs::memberships::get_memberships))
-        .route(
-            "/memberships/{id}",
-            get(clubs::memberships::get_membership_by_id),
-        )
-        .route(
-            "/memberships/{id}",
-            delete(clubs::memberships::delete_membership),
-        )
-        .nest("/auth", auth::router())
-        .with_state(app_state);
-
-    Ok(app)
-}
 
 #[tokio::main]
-async fn main() -> AppResult<()> {
+async fn main() -> Result<()> {
     let start = Instant::now();
     tracing_subscriber::fmt()
-        // .with_env_filter(EnvFilter::from_default_env())
+        // .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
     warn!("config default: {}", env!("CONFIG_DEFAULT"));
 
-    #[cfg(debug_assertions)]
-    let mode_config = option_env!("CONFIG_DEBUG");
-
-    #[cfg(not(debug_assertions))]
-    let mode_config = option_env!("CONFIG_RELEASE");
-
-    let mut config_builder = config::Config::builder().add_source(config::File::from_str(
-        env!("CONFIG_DEFAULT"),
-        config::FileFormat::Json,
-    ));
-
-    if let Some(mode_config) = mode_config {
-        config_builder = config_builder.add_source(config::File::from_str(
-            mode_config,
-            config::FileFormat::Json,
-        ));
-    }
-
-    config_builder = config_builder.add_source(Environment::default().separator("."));
-
-    let config = config_builder.build().expect("Failed to build config");
+    let config = load_config();
 
     let app = create_app(config).await?;
 
@@ -144,45 +51,3 @@ async fn main() -> AppResult<()> {
 
     Ok(())
 }
-
-#[cfg(test)]
-pub(crate) mod tests {
-    use super::*;
-    use axum_test::TestServer;
-    use tracing_test::traced_test;
-
-    pub async fn create_test_server() -> TestServer {
-        let default_config = env!("CONFIG_DEFAULT");
-        let mode_config = option_env!("CONFIG_TEST");
-
-        let mut config_builder = Config::builder().add_source(config::File::from_str(
-            default_config,
-            config::FileFormat::Json,
-        ));
-
-        if let Some(mode_config) = mode_config {
-            config_builder = config_builder.add_source(config::File::from_str(
-                mode_config,
-                config::FileFormat::Json,
-            ));
-        }
-        config_builder = config_builder
-            .set_override("sqlite.url", "sqlite::memory:")
-            .expect("Failed to set override");
-
-        let config = config_builder.build().expect("Failed to build config");
-        let app = create_app(config).await.unwrap();
-
-        TestServer::new(app).unwrap()
-    }
-
-    // Test the hello world endpoint
-    #[tokio::test]
-    #[traced_test]
-    async fn test_hello_endpoint() {
-        let server = create_test_server().await;
-        let response = server.get("/hi").await;
-        assert_eq!(response.status_code(), 200);
-        assert_eq!(response.text(), "Hello, World!");
-    }
-}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, FixedOffset};
+use chrono_tz::Tz;
+use sqlx::SqliteConnection;
+
+use crate::{
+    clubs::venues::{Venue, VenueExt},
+    error::AppResult,
+};
+
+pub use shared::{Meeting, MeetingDetails};
+
+/// Database access for [`Meeting`].
+pub trait MeetingExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    fn in_timezone(self, tz: &Tz) -> Self;
+}
+
+impl MeetingExt for Meeting {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+        let meeting = sqlx::query_as!(
+            Meeting,
+            r#"
+            SELECT id, date AS "date: DateTime<FixedOffset>", book_id, club_id, series_id,
+                   occurrence, venue_id, reading_target
+            FROM meetings
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(meeting)
+    }
+
+    fn in_timezone(self, tz: &Tz) -> Self {
+        Meeting {
+            date: self.date.with_timezone(tz).fixed_offset(),
+            ..self
+        }
+    }
+}
+
+/// Database access for [`MeetingDetails`].
+pub trait MeetingDetailsExt: Sized {
+    /// Looks up the meeting's venue, hiding private details from non-members.
+    async fn load(meeting: Meeting, is_member: bool, db: &mut SqliteConnection) -> AppResult<Self>;
+}
+
+impl MeetingDetailsExt for MeetingDetails {
+    async fn load(meeting: Meeting, is_member: bool, db: &mut SqliteConnection) -> AppResult<Self> {
+        let venue = match meeting.venue_id {
+            Some(venue_id) => Venue::from_id(venue_id, db)
+                .await?
+                .map(|venue| venue.visible_to(is_member)),
+            None => None,
+        };
+
+        Ok(MeetingDetails { meeting, venue })
+    }
+}
@@ -0,0 +1,754 @@
+mod meeting;
+pub mod series;
+
+pub use meeting::*;
+pub use shared::{CreateMeetingParams, UpdateMeetingParams};
+
+use crate::{
+    auth::session::CurrentUser,
+    books::{Book, BookExt},
+    clubs::{
+        memberships::{Membership, MembershipExt},
+        venues::{Venue, VenueExt, VenueParams, VenueParamsExt},
+        Club, ClubExt,
+    },
+    error::{error_response, AppResult},
+    notifications::{self, NotificationEvent},
+    sqlite::Database,
+    timezone::RequesterTimezone,
+    webhooks::{self, WebhookEvent},
+};
+use axum::{
+    debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
+use serde_json::json;
+use sqlx::SqliteConnection;
+
+/// Resolves the venue a meeting should take place at, creating an unsaved one
+/// for one-off locations.
+async fn meeting_venue(
+    club_id: i64,
+    venue_id: Option<i64>,
+    location: Option<VenueParams>,
+    db: &mut SqliteConnection,
+) -> AppResult<Result<Option<Venue>, Response>> {
+    match (venue_id, location) {
+        (Some(_), Some(_)) => Ok(Err(error_response(
+            StatusCode::BAD_REQUEST,
+            "Give either a venue or a location, not both",
+        ))),
+        (Some(venue_id), None) => match Venue::from_id(venue_id, db).await? {
+            Some(venue) if venue.club_id == club_id => Ok(Ok(Some(venue))),
+            Some(_) => Ok(Err(error_response(
+                StatusCode::BAD_REQUEST,
+                "Venue belongs to another club",
+            ))),
+            None => Ok(Err(error_response(
+                StatusCode::NOT_FOUND,
+                "Venue not found",
+            ))),
+        },
+        (None, Some(location)) => match location.insert(club_id, false, db).await? {
+            Ok(venue) => Ok(Ok(Some(venue))),
+            Err(e) => Ok(Err(error_response(StatusCode::BAD_REQUEST, e))),
+        },
+        (None, None) => Ok(Ok(None)),
+    }
+}
+
+#[debug_handler]
+pub async fn create_meeting(
+    State(db): State<Database>,
+    user: Option<CurrentUser>,
+    requester_tz: RequesterTimezone,
+    Json(CreateMeetingParams {
+        club_id,
+        book_id,
+        date,
+        venue_id,
+        location,
+        reading_target,
+    }): Json<CreateMeetingParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(club) = Club::from_id(club_id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    };
+    let book = match book_id {
+        Some(book_id) => match Book::from_id(book_id, &mut tx).await? {
+            Some(book) => Some(book),
+            None => return Ok(error_response(StatusCode::NOT_FOUND, "Book not found")),
+        },
+        None => None,
+    };
+    // The id may have been an alias of a merged book.
+    let book_id = book.as_ref().map(|book| book.id);
+    let venue = match meeting_venue(club_id, venue_id, location, &mut tx).await? {
+        Ok(venue) => venue,
+        Err(response) => return Ok(response),
+    };
+    let venue_id = venue.as_ref().map(|venue| venue.id);
+
+    let utc_date = date.naive_utc();
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO meetings (date, book_id, club_id, venue_id, reading_target)
+        VALUES (?, ?, ?, ?, ?)
+        RETURNING id
+        "#,
+        utc_date,
+        book_id,
+        club_id,
+        venue_id,
+        reading_target
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .id;
+
+    let when = date
+        .with_timezone(&club.tz())
+        .format("%A %-d %B at %H:%M %Z");
+    let body = match &book {
+        Some(book) => format!(
+            "{} meets on {when} to talk about {} by {}.",
+            club.name, book.title, book.author
+        ),
+        None => format!("{} meets on {when}.", club.name),
+    };
+    notifications::enqueue_for_club(
+        &mut tx,
+        club_id,
+        NotificationEvent::MeetingScheduled,
+        &format!("New {} meeting", club.name),
+        &body,
+    )
+    .await?;
+
+    let meeting = Meeting {
+        id,
+        date: date.fixed_offset(),
+        book_id,
+        club_id,
+        series_id: None,
+        occurrence: None,
+        venue_id,
+        reading_target,
+    };
+    webhooks::enqueue(&mut tx, club_id, WebhookEvent::MeetingCreated, &meeting).await?;
+    if let Some(book) = &book {
+        webhooks::enqueue(
+            &mut tx,
+            club_id,
+            WebhookEvent::BookSelected,
+            json!({ "meeting_id": id, "book": book }),
+        )
+        .await?;
+    }
+
+    let is_member = Membership::is_member(user.as_ref(), club_id, &mut tx).await?;
+    tx.commit().await?;
+
+    let details = MeetingDetails {
+        meeting: meeting.in_timezone(&requester_tz.or(club.tz())),
+        venue: venue.map(|venue| venue.visible_to(is_member)),
+    };
+
+    Ok((StatusCode::CREATED, Json(details)).into_response())
+}
+
+#[debug_handler]
+pub async fn get_meeting_by_id(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    user: Option<CurrentUser>,
+    requester_tz: RequesterTimezone,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    let Some(meeting) = Meeting::from_id(id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Meeting not found"));
+    };
+    let tz = match requester_tz.0 {
+        Some(tz) => tz,
+        None => Club::from_id(meeting.club_id, &mut conn)
+            .await?
+            .map(|club| club.tz())
+            .unwrap_or(Tz::UTC),
+    };
+    let is_member = Membership::is_member(user.as_ref(), meeting.club_id, &mut conn).await?;
+    let details = MeetingDetails::load(meeting.in_timezone(&tz), is_member, &mut conn).await?;
+
+    Ok(Json(details).into_response())
+}
+
+#[debug_handler]
+pub async fn get_club_meetings(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    user: Option<CurrentUser>,
+    requester_tz: RequesterTimezone,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    let Some(club) = Club::from_id(club_id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    };
+    let tz = requester_tz.or(club.tz());
+    let is_member = Membership::is_member(user.as_ref(), club_id, &mut conn).await?;
+
+    let meetings = sqlx::query_as!(
+        Meeting,
+        r#"
+        SELECT id AS "id!", date AS "date: DateTime<FixedOffset>", book_id, club_id,
+               series_id, occurrence, venue_id, reading_target
+        FROM meetings
+        WHERE club_id = ?
+        ORDER BY date
+        "#,
+        club_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut details = Vec::with_capacity(meetings.len());
+    for meeting in meetings {
+        details.push(MeetingDetails::load(meeting.in_timezone(&tz), is_member, &mut conn).await?);
+    }
+
+    Ok(Json(details).into_response())
+}
+
+/// The club's next upcoming meeting.
+#[debug_handler]
+pub async fn get_next_meeting(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    user: Option<CurrentUser>,
+    requester_tz: RequesterTimezone,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    let Some(club) = Club::from_id(club_id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    };
+
+    let now = Utc::now().naive_utc();
+    let meeting = sqlx::query_as!(
+        Meeting,
+        r#"
+        SELECT id AS "id!", date AS "date: DateTime<FixedOffset>", book_id, club_id,
+               series_id, occurrence, venue_id, reading_target
+        FROM meetings
+        WHERE club_id = ? AND date >= ?
+        ORDER BY date
+        LIMIT 1
+        "#,
+        club_id,
+        now
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(meeting) = meeting else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "No upcoming meeting"));
+    };
+
+    let is_member = Membership::is_member(user.as_ref(), club_id, &mut conn).await?;
+    let meeting = meeting.in_timezone(&requester_tz.or(club.tz()));
+    let details = MeetingDetails::load(meeting, is_member, &mut conn).await?;
+
+    Ok(Json(details).into_response())
+}
+
+/// Moving a meeting that belongs to a series keeps its original occurrence, so
+/// the series treats it as an exception rather than generating it again.
+#[debug_handler]
+pub async fn update_meeting(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    user: Option<CurrentUser>,
+    requester_tz: RequesterTimezone,
+    Json(params): Json<UpdateMeetingParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().begin().await?;
+
+    let Some(meeting) = Meeting::from_id(id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Meeting not found"));
+    };
+    let book = match params.book_id {
+        Some(book_id) => match Book::from_id(book_id, &mut conn).await? {
+            Some(book) => Some(book),
+            None => return Ok(error_response(StatusCode::NOT_FOUND, "Book not found")),
+        },
+        None => None,
+    };
+    let venue =
+        match meeting_venue(meeting.club_id, params.venue_id, params.location, &mut conn).await? {
+            Ok(venue) => venue,
+            Err(response) => return Ok(response),
+        };
+
+    if params.date.is_some()
+        || params.book_id.is_some()
+        || venue.is_some()
+        || params.reading_target.is_some()
+    {
+        let mut query = sqlx::QueryBuilder::new(
+            r#"
+            UPDATE meetings SET 
+            "#,
+        );
+        let mut separated = query.separated(", ");
+        if let Some(date) = params.date {
+            separated.push("date = ");
+            separated.push_bind_unseparated(date.naive_utc());
+        }
+        if let Some(book) = &book {
+            separated.push("book_id = ");
+            separated.push_bind_unseparated(book.id);
+        }
+        if let Some(venue) = &venue {
+            separated.push("venue_id = ");
+            separated.push_bind_unseparated(venue.id);
+        }
+        if let Some(reading_target) = params.reading_target {
+            separated.push("reading_target = ");
+            separated.push_bind_unseparated(reading_target);
+        }
+        query.push(" WHERE id = ");
+        query.push_bind(id);
+        tracing::debug!("Query: {}", query.sql());
+        query.build().execute(&mut *conn).await?;
+    }
+
+    let club = Club::from_id(meeting.club_id, &mut conn).await?;
+    let tz = requester_tz.or(club.map(|club| club.tz()).unwrap_or(Tz::UTC));
+
+    let meeting = sqlx::query_as!(
+        Meeting,
+        r#"
+        SELECT id, date AS "date: DateTime<FixedOffset>", book_id, club_id, series_id,
+               occurrence, venue_id, reading_target
+        FROM meetings WHERE id = ?
+        "#,
+        id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    webhooks::enqueue(
+        &mut conn,
+        meeting.club_id,
+        WebhookEvent::MeetingUpdated,
+        &meeting,
+    )
+    .await?;
+    if let Some(book) = book {
+        webhooks::enqueue(
+            &mut conn,
+            meeting.club_id,
+            WebhookEvent::BookSelected,
+            json!({ "meeting_id": id, "book": book }),
+        )
+        .await?;
+    }
+
+    let is_member = Membership::is_member(user.as_ref(), meeting.club_id, &mut conn).await?;
+    let details = MeetingDetails::load(meeting.in_timezone(&tz), is_member, &mut conn).await?;
+    conn.commit().await?;
+
+    Ok(Json(details).into_response())
+}
+
+/// Deleting a meeting generated by a series records an exception so the
+/// occurrence stays cancelled.
+#[debug_handler]
+pub async fn delete_meeting(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(meeting) = Meeting::from_id(id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Meeting not found"));
+    };
+
+    if let (Some(series_id), Some(occurrence)) = (meeting.series_id, meeting.occurrence) {
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO meeting_series_exceptions (series_id, occurrence)
+            VALUES (?, ?)
+            "#,
+            series_id,
+            occurrence
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    // Attendance doesn't cascade, so it would keep the meeting from being
+    // deleted.
+    sqlx::query!("DELETE FROM attendance WHERE meeting_id = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM meetings WHERE id = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    webhooks::enqueue(
+        &mut tx,
+        meeting.club_id,
+        WebhookEvent::MeetingDeleted,
+        &meeting,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::books::test::create_test_book;
+    use crate::clubs::memberships::CreateMembershipParams;
+    use crate::clubs::test::{create_club_admin, create_test_club};
+    use crate::clubs::venues::test::create_test_venue;
+    use crate::tests::{create_test_server, create_test_server_with_state};
+    use crate::users::{test::create_user, CreateUserParams};
+    use axum_test::TestServer;
+    use chrono::TimeZone;
+
+    pub async fn create_meeting(server: &TestServer, meeting: CreateMeetingParams) -> Meeting {
+        let response = server.post("/meetings").json(&meeting).await;
+        response.assert_status(StatusCode::CREATED);
+        response.json()
+    }
+
+    pub async fn create_test_meeting(server: &TestServer, club_id: i64, book_id: i64) -> Meeting {
+        create_meeting(
+            server,
+            CreateMeetingParams {
+                club_id,
+                book_id: Some(book_id),
+                date: Utc.with_ymd_and_hms(2030, 1, 9, 19, 0, 0).unwrap(),
+                venue_id: None,
+                location: None,
+                reading_target: None,
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_create_meeting() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+        assert_eq!(meeting.club_id, club.id);
+        assert_eq!(meeting.book_id, Some(book.id));
+
+        let response = server.get(&format!("/meetings/{}", meeting.id)).await;
+        response.assert_status(StatusCode::OK);
+        let fetched: Meeting = response.json();
+        assert_eq!(fetched.date, meeting.date);
+
+        let response = server.get(&format!("/clubs/{}/meetings", club.id)).await;
+        response.assert_status(StatusCode::OK);
+        let meetings: Vec<Meeting> = response.json();
+        assert_eq!(meetings.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_meeting_unknown_book() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        let response = server
+            .post("/meetings")
+            .json(&CreateMeetingParams {
+                club_id: club.id,
+                book_id: Some(42),
+                date: Utc.with_ymd_and_hms(2030, 1, 9, 19, 0, 0).unwrap(),
+                venue_id: None,
+                location: None,
+                reading_target: None,
+            })
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_meeting() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+
+        let response = server.delete(&format!("/meetings/{}", meeting.id)).await;
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        let response = server.get(&format!("/meetings/{}", meeting.id)).await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_meeting_with_attendance() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+        let user = crate::users::test::create_test_user(&server).await;
+        sqlx::query!(
+            "INSERT INTO attendance (user_id, meeting_id) VALUES (?, ?)",
+            user.id,
+            meeting.id
+        )
+        .execute(state.db.as_ref())
+        .await
+        .unwrap();
+
+        let response = server.delete(&format!("/meetings/{}", meeting.id)).await;
+        response.assert_status(StatusCode::NO_CONTENT);
+
+        let attendance = sqlx::query_scalar!("SELECT COUNT(*) FROM attendance")
+            .fetch_one(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(attendance, 0);
+    }
+
+    #[tokio::test]
+    async fn test_meeting_rendered_in_requester_timezone() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+        assert_eq!(meeting.date.to_rfc3339(), "2030-01-09T19:00:00+00:00");
+
+        let response = server
+            .get(&format!("/meetings/{}", meeting.id))
+            .add_query_param("tz", "America/New_York")
+            .await;
+        response.assert_status(StatusCode::OK);
+        let fetched: Meeting = response.json();
+        assert_eq!(fetched.date.to_rfc3339(), "2030-01-09T14:00:00-05:00");
+        assert_eq!(fetched.date, meeting.date);
+
+        // Without a `tz` parameter the logged in user's timezone is used.
+        let user = create_user(
+            &server,
+            CreateUserParams {
+                email: "tokyo@example.com".to_string(),
+                first_name: "Test".to_string(),
+                last_name: "User".to_string(),
+                timezone: Some("Asia/Tokyo".to_string()),
+            },
+        )
+        .await;
+        let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let response = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={token}"))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let meetings: Vec<Meeting> = response.json();
+        assert_eq!(meetings[0].date.to_rfc3339(), "2030-01-10T04:00:00+09:00");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_requester_timezone() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        let response = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .add_query_param("tz", "Mars/Olympus_Mons")
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_private_venue_visible_to_members_only() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let user = create_user(
+            &server,
+            CreateUserParams {
+                email: "member@example.com".to_string(),
+                first_name: "Test".to_string(),
+                last_name: "Member".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+        let token = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let cookie = format!("{SESSION_COOKIE}={token}");
+        let venue = create_test_venue(&server, &cookie, club.id, true).await;
+
+        let meeting: MeetingDetails = server
+            .post("/meetings")
+            .json(&CreateMeetingParams {
+                club_id: club.id,
+                book_id: None,
+                date: Utc.with_ymd_and_hms(2030, 1, 9, 19, 0, 0).unwrap(),
+                venue_id: Some(venue.id),
+                location: None,
+                reading_target: None,
+            })
+            .await
+            .json();
+        assert_eq!(meeting.meeting.venue_id, Some(venue.id));
+
+        let anonymous: MeetingDetails = server
+            .get(&format!("/meetings/{}", meeting.meeting.id))
+            .await
+            .json();
+        let anonymous_venue = anonymous.venue.unwrap();
+        assert_eq!(anonymous_venue.name.as_deref(), Some("Anna's place"));
+        assert_eq!(anonymous_venue.address, None);
+
+        let meetings: Vec<MeetingDetails> = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .add_header("cookie", cookie)
+            .await
+            .json();
+        let member_venue = meetings[0].venue.as_ref().unwrap();
+        assert_eq!(member_venue.address.as_deref(), Some("12 Elm Street"));
+        assert_eq!(
+            member_venue.directions.as_deref(),
+            Some("Ring twice, third floor")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_reading_target() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+        assert_eq!(meeting.reading_target, None);
+
+        let response = server
+            .put(&format!("/meetings/{}", meeting.id))
+            .json(&UpdateMeetingParams {
+                reading_target: Some("Chapters 1-10".to_string()),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status(StatusCode::OK);
+        let updated: MeetingDetails = response.json();
+        assert_eq!(
+            updated.meeting.reading_target.as_deref(),
+            Some("Chapters 1-10")
+        );
+        assert_eq!(updated.meeting.book_id, Some(book.id));
+    }
+
+    #[tokio::test]
+    async fn test_meeting_with_one_off_location() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+        let book = create_test_book(&server).await;
+        let meeting = create_test_meeting(&server, club.id, book.id).await;
+
+        let response = server
+            .put(&format!("/meetings/{}", meeting.id))
+            .json(&UpdateMeetingParams {
+                location: Some(VenueParams {
+                    video_url: Some("https://meet.example.com/book-club".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await;
+        response.assert_status(StatusCode::OK);
+        let updated: MeetingDetails = response.json();
+        let venue = updated.venue.unwrap();
+        assert_eq!(
+            venue.video_url.as_deref(),
+            Some("https://meet.example.com/book-club")
+        );
+        assert!(!venue.saved);
+
+        // One-off locations don't clutter the club's saved venues.
+        let venues: Vec<Venue> = server
+            .get(&format!("/clubs/{}/venues", club.id))
+            .await
+            .json();
+        assert!(venues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_meeting_venue_from_other_club() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let other_club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[other_club.id]).await;
+        let venue = create_test_venue(&server, &cookie, other_club.id, false).await;
+
+        let response = server
+            .post("/meetings")
+            .json(&CreateMeetingParams {
+                club_id: club.id,
+                book_id: None,
+                date: Utc.with_ymd_and_hms(2030, 1, 9, 19, 0, 0).unwrap(),
+                venue_id: Some(venue.id),
+                location: None,
+                reading_target: None,
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_get_next_meeting() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        server
+            .get(&format!("/clubs/{}/meetings/next", club.id))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        for days in [-7, 14, 7] {
+            create_meeting(
+                &server,
+                CreateMeetingParams {
+                    club_id: club.id,
+                    book_id: None,
+                    date: Utc::now() + chrono::Duration::days(days),
+                    venue_id: None,
+                    location: None,
+                    reading_target: None,
+                },
+            )
+            .await;
+        }
+
+        let response = server
+            .get(&format!("/clubs/{}/meetings/next", club.id))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let next: Meeting = response.json();
+        let days_ahead = (next.date.with_timezone(&Utc) - Utc::now()).num_days();
+        assert_eq!(days_ahead, 6);
+    }
+}
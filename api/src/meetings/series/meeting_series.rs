@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use sqlx::SqliteConnection;
+
+use super::RRule;
+use crate::{
+    clubs::{Club, ClubExt},
+    error::AppResult,
+    meetings::{Meeting, MeetingExt},
+    timezone::local_to_utc,
+};
+
+pub use shared::MeetingSeries;
+
+/// Database access for [`MeetingSeries`].
+pub trait MeetingSeriesExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+
+    /// Creates a meeting for every upcoming occurrence up to `until` that doesn't
+    /// have one yet. Occurrences that were moved keep their meeting and cancelled
+    /// ones are recorded as exceptions, so expanding again never duplicates or
+    /// resurrects meetings. Occurrences are computed in the club's timezone and
+    /// stored in UTC.
+    async fn expand(
+        &self,
+        until: NaiveDateTime,
+        db: &mut SqliteConnection,
+    ) -> AppResult<Vec<Meeting>>;
+}
+
+impl MeetingSeriesExt for MeetingSeries {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+        let series = sqlx::query_as!(
+            MeetingSeries,
+            r#"
+            SELECT id, club_id, rrule, starts_at, created_at
+            FROM meeting_series
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(series)
+    }
+
+    async fn expand(
+        &self,
+        until: NaiveDateTime,
+        db: &mut SqliteConnection,
+    ) -> AppResult<Vec<Meeting>> {
+        let rule: RRule = self.rrule.parse()?;
+        let now = Utc::now().naive_utc();
+        let tz = Club::from_id(self.club_id, &mut *db)
+            .await?
+            .map(|club| club.tz())
+            .unwrap_or(Tz::UTC);
+
+        let existing = sqlx::query_scalar!(
+            r#"
+            SELECT occurrence AS "occurrence!"
+            FROM meetings
+            WHERE series_id = ? AND occurrence IS NOT NULL
+            UNION
+            SELECT occurrence
+            FROM meeting_series_exceptions
+            WHERE series_id = ?
+            "#,
+            self.id,
+            self.id
+        )
+        .fetch_all(&mut *db)
+        .await?
+        .into_iter()
+        .collect::<HashSet<NaiveDateTime>>();
+
+        let mut meetings = vec![];
+        for occurrence in rule.occurrences(self.starts_at, until) {
+            let date = local_to_utc(&tz, occurrence);
+            if date < now || existing.contains(&occurrence) {
+                continue;
+            }
+
+            let meeting = sqlx::query_as!(
+                Meeting,
+                r#"
+                INSERT INTO meetings (date, club_id, series_id, occurrence)
+                VALUES (?, ?, ?, ?)
+                RETURNING id AS "id!", date AS "date!: DateTime<FixedOffset>", book_id,
+                          club_id AS "club_id!", series_id, occurrence, venue_id,
+                          reading_target
+                "#,
+                date,
+                self.club_id,
+                self.id,
+                occurrence
+            )
+            .fetch_one(&mut *db)
+            .await?;
+            meetings.push(meeting.in_timezone(&tz));
+        }
+
+        Ok(meetings)
+    }
+}
@@ -0,0 +1,324 @@
+mod meeting_series;
+mod rrule;
+
+pub use meeting_series::*;
+pub use rrule::*;
+pub use shared::{CreateSeriesParams, ExpandSeriesParams};
+
+use crate::{
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    meetings::Meeting,
+    sqlite::Database,
+};
+use axum::{
+    debug_handler,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+
+/// How far ahead meetings are generated when no explicit horizon is given.
+pub const DEFAULT_HORIZON_DAYS: i64 = 90;
+
+fn default_horizon() -> NaiveDateTime {
+    Utc::now().naive_utc() + Duration::days(DEFAULT_HORIZON_DAYS)
+}
+
+#[debug_handler]
+pub async fn create_series(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    Json(params): Json<CreateSeriesParams>,
+) -> AppResult<impl IntoResponse> {
+    if let Err(e) = params.rrule.parse::<RRule>() {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid rrule: {e}"),
+        ));
+    }
+
+    let mut tx = db.as_ref().begin().await?;
+
+    if Club::from_id(club_id, &mut tx).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+
+    let series = sqlx::query_as!(
+        MeetingSeries,
+        r#"
+        INSERT INTO meeting_series (club_id, rrule, starts_at)
+        VALUES (?, ?, ?)
+        RETURNING id AS "id!", club_id AS "club_id!", rrule AS "rrule!",
+                  starts_at AS "starts_at!", created_at AS "created_at!"
+        "#,
+        club_id,
+        params.rrule,
+        params.starts_at
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    series
+        .expand(params.until.unwrap_or_else(default_horizon), &mut tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(series)).into_response())
+}
+
+#[debug_handler]
+pub async fn get_club_series(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+) -> AppResult<Json<Vec<MeetingSeries>>> {
+    let series = sqlx::query_as!(
+        MeetingSeries,
+        r#"
+        SELECT id AS "id!", club_id, rrule, starts_at, created_at
+        FROM meeting_series
+        WHERE club_id = ?
+        ORDER BY id
+        "#,
+        club_id
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    Ok(Json(series))
+}
+
+/// Generates meetings for the series up to the horizon, returning only the new ones.
+#[debug_handler]
+pub async fn expand_series(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    Query(params): Query<ExpandSeriesParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(series) = MeetingSeries::from_id(id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Series not found"));
+    };
+
+    let meetings: Vec<Meeting> = series
+        .expand(params.until.unwrap_or_else(default_horizon), &mut tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(meetings).into_response())
+}
+
+/// Ends the series and cancels its upcoming meetings. Past meetings are kept.
+#[debug_handler]
+pub async fn delete_series(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let now = Utc::now().naive_utc();
+    let mut tx = db.as_ref().begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM meetings WHERE series_id = ? AND date >= ?",
+        id,
+        now
+    )
+    .execute(&mut *tx)
+    .await?;
+    let result = sqlx::query!("DELETE FROM meeting_series WHERE id = ?", id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Series not found"));
+    }
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clubs::{test::create_test_club, CreateClubParams};
+    use crate::meetings::UpdateMeetingParams;
+    use crate::tests::create_test_server;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(19, 0, 0)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_series_exceptions_survive_expansion() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        let response = server
+            .post(&format!("/clubs/{}/series", club.id))
+            .json(&CreateSeriesParams {
+                rrule: "FREQ=MONTHLY;BYDAY=2TH".to_string(),
+                starts_at: at(2030, 1, 1),
+                until: Some(at(2030, 4, 30)),
+            })
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let series: MeetingSeries = response.json();
+
+        let meetings: Vec<Meeting> = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .await
+            .json();
+        let dates = meetings
+            .iter()
+            .map(|m| m.date.naive_utc())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                at(2030, 1, 10),
+                at(2030, 2, 14),
+                at(2030, 3, 14),
+                at(2030, 4, 11)
+            ]
+        );
+
+        // Skip February and move March to the following day.
+        server
+            .delete(&format!("/meetings/{}", meetings[1].id))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        let response = server
+            .put(&format!("/meetings/{}", meetings[2].id))
+            .json(&UpdateMeetingParams {
+                date: Some(at(2030, 3, 15).and_utc()),
+                book_id: None,
+                ..Default::default()
+            })
+            .await;
+        response.assert_status(StatusCode::OK);
+        let moved: Meeting = response.json();
+        assert_eq!(moved.occurrence, Some(at(2030, 3, 14)));
+
+        let response = server
+            .post(&format!("/meeting-series/{}/expand", series.id))
+            .add_query_param("until", "2030-05-31T00:00:00")
+            .await;
+        response.assert_status(StatusCode::OK);
+        let created: Vec<Meeting> = response.json();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].date.naive_utc(), at(2030, 5, 9));
+
+        let meetings: Vec<Meeting> = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .await
+            .json();
+        let dates = meetings
+            .iter()
+            .map(|m| m.date.naive_utc())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![
+                at(2030, 1, 10),
+                at(2030, 3, 15),
+                at(2030, 4, 11),
+                at(2030, 5, 9)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_series_keeps_local_time_across_dst() {
+        let server = create_test_server().await;
+        let club: Club = server
+            .post("/clubs")
+            .json(&CreateClubParams {
+                name: "London Club".to_string(),
+                description: "Meets on Thursdays".to_string(),
+                timezone: Some("Europe/London".to_string()),
+            })
+            .await
+            .json();
+
+        server
+            .post(&format!("/clubs/{}/series", club.id))
+            .json(&CreateSeriesParams {
+                rrule: "FREQ=WEEKLY".to_string(),
+                starts_at: at(2030, 3, 28),
+                until: Some(at(2030, 4, 4)),
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let meetings: Vec<Meeting> = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .await
+            .json();
+        let dates = meetings
+            .iter()
+            .map(|m| m.date.to_rfc3339())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec!["2030-03-28T19:00:00+00:00", "2030-04-04T19:00:00+01:00"]
+        );
+        assert_eq!(
+            meetings[1].date.naive_utc(),
+            at(2030, 4, 4) - Duration::hours(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_series_invalid_rule() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        let response = server
+            .post(&format!("/clubs/{}/series", club.id))
+            .json(&CreateSeriesParams {
+                rrule: "FREQ=FORTNIGHTLY".to_string(),
+                starts_at: at(2030, 1, 1),
+                until: None,
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_delete_series() {
+        let server = create_test_server().await;
+        let club = create_test_club(&server).await;
+
+        let series: MeetingSeries = server
+            .post(&format!("/clubs/{}/series", club.id))
+            .json(&CreateSeriesParams {
+                rrule: "FREQ=WEEKLY".to_string(),
+                starts_at: at(2030, 1, 1),
+                until: Some(at(2030, 1, 31)),
+            })
+            .await
+            .json();
+
+        server
+            .delete(&format!("/meeting-series/{}", series.id))
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let meetings: Vec<Meeting> = server
+            .get(&format!("/clubs/{}/meetings", club.id))
+            .await
+            .json();
+        assert!(meetings.is_empty());
+        let series: Vec<MeetingSeries> = server
+            .get(&format!("/clubs/{}/series", club.id))
+            .await
+            .json();
+        assert!(series.is_empty());
+    }
+}
@@ -0,0 +1,417 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, Weekday};
+
+// Bounds expansion of daily rules over a far-away horizon.
+const MAX_PERIODS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A weekday, optionally restricted to its n-th occurrence within the month (`2TH`, `-1FR`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+/// The subset of RFC 5545 recurrence rules that club schedules need:
+/// `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY` and `BYMONTHDAY` (monthly rules only).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<usize>,
+    pub until: Option<NaiveDateTime>,
+    pub by_day: Vec<ByDay>,
+    pub by_month_day: Vec<i32>,
+}
+
+impl FromStr for RRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix("RRULE:").unwrap_or(s);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = vec![];
+        let mut by_month_day = vec![];
+
+        for part in s.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid rule part '{part}'"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(parse_frequency(value)?),
+                "INTERVAL" => {
+                    interval = value.parse()?;
+                    if interval == 0 {
+                        bail!("INTERVAL must be at least 1");
+                    }
+                }
+                "COUNT" => count = Some(value.parse()?),
+                "UNTIL" => until = Some(parse_until(value)?),
+                "BYDAY" => by_day = value.split(',').map(parse_by_day).collect::<Result<_>>()?,
+                "BYMONTHDAY" => {
+                    by_month_day = value
+                        .split(',')
+                        .map(parse_month_day)
+                        .collect::<Result<_>>()?
+                }
+                "WKST" => {}
+                _ => bail!("unsupported rule part '{key}'"),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| anyhow!("FREQ is required"))?;
+        if count.is_some() && until.is_some() {
+            bail!("COUNT and UNTIL can't both be set");
+        }
+        if frequency != Frequency::Monthly && by_day.iter().any(|day| day.ordinal.is_some()) {
+            bail!("BYDAY ordinals are only supported with FREQ=MONTHLY");
+        }
+        if frequency != Frequency::Monthly && !by_month_day.is_empty() {
+            bail!("BYMONTHDAY is only supported with FREQ=MONTHLY");
+        }
+
+        Ok(RRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+            by_month_day,
+        })
+    }
+}
+
+impl RRule {
+    /// All occurrences starting at `start` up to and including `end`, in order.
+    pub fn occurrences(&self, start: NaiveDateTime, end: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let end = match self.until {
+            Some(until) => until.min(end),
+            None => end,
+        };
+
+        let mut occurrences = vec![];
+        let mut seen = 0;
+
+        for period in 0..MAX_PERIODS {
+            // Periods too far out for the calendar end the series.
+            let Some(period_start) = (period as u32)
+                .checked_mul(self.interval)
+                .and_then(|offset| self.period_start(start, offset))
+            else {
+                break;
+            };
+            if period_start > end {
+                break;
+            }
+
+            for candidate in self
+                .period_candidates(start, period_start.date())
+                .into_iter()
+                .filter(|candidate| *candidate >= start)
+            {
+                if self.count.is_some_and(|count| seen >= count) || candidate > end {
+                    return occurrences;
+                }
+                seen += 1;
+                occurrences.push(candidate);
+            }
+        }
+
+        occurrences
+    }
+
+    /// The start of the period `offset` units of the frequency after the one containing `start`.
+    fn period_start(&self, start: NaiveDateTime, offset: u32) -> Option<NaiveDateTime> {
+        let date = start.date();
+        let date = match self.frequency {
+            Frequency::Daily => date.checked_add_days(Days::new(offset.into()))?,
+            Frequency::Weekly => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+                monday.checked_add_days(Days::new(u64::from(offset) * 7))?
+            }
+            Frequency::Monthly => date.with_day(1)?.checked_add_months(Months::new(offset))?,
+            Frequency::Yearly => date
+                .with_day(1)?
+                .with_month(1)?
+                .checked_add_months(Months::new(offset.checked_mul(12)?))?,
+        };
+        Some(date.and_time(start.time()))
+    }
+
+    /// The candidate occurrences in the period starting on `period_start`, sorted.
+    fn period_candidates(
+        &self,
+        start: NaiveDateTime,
+        period_start: NaiveDate,
+    ) -> Vec<NaiveDateTime> {
+        let mut dates = match self.frequency {
+            Frequency::Daily => {
+                if self.matches_weekday(period_start) {
+                    vec![period_start]
+                } else {
+                    vec![]
+                }
+            }
+            Frequency::Weekly => {
+                if self.by_day.is_empty() {
+                    vec![
+                        period_start
+                            + Duration::days(start.weekday().num_days_from_monday().into()),
+                    ]
+                } else {
+                    self.by_day
+                        .iter()
+                        .map(|day| {
+                            period_start + Duration::days(day.weekday.num_days_from_monday().into())
+                        })
+                        .collect()
+                }
+            }
+            Frequency::Monthly => self.month_candidates(start.date(), period_start),
+            Frequency::Yearly => {
+                NaiveDate::from_ymd_opt(period_start.year(), start.month(), start.day())
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        dates.sort();
+        dates.dedup();
+        dates
+            .into_iter()
+            .map(|date| date.and_time(start.time()))
+            .collect()
+    }
+
+    fn month_candidates(&self, start: NaiveDate, month: NaiveDate) -> Vec<NaiveDate> {
+        let days_in_month = days_in_month(month);
+
+        if self.by_day.is_empty() && self.by_month_day.is_empty() {
+            return month.with_day(start.day()).into_iter().collect();
+        }
+
+        let mut month_days = vec![];
+        for &day in &self.by_month_day {
+            let day = if day < 0 {
+                days_in_month as i32 + day + 1
+            } else {
+                day
+            };
+            if (1..=days_in_month as i32).contains(&day) {
+                month_days.extend(month.with_day(day as u32));
+            }
+        }
+
+        let mut weekdays = vec![];
+        for by_day in &self.by_day {
+            let matching = (1..=days_in_month)
+                .filter_map(|day| month.with_day(day))
+                .filter(|date| date.weekday() == by_day.weekday)
+                .collect::<Vec<_>>();
+            match by_day.ordinal {
+                None => weekdays.extend(matching),
+                Some(n) if n > 0 => weekdays.extend(matching.get(n as usize - 1)),
+                Some(n) => weekdays.extend(
+                    matching
+                        .len()
+                        .checked_sub(n.unsigned_abs() as usize)
+                        .and_then(|i| matching.get(i)),
+                ),
+            }
+        }
+
+        // With both, BYDAY limits BYMONTHDAY: Friday the 13th, not every Friday and every 13th.
+        match (month_days.is_empty(), weekdays.is_empty()) {
+            (_, true) => month_days,
+            (true, _) => weekdays,
+            _ => month_days
+                .into_iter()
+                .filter(|date| weekdays.contains(date))
+                .collect(),
+        }
+    }
+
+    fn matches_weekday(&self, date: NaiveDate) -> bool {
+        self.by_day.is_empty() || self.by_day.iter().any(|day| day.weekday == date.weekday())
+    }
+}
+
+fn days_in_month(month: NaiveDate) -> u32 {
+    let first = month.with_day(1).expect("every month has a first day");
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+fn parse_frequency(value: &str) -> Result<Frequency> {
+    match value.to_ascii_uppercase().as_str() {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        _ => bail!("unsupported FREQ '{value}'"),
+    }
+}
+
+fn parse_until(value: &str) -> Result<NaiveDateTime> {
+    let value = value.trim_end_matches('Z');
+    if let Ok(until) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Ok(until);
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+        .map_err(|_| anyhow!("invalid UNTIL '{value}'"))?;
+    // A bare date includes the whole day.
+    Ok(date.and_hms_opt(23, 59, 59).expect("valid time"))
+}
+
+fn parse_by_day(value: &str) -> Result<ByDay> {
+    let value = value.trim();
+    if value.len() < 2 {
+        bail!("invalid BYDAY '{value}'");
+    }
+    let (ordinal, weekday) = value.split_at(value.len() - 2);
+    let weekday = match weekday.to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => bail!("invalid BYDAY weekday '{weekday}'"),
+    };
+    let ordinal = match ordinal {
+        "" => None,
+        ordinal => {
+            let ordinal: i32 = ordinal.trim_start_matches('+').parse()?;
+            if ordinal == 0 || ordinal.abs() > 5 {
+                bail!("invalid BYDAY ordinal '{ordinal}'");
+            }
+            Some(ordinal)
+        }
+    };
+    Ok(ByDay { ordinal, weekday })
+}
+
+fn parse_month_day(value: &str) -> Result<i32> {
+    let day: i32 = value.trim().parse()?;
+    if day == 0 || day.abs() > 31 {
+        bail!("invalid BYMONTHDAY '{day}'");
+    }
+    Ok(day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(19, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_second_thursday_of_every_month() {
+        let rule: RRule = "FREQ=MONTHLY;BYDAY=2TH".parse().unwrap();
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2030, 4, 30));
+        assert_eq!(
+            occurrences,
+            vec![
+                at(2030, 1, 10),
+                at(2030, 2, 14),
+                at(2030, 3, 14),
+                at(2030, 4, 11)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_last_friday_of_every_other_month() {
+        let rule: RRule = "RRULE:FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR".parse().unwrap();
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2030, 6, 30));
+        assert_eq!(
+            occurrences,
+            vec![at(2030, 1, 25), at(2030, 3, 29), at(2030, 5, 31)]
+        );
+    }
+
+    #[test]
+    fn test_huge_interval_stops_at_the_first_occurrence() {
+        for frequency in ["DAILY", "WEEKLY", "MONTHLY", "YEARLY"] {
+            let rule: RRule = format!("FREQ={frequency};INTERVAL=4294967295")
+                .parse()
+                .unwrap();
+            let occurrences = rule.occurrences(at(2030, 1, 1), at(2100, 1, 1));
+            assert_eq!(occurrences.first(), Some(&at(2030, 1, 1)), "{frequency}");
+            assert_eq!(occurrences.len(), 1, "{frequency}");
+        }
+    }
+
+    #[test]
+    fn test_month_day_skips_short_months() {
+        let rule: RRule = "FREQ=MONTHLY;BYMONTHDAY=31".parse().unwrap();
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2030, 4, 30));
+        assert_eq!(occurrences, vec![at(2030, 1, 31), at(2030, 3, 31)]);
+    }
+
+    #[test]
+    fn test_friday_the_13th() {
+        let rule: RRule = "FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13".parse().unwrap();
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2031, 6, 30));
+        assert_eq!(
+            occurrences,
+            vec![at(2030, 9, 13), at(2030, 12, 13), at(2031, 6, 13)]
+        );
+    }
+
+    #[test]
+    fn test_weekly_with_count() {
+        let rule: RRule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;COUNT=3"
+            .parse()
+            .unwrap();
+        // 2030-01-01 is a Tuesday.
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2031, 1, 1));
+        assert_eq!(
+            occurrences,
+            vec![at(2030, 1, 1), at(2030, 1, 3), at(2030, 1, 15)]
+        );
+    }
+
+    #[test]
+    fn test_until_is_inclusive() {
+        let rule: RRule = "FREQ=DAILY;UNTIL=20300103".parse().unwrap();
+        let occurrences = rule.occurrences(at(2030, 1, 1), at(2031, 1, 1));
+        assert_eq!(
+            occurrences,
+            vec![at(2030, 1, 1), at(2030, 1, 2), at(2030, 1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!("BYDAY=MO".parse::<RRule>().is_err());
+        assert!("FREQ=HOURLY".parse::<RRule>().is_err());
+        assert!("FREQ=WEEKLY;BYDAY=2MO".parse::<RRule>().is_err());
+        assert!("FREQ=DAILY;INTERVAL=0".parse::<RRule>().is_err());
+        assert!("FREQ=DAILY;BYMONTHDAY=1".parse::<RRule>().is_err());
+        assert!("FREQ=WEEKLY;BYMONTHDAY=1".parse::<RRule>().is_err());
+        assert!("FREQ=DAILY;COUNT=2;UNTIL=20300101"
+            .parse::<RRule>()
+            .is_err());
+    }
+}
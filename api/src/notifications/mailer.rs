@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use lettre::{
+    message::Mailbox,
+    transport::{smtp::authentication::Credentials, stub::AsyncStubTransport},
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    #[default]
+    Smtp,
+    /// Keeps messages in memory instead of sending them.
+    Stub,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SmtpSettings {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Disabled for local mail catchers such as Mailpit, which speak plain SMTP.
+    pub starttls: bool,
+}
+
+impl Default for SmtpSettings {
+    fn default() -> Self {
+        SmtpSettings {
+            host: "localhost".to_string(),
+            port: 1025,
+            username: None,
+            password: None,
+            starttls: false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Mailer {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    Stub(AsyncStubTransport),
+}
+
+impl Mailer {
+    pub fn new(transport: Transport, settings: &SmtpSettings) -> Result<Mailer> {
+        if transport == Transport::Stub {
+            return Ok(Mailer::Stub(AsyncStubTransport::new_ok()));
+        }
+
+        let mut builder = if settings.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+        }
+        .port(settings.port);
+        if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Mailer::Smtp(builder.build()))
+    }
+
+    pub async fn send(&self, from: &str, to: &str, subject: &str, body: &str) -> Result<()> {
+        let message = Message::builder()
+            .from(from.parse::<Mailbox>().context("Invalid sender address")?)
+            .to(to.parse::<Mailbox>().context("Invalid recipient address")?)
+            .subject(subject)
+            .body(body.to_string())?;
+
+        match self {
+            Mailer::Smtp(transport) => {
+                transport.send(message).await?;
+            }
+            Mailer::Stub(transport) => {
+                transport.send(message).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Messages sent through the stub transport, as `(recipient, raw message)`.
+    #[cfg(test)]
+    pub async fn sent(&self) -> Vec<(String, String)> {
+        match self {
+            Mailer::Smtp(_) => vec![],
+            Mailer::Stub(transport) => transport
+                .messages()
+                .await
+                .into_iter()
+                .map(|(envelope, message)| (envelope.to()[0].to_string(), message))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,444 @@
+mod event;
+mod mailer;
+mod outbox;
+
+pub use event::*;
+pub use mailer::*;
+pub use outbox::*;
+pub use shared::NotificationPreference;
+
+use std::collections::HashMap;
+
+use crate::{
+    auth::session::CurrentUser,
+    error::{error_response, AppResult},
+    sqlite::Database,
+    AppState,
+};
+use axum::{
+    debug_handler,
+    extract::{FromRef, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqliteConnection;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub transport: Transport,
+    pub smtp: SmtpSettings,
+    pub from: String,
+    pub max_attempts: i64,
+    pub poll_interval_secs: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            transport: Transport::default(),
+            smtp: SmtpSettings::default(),
+            from: "Book Club <bookclub@localhost>".to_string(),
+            max_attempts: 5,
+            poll_interval_secs: 60,
+        }
+    }
+}
+
+impl FromRef<AppState> for Notifier {
+    fn from_ref(state: &AppState) -> Self {
+        state.notifier.clone()
+    }
+}
+
+async fn preferences(
+    user_id: i64,
+    db: &mut SqliteConnection,
+) -> AppResult<Vec<NotificationPreference>> {
+    let stored = sqlx::query!(
+        r#"
+        SELECT event, enabled AS "enabled: bool"
+        FROM notification_preferences
+        WHERE user_id = ?
+        "#,
+        user_id
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| (row.event, row.enabled))
+    .collect::<HashMap<_, _>>();
+
+    Ok(NotificationEvent::ALL
+        .into_iter()
+        .map(|event| NotificationPreference {
+            event,
+            enabled: stored.get(event.as_str()).copied().unwrap_or(true),
+        })
+        .collect())
+}
+
+/// Lists every event type with whether the user receives it.
+#[debug_handler]
+pub async fn get_notification_preferences(
+    State(db): State<Database>,
+    Path(user_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    if user.id != user_id {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "You can only manage your own notifications",
+        ));
+    }
+
+    let mut conn = db.as_ref().acquire().await?;
+
+    Ok(Json(preferences(user_id, &mut conn).await?).into_response())
+}
+
+/// Opts the user in or out of event types, e.g. `{"meeting_tomorrow": false}`.
+/// Events that are left out keep their current setting.
+#[debug_handler]
+pub async fn update_notification_preferences(
+    State(db): State<Database>,
+    Path(user_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+    Json(params): Json<HashMap<NotificationEvent, bool>>,
+) -> AppResult<impl IntoResponse> {
+    if user.id != user_id {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "You can only manage your own notifications",
+        ));
+    }
+
+    let mut tx = db.as_ref().begin().await?;
+    for (event, enabled) in params {
+        let event = event.as_str();
+        sqlx::query!(
+            r#"
+            INSERT INTO notification_preferences (user_id, event, enabled)
+            VALUES (?, ?, ?)
+            ON CONFLICT (user_id, event) DO UPDATE SET enabled = excluded.enabled
+            "#,
+            user_id,
+            event,
+            enabled
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    let preferences = preferences(user_id, &mut tx).await?;
+    tx.commit().await?;
+
+    Ok(Json(preferences).into_response())
+}
+
+/// The user's most recent notifications with their delivery status.
+#[debug_handler]
+pub async fn get_user_notifications(
+    State(db): State<Database>,
+    Path(user_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    if user.id != user_id {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "You can only manage your own notifications",
+        ));
+    }
+
+    let notifications = sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT id AS "id!", user_id, event, subject, body, dedup_key, status, attempts,
+               last_error, next_attempt_at, created_at, sent_at
+        FROM notification_outbox
+        WHERE user_id = ?
+        ORDER BY id DESC
+        LIMIT 100
+        "#,
+        user_id
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    Ok(Json(notifications).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::clubs::memberships::CreateMembershipParams;
+    use crate::clubs::test::create_test_club;
+    use crate::meetings::{test::create_meeting, CreateMeetingParams};
+    use crate::tests::create_test_server_with_state;
+    use crate::users::{
+        test::{create_test_user, create_user},
+        CreateUserParams,
+    };
+    use axum_test::TestServer;
+    use chrono::{Duration, TimeZone, Utc};
+    use lettre::transport::stub::AsyncStubTransport;
+
+    async fn join(server: &TestServer, user_id: i64, club_id: i64) {
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id,
+                club_id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    async fn outbox(db: &Database) -> Vec<Notification> {
+        sqlx::query_as("SELECT * FROM notification_outbox ORDER BY id")
+            .fetch_all(db.as_ref())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_membership_and_meeting_notifications_are_delivered() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let club = create_test_club(&server).await;
+        join(&server, user.id, club.id).await;
+
+        create_meeting(
+            &server,
+            CreateMeetingParams {
+                club_id: club.id,
+                book_id: None,
+                date: Utc::now() + Duration::days(7),
+                venue_id: None,
+                location: None,
+                reading_target: None,
+            },
+        )
+        .await;
+
+        let queued = outbox(&state.db).await;
+        let events = queued.iter().map(|n| n.event.as_str()).collect::<Vec<_>>();
+        assert_eq!(events, vec!["club_joined", "meeting_scheduled"]);
+
+        let sent = state
+            .notifier
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(sent, 2);
+        let messages = state.notifier.mailer.sent().await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0, user.email);
+        let session = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let notifications: Vec<Notification> = server
+            .get(&format!("/users/{}/notifications", user.id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={session}"))
+            .await
+            .json();
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications.iter().all(|n| n.status == "sent"));
+
+        // Nothing is sent twice.
+        let sent = state
+            .notifier
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_meeting_tomorrow_reminder_respects_preferences() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let club = create_test_club(&server).await;
+        join(&server, user.id, club.id).await;
+
+        let session = create_session(state.db.as_ref(), user.id).await.unwrap();
+        let response = server
+            .put(&format!("/users/{}/notification-preferences", user.id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={session}"))
+            .json(&HashMap::from([(
+                NotificationEvent::MeetingScheduled,
+                false,
+            )]))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let preferences: Vec<NotificationPreference> = response.json();
+        assert_eq!(preferences.len(), NotificationEvent::ALL.len());
+        assert!(preferences
+            .iter()
+            .all(|p| p.enabled == (p.event != NotificationEvent::MeetingScheduled)));
+
+        create_meeting(
+            &server,
+            CreateMeetingParams {
+                club_id: club.id,
+                book_id: None,
+                date: Utc::now() + Duration::hours(20),
+                venue_id: None,
+                location: None,
+                reading_target: None,
+            },
+        )
+        .await;
+
+        let now = Utc::now().naive_utc();
+        queue_meeting_reminders(state.db.as_ref(), now)
+            .await
+            .unwrap();
+        queue_meeting_reminders(state.db.as_ref(), now)
+            .await
+            .unwrap();
+
+        let events = outbox(&state.db)
+            .await
+            .into_iter()
+            .map(|n| n.event)
+            .collect::<Vec<_>>();
+        assert_eq!(events, vec!["club_joined", "meeting_tomorrow"]);
+    }
+
+    #[tokio::test]
+    async fn test_meeting_reminder_says_today_or_tomorrow() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let new_yorker = create_user(
+            &server,
+            CreateUserParams {
+                email: "ny@example.com".to_string(),
+                first_name: "New".to_string(),
+                last_name: "Yorker".to_string(),
+                timezone: Some("America/New_York".to_string()),
+            },
+        )
+        .await;
+        let club = create_test_club(&server).await;
+        join(&server, user.id, club.id).await;
+        join(&server, new_yorker.id, club.id).await;
+
+        // 22:00 on the 9th in New York.
+        create_meeting(
+            &server,
+            CreateMeetingParams {
+                club_id: club.id,
+                book_id: None,
+                date: Utc.with_ymd_and_hms(2030, 1, 10, 3, 0, 0).unwrap(),
+                venue_id: None,
+                location: None,
+                reading_target: None,
+            },
+        )
+        .await;
+
+        let now = Utc.with_ymd_and_hms(2030, 1, 9, 12, 0, 0).unwrap();
+        queue_meeting_reminders(state.db.as_ref(), now.naive_utc())
+            .await
+            .unwrap();
+
+        let reminders = outbox(&state.db)
+            .await
+            .into_iter()
+            .filter(|n| n.event == "meeting_tomorrow")
+            .map(|n| (n.user_id, n.body))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reminders,
+            vec![
+                (
+                    user.id,
+                    "Test Club meets tomorrow at 03:00 UTC.".to_string()
+                ),
+                (
+                    new_yorker.id,
+                    "Test Club meets today at 22:00 EST.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_retried() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+        let club = create_test_club(&server).await;
+        join(&server, user.id, club.id).await;
+
+        let notifier = Notifier {
+            mailer: Mailer::Stub(AsyncStubTransport::new_error()),
+            ..state.notifier.clone()
+        };
+        assert_eq!(
+            notifier.deliver_pending(state.db.as_ref()).await.unwrap(),
+            0
+        );
+
+        let notification = &outbox(&state.db).await[0];
+        assert_eq!(notification.status, "pending");
+        assert_eq!(notification.attempts, 1);
+        assert!(notification.last_error.is_some());
+        assert!(notification.next_attempt_at > Utc::now().naive_utc());
+
+        // Not due yet, so a working mailer doesn't pick it up either.
+        let sent = state
+            .notifier
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_require_the_user() {
+        let (server, state) = create_test_server_with_state().await;
+        let user = create_test_user(&server).await;
+
+        let response = server
+            .get(&format!("/users/{}/notification-preferences", user.id))
+            .await;
+        response.assert_status(StatusCode::UNAUTHORIZED);
+
+        let other = create_user(
+            &server,
+            CreateUserParams {
+                email: "other@example.com".to_string(),
+                first_name: "Other".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        let session = create_session(state.db.as_ref(), other.id).await.unwrap();
+        let cookie = format!("{SESSION_COOKIE}={session}");
+
+        let response = server
+            .get(&format!("/users/{}/notification-preferences", user.id))
+            .add_header("cookie", cookie.clone())
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+
+        let response = server
+            .put(&format!("/users/{}/notification-preferences", user.id))
+            .add_header("cookie", cookie.clone())
+            .json(&HashMap::from([(
+                NotificationEvent::MeetingScheduled,
+                false,
+            )]))
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+
+        let response = server
+            .get(&format!("/users/{}/notifications", user.id))
+            .add_header("cookie", cookie)
+            .await;
+        response.assert_status(StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,279 @@
+pub use shared::Notification;
+
+use std::time::Duration as StdDuration;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::{Mailer, NotificationEvent, Settings};
+use crate::{error::AppResult, retry, sqlite::Database};
+
+/// Retries back off exponentially from this delay.
+const FIRST_RETRY_MINUTES: i64 = 1;
+const MAX_RETRY_MINUTES: i64 = 6 * 60;
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+/// Queues a notification for the user unless they opted out of the event.
+/// Returns whether it was queued; a `dedup_key` that was used before also
+/// counts as not queued.
+pub async fn enqueue(
+    db: &mut SqliteConnection,
+    user_id: i64,
+    event: NotificationEvent,
+    subject: &str,
+    body: &str,
+    dedup_key: Option<&str>,
+) -> AppResult<bool> {
+    let event = event.as_str();
+    let enabled = sqlx::query_scalar!(
+        r#"
+        SELECT enabled AS "enabled: bool"
+        FROM notification_preferences
+        WHERE user_id = ? AND event = ?
+        "#,
+        user_id,
+        event
+    )
+    .fetch_optional(&mut *db)
+    .await?
+    .unwrap_or(true);
+    if !enabled {
+        return Ok(false);
+    }
+
+    let now = Utc::now().naive_utc();
+    let result = sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO notification_outbox
+            (user_id, event, subject, body, dedup_key, next_attempt_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        user_id,
+        event,
+        subject,
+        body,
+        dedup_key,
+        now
+    )
+    .execute(&mut *db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Queues a notification for every member of the club.
+pub async fn enqueue_for_club(
+    db: &mut SqliteConnection,
+    club_id: i64,
+    event: NotificationEvent,
+    subject: &str,
+    body: &str,
+) -> AppResult<()> {
+    let members = sqlx::query_scalar!("SELECT user_id FROM memberships WHERE club_id = ?", club_id)
+        .fetch_all(&mut *db)
+        .await?;
+
+    for user_id in members {
+        enqueue(db, user_id, event, subject, body, None).await?;
+    }
+
+    Ok(())
+}
+
+struct MeetingReminder {
+    meeting_id: i64,
+    user_id: i64,
+    date: DateTime<Utc>,
+    club_name: String,
+    timezone: String,
+    title: Option<String>,
+}
+
+/// Delivers the outbox over email.
+#[derive(Clone)]
+pub struct Notifier {
+    pub mailer: Mailer,
+    pub from: String,
+    pub max_attempts: i64,
+    pub poll_interval: StdDuration,
+}
+
+impl Notifier {
+    pub fn new(settings: &Settings) -> Result<Notifier> {
+        Ok(Notifier {
+            mailer: Mailer::new(settings.transport, &settings.smtp)?,
+            from: settings.from.clone(),
+            max_attempts: settings.max_attempts,
+            poll_interval: StdDuration::from_secs(settings.poll_interval_secs),
+        })
+    }
+
+    /// Queues reminders for the meetings happening within the next day and
+    /// delivers pending notifications until stopped.
+    pub async fn run(self, db: Database) {
+        let mut interval = tokio::time::interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = queue_meeting_reminders(db.as_ref(), Utc::now().naive_utc()).await {
+                tracing::error!("Error queueing meeting reminders: {:?}", e);
+            }
+            if let Err(e) = self.deliver_pending(db.as_ref()).await {
+                tracing::error!("Error delivering notifications: {:?}", e);
+            }
+        }
+    }
+
+    /// Sends the notifications that are due. Failed deliveries are retried with
+    /// exponential backoff until `max_attempts` is reached. Returns the number
+    /// of notifications sent.
+    pub async fn deliver_pending(&self, db: &SqlitePool) -> AppResult<usize> {
+        let now = Utc::now().naive_utc();
+        let due = sqlx::query!(
+            r#"
+            SELECT notification_outbox.id AS "id!", notification_outbox.attempts,
+                   notification_outbox.subject, notification_outbox.body, users.email
+            FROM notification_outbox
+            JOIN users ON users.id = notification_outbox.user_id
+            WHERE status = 'pending' AND next_attempt_at <= ?
+            ORDER BY next_attempt_at
+            LIMIT ?
+            "#,
+            now,
+            DELIVERY_BATCH_SIZE
+        )
+        .fetch_all(db)
+        .await?;
+
+        let mut sent = 0;
+        for notification in due {
+            // Marked as sent only after the mail server accepted it, so a crash in
+            // between can deliver a notification twice but never drops one.
+            match self
+                .mailer
+                .send(
+                    &self.from,
+                    &notification.email,
+                    &notification.subject,
+                    &notification.body,
+                )
+                .await
+            {
+                Ok(()) => {
+                    let sent_at = Utc::now().naive_utc();
+                    sqlx::query!(
+                        r#"
+                        UPDATE notification_outbox
+                        SET status = 'sent', attempts = attempts + 1, sent_at = ?,
+                            last_error = NULL
+                        WHERE id = ?
+                        "#,
+                        sent_at,
+                        notification.id
+                    )
+                    .execute(db)
+                    .await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Error sending notification {}: {}", notification.id, e);
+                    let attempts = notification.attempts + 1;
+                    let status = if attempts >= self.max_attempts {
+                        "failed"
+                    } else {
+                        "pending"
+                    };
+                    let next_attempt_at = now
+                        + retry::backoff(
+                            attempts,
+                            Duration::minutes(FIRST_RETRY_MINUTES),
+                            Duration::minutes(MAX_RETRY_MINUTES),
+                        );
+                    let error = e.to_string();
+                    sqlx::query!(
+                        r#"
+                        UPDATE notification_outbox
+                        SET status = ?, attempts = ?, next_attempt_at = ?, last_error = ?
+                        WHERE id = ?
+                        "#,
+                        status,
+                        attempts,
+                        next_attempt_at,
+                        error,
+                        notification.id
+                    )
+                    .execute(db)
+                    .await?;
+                }
+            }
+        }
+
+        Ok(sent)
+    }
+}
+
+/// Queues a reminder for every member of a club meeting in the next 24 hours,
+/// saying whether it's today or tomorrow in the member's timezone. Each member
+/// gets at most one reminder per meeting.
+pub async fn queue_meeting_reminders(db: &SqlitePool, now: NaiveDateTime) -> AppResult<()> {
+    let until = now + Duration::days(1);
+    let reminders = sqlx::query_as!(
+        MeetingReminder,
+        r#"
+        SELECT meetings.id AS "meeting_id!", memberships.user_id,
+               meetings.date AS "date: DateTime<Utc>", clubs.name AS club_name,
+               COALESCE(users.timezone, clubs.timezone) AS "timezone!: String",
+               books.title AS "title?"
+        FROM meetings
+        JOIN clubs ON clubs.id = meetings.club_id
+        JOIN memberships ON memberships.club_id = meetings.club_id
+        JOIN users ON users.id = memberships.user_id
+        LEFT JOIN books ON books.id = meetings.book_id
+        WHERE meetings.date > ? AND meetings.date <= ?
+        "#,
+        now,
+        until
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut conn = db.acquire().await?;
+    for reminder in reminders {
+        let tz = reminder.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        let date = reminder.date.with_timezone(&tz);
+        let day = if date.date_naive() == tz.from_utc_datetime(&now).date_naive() {
+            "today"
+        } else {
+            "tomorrow"
+        };
+        let time = date.format("%H:%M %Z");
+        let subject = format!("{} meets {day}", reminder.club_name);
+        let body = match reminder.title {
+            Some(title) => format!(
+                "{} meets {day} at {time} to talk about {title}.",
+                reminder.club_name
+            ),
+            None => format!("{} meets {day} at {time}.", reminder.club_name),
+        };
+        let dedup_key = format!(
+            "{}:{}:{}",
+            NotificationEvent::MeetingTomorrow,
+            reminder.meeting_id,
+            reminder.user_id
+        );
+
+        enqueue(
+            &mut conn,
+            reminder.user_id,
+            NotificationEvent::MeetingTomorrow,
+            &subject,
+            &body,
+            Some(&dedup_key),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -1,13 +1,9 @@
+pub use shared::OpenLibBook;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-const FIELDS: &str = "title,author_name,key";
-#[derive(Debug, Deserialize, Serialize)]
-pub struct OpenLibBook {
-    pub title: String,
-    pub author_name: Option<Vec<String>>,
-    pub key: String,
-}
+const FIELDS: &str = "title,author_name,author_key,key,cover_i,first_publish_year";
 
 #[derive(Debug, Deserialize, Serialize)]
 struct SearchResponse {
@@ -31,9 +27,19 @@ impl OpenLibraryClient {
     }
 
     pub async fn search_book(&self, title: &str) -> Result<Option<OpenLibBook>> {
-        let escaped_title = title.replace(' ', "+");
+        Ok(self.search_books(title, 1).await?.into_iter().next())
+    }
+
+    /// The work with the id, e.g. `OL45804W`.
+    pub async fn work(&self, id: &str) -> Result<Option<OpenLibBook>> {
+        self.search_book(&format!("key:/works/{id}")).await
+    }
+
+    /// The best `limit` matches for the query.
+    pub async fn search_books(&self, query: &str, limit: usize) -> Result<Vec<OpenLibBook>> {
+        let escaped_query = query.replace(' ', "+");
         let url = format!(
-            "{}/search.json?q={escaped_title}&fields={FIELDS}",
+            "{}/search.json?q={escaped_query}&fields={FIELDS}&limit={limit}",
             self.settings.base_url
         );
         tracing::info!("OpenLib URL: {}", url);
@@ -45,6 +51,6 @@ impl OpenLibraryClient {
         let body = res.text().await?;
         let search_res = serde_json::from_str::<SearchResponse>(&body)?;
 
-        Ok(search_res.docs.into_iter().next())
+        Ok(search_res.docs)
     }
 }
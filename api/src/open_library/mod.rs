@@ -1,36 +1,65 @@
 mod client;
 
 pub use client::*;
+pub use shared::{OpenLibraryResultsParams, OpenLibrarySearchParams};
 
-use crate::AppState;
+use crate::{error::error_response, AppState};
 use axum::{
     debug_handler,
-    extract::{FromRef, Query, State},
+    extract::{FromRef, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
 
-#[derive(Deserialize)]
-pub struct Params {
-    title: String,
-}
+/// How many results `search_books` returns unless asked for fewer.
+const MAX_RESULTS: usize = 20;
+
 #[debug_handler]
 pub async fn search_book(
-    Query(Params { title }): Query<Params>,
+    Query(OpenLibrarySearchParams { title }): Query<OpenLibrarySearchParams>,
     State(client): State<OpenLibraryClient>,
 ) -> Response {
     match client.search_book(&title).await {
         Ok(Some(book)) => (StatusCode::OK, Json(book)).into_response(),
-        Ok(None) => (StatusCode::NOT_FOUND, "Book not found").into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Book not found"),
         Err(e) => {
             tracing::error!("Error searching for book: {}", e);
-            (
+            error_response(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Error searching for book",
             )
-                .into_response()
+        }
+    }
+}
+
+/// Every match for the title, best first, e.g. for the Telegram bot's inline search.
+#[debug_handler]
+pub async fn search_books(
+    Query(OpenLibraryResultsParams { title, limit }): Query<OpenLibraryResultsParams>,
+    State(client): State<OpenLibraryClient>,
+) -> Response {
+    let limit = limit.unwrap_or(MAX_RESULTS).min(MAX_RESULTS);
+    match client.search_books(&title, limit).await {
+        Ok(books) => Json(books).into_response(),
+        Err(e) => {
+            tracing::error!("Error searching for books: {}", e);
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Error searching for books",
+            )
+        }
+    }
+}
+
+#[debug_handler]
+pub async fn get_work(Path(id): Path<String>, State(client): State<OpenLibraryClient>) -> Response {
+    match client.work(&id).await {
+        Ok(Some(book)) => Json(book).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "Book not found"),
+        Err(e) => {
+            tracing::error!("Error fetching work {}: {}", id, e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Error fetching book")
         }
     }
 }
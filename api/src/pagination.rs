@@ -0,0 +1,51 @@
+use axum::{
+    http::{header, HeaderValue, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+pub use shared::PageParams;
+
+/// Pages hold this many items when the client only gives an offset.
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// The page's size and offset, clamped to sensible values. Lists are only
+/// paged when the client asks for a page, so clients from before paging still
+/// get every item.
+pub fn bounds(params: &PageParams) -> (i64, i64) {
+    if params.limit.is_none() && params.offset.is_none() {
+        // Leaves room for the extra row `page` looks for.
+        return (i64::MAX - 1, 0);
+    }
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let offset = params.offset.unwrap_or(0).max(0);
+    (limit, offset)
+}
+
+/// Answers with a page of `items`, which should have been fetched with one
+/// more row than the page holds. That extra row tells whether a next page
+/// exists, in which case its URL goes into the `Link` header.
+pub fn page<T: Serialize>(mut items: Vec<T>, params: &PageParams, uri: &Uri) -> Response {
+    let (limit, offset) = bounds(params);
+    let has_next = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+
+    let mut response = Json(items).into_response();
+    if has_next {
+        let next = format!(
+            "<{}?limit={limit}&offset={}>; rel=\"next\"",
+            uri.path(),
+            offset + limit
+        );
+        if let Ok(value) = HeaderValue::from_str(&next) {
+            response.headers_mut().insert(header::LINK, value);
+        }
+    }
+    response
+}
@@ -0,0 +1,24 @@
+use chrono::Duration;
+
+/// How long to wait before the next attempt after `attempts` failed ones: the
+/// `first` delay, doubled with every further attempt, but no more than `max`.
+pub fn backoff(attempts: i64, first: Duration, max: Duration) -> Duration {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (first * 2_i32.pow(exponent)).min(max)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let delay = |attempts| backoff(attempts, Duration::seconds(30), Duration::hours(1));
+        assert_eq!(delay(0), Duration::seconds(30));
+        assert_eq!(delay(1), Duration::seconds(30));
+        assert_eq!(delay(2), Duration::minutes(1));
+        assert_eq!(delay(4), Duration::minutes(4));
+        assert_eq!(delay(8), Duration::hours(1));
+        assert_eq!(delay(i64::MAX), Duration::hours(1));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{auth, open_library, sqlite};
+use crate::{auth, backup, notifications, open_library, sqlite, telegram, webhooks};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -6,4 +6,12 @@ pub struct Settings {
     pub sqlite: sqlite::Settings,
     pub open_library: open_library::Settings,
     pub google_auth: auth::google::Settings,
+    #[serde(default)]
+    pub notifications: notifications::Settings,
+    #[serde(default)]
+    pub webhooks: webhooks::Settings,
+    #[serde(default)]
+    pub backup: backup::Settings,
+    #[serde(default)]
+    pub telegram: telegram::Settings,
 }
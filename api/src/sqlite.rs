@@ -1,30 +1,104 @@
 use anyhow::Result;
 use axum::extract::FromRef;
 use serde::Deserialize;
-use sqlx::{migrate::MigrateDatabase, Sqlite, SqlitePool};
+use std::path::{Path, PathBuf};
+
+use sqlx::{
+    migrate::{MigrateDatabase, Migrator},
+    sqlite::SqliteConnectOptions,
+    Sqlite, SqlitePool,
+};
 
 use crate::AppState;
 
+static MIGRATOR: Migrator = sqlx::migrate!("db/migrations");
+
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub url: String,
 }
 
+impl Settings {
+    /// The database file, or `None` for an in-memory database.
+    pub fn path(&self) -> Option<PathBuf> {
+        let path = self
+            .url
+            .strip_prefix("sqlite://")
+            .or_else(|| self.url.strip_prefix("sqlite:"))?;
+        let path = path.split('?').next().unwrap_or(path);
+        (!path.is_empty() && path != ":memory:").then(|| PathBuf::from(path))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Database(SqlitePool);
 
 impl Database {
     pub async fn new(settings: &Settings) -> Result<Database> {
+        let db = Database::open(settings).await?;
+        db.migrate().await?;
+
+        Ok(db)
+    }
+
+    /// Connects without running migrations, creating the file if needed.
+    pub async fn open(settings: &Settings) -> Result<Database> {
         match Sqlite::database_exists(&settings.url).await? {
             true => tracing::info!("Database already exists"),
             false => Sqlite::create_database(&settings.url).await?,
         }
         let pool = SqlitePool::connect(&settings.url).await?;
 
-        sqlx::migrate!("db/migrations").run(&pool).await?;
+        Ok(Database(pool))
+    }
+
+    /// Opens an existing database file without writing to it.
+    pub async fn open_read_only(path: &Path) -> Result<Database> {
+        let options = SqliteConnectOptions::new().filename(path).read_only(true);
+        let pool = SqlitePool::connect_with(options).await?;
 
         Ok(Database(pool))
     }
+
+    /// The version of the latest migration this build knows about.
+    pub fn latest_migration() -> i64 {
+        MIGRATOR
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Applies pending migrations and returns the versions it applied.
+    pub async fn migrate(&self) -> Result<Vec<i64>> {
+        let before = self.schema_versions().await?;
+        MIGRATOR.run(&self.0).await?;
+        let after = self.schema_versions().await?;
+
+        Ok(after.into_iter().filter(|v| !before.contains(v)).collect())
+    }
+
+    /// The latest migration applied, or 0 for a fresh database.
+    pub async fn schema_version(&self) -> Result<i64> {
+        Ok(self.schema_versions().await?.last().copied().unwrap_or(0))
+    }
+
+    async fn schema_versions(&self) -> Result<Vec<i64>> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = '_sqlx_migrations')",
+        )
+        .fetch_one(&self.0)
+        .await?;
+        if !exists {
+            return Ok(vec![]);
+        }
+
+        Ok(sqlx::query_scalar(
+            "SELECT version FROM _sqlx_migrations WHERE success ORDER BY version",
+        )
+        .fetch_all(&self.0)
+        .await?)
+    }
 }
 
 impl FromRef<AppState> for Database {
@@ -0,0 +1,557 @@
+pub use shared::{ChatVote, LinkChatParams, TelegramChat, TelegramLinkCode};
+
+use crate::{
+    auth::session::{bearer_token, CurrentUser},
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    notifications::{self, NotificationEvent},
+    sqlite::Database,
+    webhooks::{self, WebhookEvent},
+    AppState,
+};
+use axum::{
+    debug_handler,
+    extract::{FromRef, FromRequestParts, Path, State},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+/// How long a club admin has to send a link code to the chat.
+const LINK_CODE_LIFETIME_MINS: i64 = 60;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Sent by the bot as an `Authorization: Bearer` token. Chats can't be
+    /// linked or unlinked while it isn't set.
+    pub bot_key: Option<String>,
+}
+
+impl FromRef<AppState> for Settings {
+    fn from_ref(state: &AppState) -> Self {
+        state.telegram.clone()
+    }
+}
+
+/// A request from the Telegram bot, which proves itself with the configured
+/// bot key.
+pub struct Bot;
+
+impl<S> FromRequestParts<S> for Bot
+where
+    Settings: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let settings = Settings::from_ref(state);
+        match (settings.bot_key.as_deref(), bearer_token(parts)) {
+            (Some(key), Some(token)) if key == token => Ok(Bot),
+            _ => Err(error_response(
+                StatusCode::UNAUTHORIZED,
+                "Only the Telegram bot can do this",
+            )),
+        }
+    }
+}
+
+/// Issues a one-time code for linking a Telegram chat to the club. Only club
+/// admins can, since the chat can then read and change the club's meetings.
+#[debug_handler]
+pub async fn create_link_code(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    if Club::from_id(club_id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+    let permission_level = sqlx::query_scalar!(
+        "SELECT permission_level FROM memberships WHERE user_id = ? AND club_id = ?",
+        user.id,
+        club_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+    if permission_level != Some(2) {
+        return Ok(error_response(
+            StatusCode::FORBIDDEN,
+            "Only club admins can link a Telegram chat",
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    sqlx::query!("DELETE FROM telegram_link_codes WHERE expires_at <= ?", now)
+        .execute(&mut *conn)
+        .await?;
+
+    let code = Uuid::new_v4().simple().to_string()[..12].to_string();
+    let expires_at = now + Duration::minutes(LINK_CODE_LIFETIME_MINS);
+    let link_code = sqlx::query_as!(
+        TelegramLinkCode,
+        r#"
+        INSERT INTO telegram_link_codes (code, club_id, expires_at)
+        VALUES (?, ?, ?)
+        RETURNING code AS "code!", club_id AS "club_id!", expires_at AS "expires_at!"
+        "#,
+        code,
+        club_id,
+        expires_at
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(link_code)).into_response())
+}
+
+/// Links the chat to the club the link code was issued for, replacing any
+/// previous link. The code can't be used again.
+#[debug_handler(state = AppState)]
+pub async fn link_chat(
+    State(db): State<Database>,
+    _: Bot,
+    Path(chat_id): Path<i64>,
+    Json(LinkChatParams { code }): Json<LinkChatParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let now = Utc::now().naive_utc();
+    let club_id = sqlx::query_scalar!(
+        r#"
+        DELETE FROM telegram_link_codes
+        WHERE code = ? AND expires_at > ?
+        RETURNING club_id AS "club_id!"
+        "#,
+        code,
+        now
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(club_id) = club_id else {
+        return Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Link code not found or expired",
+        ));
+    };
+    if Club::from_id(club_id, &mut tx).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+
+    let chat = sqlx::query_as!(
+        TelegramChat,
+        r#"
+        INSERT INTO telegram_chats (chat_id, club_id)
+        VALUES (?, ?)
+        ON CONFLICT (chat_id) DO UPDATE
+        SET club_id = excluded.club_id, linked_at = CURRENT_TIMESTAMP
+        RETURNING chat_id AS "chat_id!", club_id AS "club_id!", linked_at AS "linked_at!"
+        "#,
+        chat_id,
+        club_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(chat).into_response())
+}
+
+/// Every linked chat, for the bot to post reminders in.
+#[debug_handler]
+pub async fn get_chats(State(db): State<Database>) -> AppResult<Json<Vec<TelegramChat>>> {
+    let chats = sqlx::query_as!(
+        TelegramChat,
+        "SELECT chat_id, club_id, linked_at FROM telegram_chats ORDER BY chat_id"
+    )
+    .fetch_all(db.as_ref())
+    .await?;
+
+    Ok(Json(chats))
+}
+
+#[debug_handler]
+pub async fn get_chat(
+    State(db): State<Database>,
+    Path(chat_id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let chat = sqlx::query_as!(
+        TelegramChat,
+        "SELECT chat_id, club_id, linked_at FROM telegram_chats WHERE chat_id = ?",
+        chat_id
+    )
+    .fetch_optional(db.as_ref())
+    .await?;
+
+    match chat {
+        Some(chat) => Ok(Json(chat).into_response()),
+        None => Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Chat is not linked to a club",
+        )),
+    }
+}
+
+#[debug_handler(state = AppState)]
+pub async fn unlink_chat(
+    State(db): State<Database>,
+    _: Bot,
+    Path(chat_id): Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let result = sqlx::query!("DELETE FROM telegram_chats WHERE chat_id = ?", chat_id)
+        .execute(db.as_ref())
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Chat is not linked to a club",
+        ));
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+async fn linked_club(db: &mut SqliteConnection, chat_id: i64) -> AppResult<Option<Club>> {
+    let club_id = sqlx::query_scalar!(
+        "SELECT club_id FROM telegram_chats WHERE chat_id = ?",
+        chat_id
+    )
+    .fetch_optional(&mut *db)
+    .await?;
+
+    match club_id {
+        Some(club_id) => Ok(Club::from_id(club_id, db).await?),
+        None => Ok(None),
+    }
+}
+
+/// Tells the members and webhooks of the chat's club that a date poll opened.
+#[debug_handler(state = AppState)]
+pub async fn open_vote(
+    State(db): State<Database>,
+    _: Bot,
+    Path(chat_id): Path<i64>,
+    Json(vote): Json<ChatVote>,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(club) = linked_club(&mut tx, chat_id).await? else {
+        return Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Chat is not linked to a club",
+        ));
+    };
+
+    let dates = vote
+        .dates
+        .iter()
+        .map(|date| date.format("%a %-d %b").to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    notifications::enqueue_for_club(
+        &mut tx,
+        club.id,
+        NotificationEvent::VoteOpened,
+        &format!("Vote on the next meeting of {}", club.name),
+        &format!(
+            "A poll in {}'s Telegram chat asks which days you can make: {dates}.",
+            club.name
+        ),
+    )
+    .await?;
+    webhooks::enqueue(&mut tx, club.id, WebhookEvent::VoteOpened, &vote).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Tells the webhooks of the chat's club which date a poll settled on.
+#[debug_handler(state = AppState)]
+pub async fn close_vote(
+    State(db): State<Database>,
+    _: Bot,
+    Path(chat_id): Path<i64>,
+    Json(vote): Json<ChatVote>,
+) -> AppResult<impl IntoResponse> {
+    if vote.outcome.is_none() {
+        return Ok(error_response(
+            StatusCode::BAD_REQUEST,
+            "A closed poll needs an outcome",
+        ));
+    }
+
+    let mut tx = db.as_ref().begin().await?;
+    let Some(club) = linked_club(&mut tx, chat_id).await? else {
+        return Ok(error_response(
+            StatusCode::NOT_FOUND,
+            "Chat is not linked to a club",
+        ));
+    };
+    webhooks::enqueue(&mut tx, club.id, WebhookEvent::VoteClosed, &vote).await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::clubs::memberships::CreateMembershipParams;
+    use crate::clubs::test::{create_club_admin, create_test_club};
+    use crate::tests::{create_test_server_with_state, TEST_BOT_KEY};
+    use crate::users::test::create_test_user;
+    use axum_test::TestServer;
+    use chrono::NaiveDate;
+    use shared::CreateWebhookParams;
+
+    async fn create_link_code(server: &TestServer, cookie: &str, club_id: i64) -> String {
+        let response = server
+            .post(&format!("/clubs/{club_id}/telegram-link-code"))
+            .add_header("cookie", cookie.to_string())
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let link_code: TelegramLinkCode = response.json();
+        assert_eq!(link_code.club_id, club_id);
+        link_code.code
+    }
+
+    #[tokio::test]
+    async fn test_link_chat() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let other_club = create_test_club(&server).await;
+        let chat_id = -1001234567890_i64;
+
+        server
+            .get(&format!("/telegram/chats/{chat_id}"))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let cookie = create_club_admin(&server, &state, &[club.id, other_club.id]).await;
+        let code = create_link_code(&server, &cookie, club.id).await;
+        server
+            .put(&format!("/telegram/chats/{chat_id}"))
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams { code: code.clone() })
+            .await
+            .assert_status(StatusCode::OK);
+
+        // Codes only work once.
+        server
+            .put("/telegram/chats/42")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams { code })
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        // Linking again moves the chat to the other club.
+        let code = create_link_code(&server, &cookie, other_club.id).await;
+        server
+            .put(&format!("/telegram/chats/{chat_id}"))
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams { code })
+            .await
+            .assert_status(StatusCode::OK);
+        let chat: TelegramChat = server
+            .get(&format!("/telegram/chats/{chat_id}"))
+            .await
+            .json();
+        assert_eq!(chat.club_id, other_club.id);
+
+        let chats: Vec<TelegramChat> = server.get("/telegram/chats").await.json();
+        assert_eq!(chats.len(), 1);
+        assert_eq!(chats[0].chat_id, chat_id);
+
+        server
+            .delete(&format!("/telegram/chats/{chat_id}"))
+            .authorization_bearer(TEST_BOT_KEY)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .get(&format!("/telegram/chats/{chat_id}"))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_open_vote_notifies_members() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let code = create_link_code(&server, &cookie, club.id).await;
+        server
+            .put("/telegram/chats/42")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams { code })
+            .await
+            .assert_status(StatusCode::OK);
+
+        let vote = ChatVote {
+            poll_id: 1,
+            dates: vec![
+                NaiveDate::from_ymd_opt(2025, 3, 6).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 3, 7).unwrap(),
+            ],
+            outcome: None,
+        };
+        server
+            .post("/telegram/chats/42/votes")
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .post("/telegram/chats/43/votes")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        server
+            .post("/telegram/chats/42/votes")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let body =
+            sqlx::query_scalar!("SELECT body FROM notification_outbox WHERE event = 'vote_opened'")
+                .fetch_one(state.db.as_ref())
+                .await
+                .unwrap();
+        assert!(body.ends_with("Thu 6 Mar, Fri 7 Mar."), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_votes_are_sent_to_webhooks() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let code = create_link_code(&server, &cookie, club.id).await;
+        server
+            .put("/telegram/chats/42")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams { code })
+            .await
+            .assert_status(StatusCode::OK);
+        server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie)
+            .json(&CreateWebhookParams {
+                url: "http://localhost:8080/hook".to_string(),
+                events: Some(vec![WebhookEvent::VoteOpened, WebhookEvent::VoteClosed]),
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 6).unwrap();
+        let mut vote = ChatVote {
+            poll_id: 1,
+            dates: vec![date],
+            outcome: None,
+        };
+        server
+            .post("/telegram/chats/42/votes")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+        server
+            .post("/telegram/chats/42/votes/closed")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        vote.outcome = date.and_hms_opt(19, 0, 0);
+        server
+            .post("/telegram/chats/42/votes/closed")
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .post("/telegram/chats/42/votes/closed")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&vote)
+            .await
+            .assert_status(StatusCode::NO_CONTENT);
+
+        let events = sqlx::query_scalar!("SELECT event FROM webhook_deliveries ORDER BY id")
+            .fetch_all(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(events, ["vote.opened", "vote.closed"]);
+    }
+
+    #[tokio::test]
+    async fn test_link_chat_unknown_code() {
+        let (server, _) = create_test_server_with_state().await;
+
+        let response = server
+            .put("/telegram/chats/42")
+            .authorization_bearer(TEST_BOT_KEY)
+            .json(&LinkChatParams {
+                code: "42".to_string(),
+            })
+            .await;
+        response.assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_link_chat_requires_the_bot() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let code = create_link_code(&server, &cookie, club.id).await;
+
+        server
+            .put("/telegram/chats/42")
+            .json(&LinkChatParams { code: code.clone() })
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .put("/telegram/chats/42")
+            .authorization_bearer("not the bot key")
+            .json(&LinkChatParams { code })
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .delete("/telegram/chats/42")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_link_code_requires_club_admin() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let user = create_test_user(&server).await;
+
+        server
+            .post(&format!("/clubs/{}/telegram-link-code", club.id))
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+        let session = create_session(state.db.as_ref(), user.id).await.unwrap();
+        server
+            .post(&format!("/clubs/{}/telegram-link-code", club.id))
+            .add_header("cookie", format!("{SESSION_COOKIE}={session}"))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, LocalResult, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::{auth::session::CurrentUser, error::error_response, sqlite::Database};
+
+/// Parses an IANA timezone name such as `Europe/London`.
+pub fn parse_timezone(name: &str) -> Result<Tz, (StatusCode, String)> {
+    name.parse::<Tz>().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Unknown timezone '{name}'"),
+        )
+    })
+}
+
+/// Converts a wall-clock time in `tz` to UTC.
+///
+/// Times skipped by a DST transition are moved forward by the size of the gap
+/// and times that happen twice resolve to the first of the two, which is what
+/// people showing up at "19:00" would expect.
+pub fn local_to_utc(tz: &Tz, local: NaiveDateTime) -> NaiveDateTime {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) => time.naive_utc(),
+        LocalResult::Ambiguous(earliest, _) => earliest.naive_utc(),
+        // Moving the time forward by the gap and then applying the offset
+        // after it comes down to applying the offset from before the gap.
+        LocalResult::None => {
+            let before = tz.offset_from_utc_datetime(&(local - Duration::days(1)));
+            local - Duration::seconds(before.fix().local_minus_utc().into())
+        }
+    }
+}
+
+/// The timezone times should be rendered in for this request: the `tz` query
+/// parameter if given, otherwise the logged in user's timezone. `None` leaves
+/// the choice to the handler, e.g. the club's own timezone.
+#[derive(Debug)]
+pub struct RequesterTimezone(pub Option<Tz>);
+
+#[derive(Deserialize)]
+struct TimezoneQuery {
+    tz: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for RequesterTimezone
+where
+    Database: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<TimezoneQuery>::try_from_uri(&parts.uri)
+            .map_err(IntoResponse::into_response)?;
+        if let Some(tz) = query.tz {
+            let tz =
+                parse_timezone(&tz).map_err(|(status, message)| error_response(status, message))?;
+            return Ok(RequesterTimezone(Some(tz)));
+        }
+
+        let user = <CurrentUser as OptionalFromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let tz = user
+            .and_then(|CurrentUser(user)| user.timezone)
+            .and_then(|tz| tz.parse::<Tz>().ok());
+
+        Ok(RequesterTimezone(tz))
+    }
+}
+
+impl RequesterTimezone {
+    pub fn or(self, fallback: Tz) -> Tz {
+        self.0.unwrap_or(fallback)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_local_to_utc_across_dst() {
+        let london: Tz = "Europe/London".parse().unwrap();
+        assert_eq!(
+            local_to_utc(&london, local(2030, 3, 28, 19, 0)),
+            local(2030, 3, 28, 19, 0)
+        );
+        assert_eq!(
+            local_to_utc(&london, local(2030, 4, 4, 19, 0)),
+            local(2030, 4, 4, 18, 0)
+        );
+    }
+
+    #[test]
+    fn test_local_to_utc_in_dst_gap() {
+        // Clocks in New York jump from 02:00 to 03:00 on 2030-03-10.
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(
+            local_to_utc(&new_york, local(2030, 3, 10, 2, 30)),
+            local(2030, 3, 10, 7, 30)
+        );
+    }
+
+    #[test]
+    fn test_local_to_utc_in_skipped_day() {
+        // Samoa skipped 2011-12-30 when it moved from UTC-10 to UTC+14.
+        let apia: Tz = "Pacific/Apia".parse().unwrap();
+        let utc = local_to_utc(&apia, local(2011, 12, 30, 19, 0));
+        assert_eq!(utc, local(2011, 12, 31, 5, 0));
+        assert_eq!(
+            apia.from_utc_datetime(&utc).naive_local(),
+            local(2011, 12, 31, 19, 0)
+        );
+    }
+
+    #[test]
+    fn test_local_to_utc_ambiguous() {
+        // 01:30 happens twice in New York on 2030-11-03, first at UTC-4.
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        assert_eq!(
+            local_to_utc(&new_york, local(2030, 11, 3, 1, 30)),
+            local(2030, 11, 3, 5, 30)
+        );
+    }
+}
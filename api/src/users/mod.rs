@@ -1,26 +1,23 @@
 mod user;
 
-use serde::{Deserialize, Serialize};
+pub use shared::{CreateUserParams, FindUserParams, UpdateUserParams};
 pub use user::*;
 
-use crate::error::AppResult;
+use crate::error::{error_response, AppResult};
 use axum::{
     debug_handler,
-    extract::{Path, Query, State},
+    extract::{OriginalUri, Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
 use sqlx::Row;
 
-use crate::sqlite::Database;
-
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CreateUserParams {
-    pub email: String,
-    pub first_name: String,
-    pub last_name: String,
-}
+use crate::{
+    pagination::{self, PageParams},
+    sqlite::Database,
+    timezone::parse_timezone,
+};
 
 #[debug_handler]
 #[tracing::instrument(skip(db))]
@@ -28,33 +25,46 @@ pub async fn create_user(
     State(db): State<Database>,
     Json(params): Json<CreateUserParams>,
 ) -> AppResult<impl IntoResponse> {
+    if let Some(Err((status, message))) = params.timezone.as_deref().map(parse_timezone) {
+        return Ok(error_response(status, message));
+    }
+
     let user: User = sqlx::query_as(
         r#"
-        INSERT INTO users (email, first_name, last_name)
-        VALUES (?, ?, ?)    
+        INSERT INTO users (email, first_name, last_name, timezone)
+        VALUES (?, ?, ?, ?)
         RETURNING *
         "#,
     )
     .bind(&params.email)
     .bind(&params.first_name)
     .bind(&params.last_name)
+    .bind(&params.timezone)
     .fetch_one(db.as_ref())
     .await?;
 
-    Ok(Json(user))
+    Ok(Json(user).into_response())
 }
 
 #[debug_handler]
 #[tracing::instrument(skip(db))]
-pub async fn get_users(State(db): State<Database>) -> AppResult<Json<Vec<User>>> {
+pub async fn get_users(
+    State(db): State<Database>,
+    Query(params): Query<PageParams>,
+    OriginalUri(uri): OriginalUri,
+) -> AppResult<Response> {
+    let (limit, offset) = pagination::bounds(&params);
     let users = sqlx::query(
         r#"
-        SELECT id, email, first_name, last_name, 
+        SELECT id, email, first_name, last_name, timezone,
                created_at, updated_at
         FROM users
         ORDER BY id
+        LIMIT ? OFFSET ?
         "#,
     )
+    .bind(limit + 1)
+    .bind(offset)
     .fetch_all(db.as_ref())
     .await?
     .into_iter()
@@ -63,12 +73,13 @@ pub async fn get_users(State(db): State<Database>) -> AppResult<Json<Vec<User>>>
         email: row.get("email"),
         first_name: row.get("first_name"),
         last_name: row.get("last_name"),
+        timezone: row.get("timezone"),
         created_at: row.get("created_at"),
         updated_at: row.get("updated_at"),
     })
     .collect::<Vec<_>>();
 
-    Ok(Json(users))
+    Ok(pagination::page(users, &params, &uri))
 }
 
 #[debug_handler]
@@ -80,7 +91,7 @@ pub async fn get_user_by_id(
     let user = sqlx::query_as!(
         User,
         r#"
-        SELECT id, email, first_name, last_name, 
+        SELECT id, email, first_name, last_name, timezone,
                created_at, updated_at
         FROM users WHERE id = ?
         "#,
@@ -91,16 +102,10 @@ pub async fn get_user_by_id(
 
     match user {
         Some(user) => Ok(Json(user).into_response()),
-        None => Ok((StatusCode::NOT_FOUND, "User not found").into_response()),
+        None => Ok(error_response(StatusCode::NOT_FOUND, "User not found")),
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateUserParams {
-    pub email: Option<String>,
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
-}
 #[debug_handler]
 #[tracing::instrument(skip(db))]
 pub async fn update_user(
@@ -108,6 +113,10 @@ pub async fn update_user(
     Path(id): Path<i64>,
     Json(params): Json<UpdateUserParams>,
 ) -> AppResult<impl IntoResponse> {
+    if let Some(Err((status, message))) = params.timezone.as_deref().map(parse_timezone) {
+        return Ok(error_response(status, message));
+    }
+
     let mut query = sqlx::QueryBuilder::new(
         r#"
         UPDATE users SET 
@@ -126,6 +135,10 @@ pub async fn update_user(
         separated.push("last_name = ");
         separated.push_bind_unseparated(last_name);
     }
+    if let Some(timezone) = params.timezone {
+        separated.push("timezone = ");
+        separated.push_bind_unseparated(timezone);
+    }
     query.push(" WHERE id = ");
     query.push_bind(id);
     tracing::debug!("Query: {}", query.sql());
@@ -135,7 +148,7 @@ pub async fn update_user(
     let user = sqlx::query_as!(
         User,
         r#"
-        SELECT id, email, first_name, last_name, 
+        SELECT id, email, first_name, last_name, timezone,
                created_at, updated_at
         FROM users WHERE id = ?
         "#,
@@ -144,7 +157,7 @@ pub async fn update_user(
     .fetch_one(db.as_ref())
     .await?;
 
-    Ok(Json(user))
+    Ok(Json(user).into_response())
 }
 
 #[debug_handler]
@@ -158,35 +171,84 @@ pub async fn delete_user(
         .await?;
 
     match result.rows_affected() {
-        0 => Ok((StatusCode::NOT_FOUND, "User not found")),
-        1 => Ok((StatusCode::NO_CONTENT, "User successfully deleted")),
-        _ => Ok((StatusCode::INTERNAL_SERVER_ERROR, "Multiple users deleted")),
+        0 => Ok(error_response(StatusCode::NOT_FOUND, "User not found")),
+        1 => Ok((StatusCode::NO_CONTENT, "User successfully deleted").into_response()),
+        _ => Ok(error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Multiple users deleted",
+        )),
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct FindUserParams {
-    pub email: Option<String>,
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
+/// How many users a search returns unless asked for fewer.
+const MAX_RESULTS: usize = 50;
+
+/// `text` as a `LIKE` pattern matching it literally, with `\` as the escape.
+fn like_literal(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
+
 #[debug_handler]
 #[tracing::instrument(skip(db))]
 pub async fn find_users(
     Query(params): Query<FindUserParams>,
     State(db): State<Database>,
 ) -> Response {
-    if params.email.is_none() && params.first_name.is_none() && params.last_name.is_none() {
-        return (StatusCode::BAD_REQUEST, "No search parameters provided").into_response();
+    let q = params
+        .q
+        .as_deref()
+        .map(|q| q.trim().to_lowercase())
+        .filter(|q| !q.is_empty());
+    if q.is_none()
+        && params.email.is_none()
+        && params.first_name.is_none()
+        && params.last_name.is_none()
+        && params.not_in_club.is_none()
+    {
+        return error_response(StatusCode::BAD_REQUEST, "No search parameters provided");
     }
+    let limit = params.limit.unwrap_or(MAX_RESULTS).min(MAX_RESULTS) as i64;
+
     let mut query = sqlx::QueryBuilder::new(
         r#"
-        SELECT id, email, first_name, last_name, 
-               created_at, updated_at
-        FROM users WHERE 
+        SELECT id, email, first_name, last_name, timezone,
+               created_at, updated_at,
         "#,
     );
+    // Exact matches first, then names and emails starting with the search,
+    // then the rest.
+    match &q {
+        Some(q) => {
+            let prefix = format!("{}%", like_literal(q));
+            query
+                .push("CASE WHEN lower(email) = ")
+                .push_bind(q.clone())
+                .push(" OR lower(first_name || ' ' || last_name) = ")
+                .push_bind(q.clone())
+                .push(" THEN 0 WHEN first_name LIKE ")
+                .push_bind(prefix.clone())
+                .push(r" ESCAPE '\' OR last_name LIKE ")
+                .push_bind(prefix.clone())
+                .push(r" ESCAPE '\' OR first_name || ' ' || last_name LIKE ")
+                .push_bind(prefix.clone())
+                .push(r" ESCAPE '\' THEN 1 WHEN email LIKE ")
+                .push_bind(prefix)
+                .push(r" ESCAPE '\' THEN 2 ELSE 3 END AS rank");
+        }
+        None => {
+            query.push("0 AS rank");
+        }
+    }
+    query.push(" FROM users WHERE ");
+
     let mut separated = query.separated(" AND ");
+    for word in q.iter().flat_map(|q| q.split_whitespace()) {
+        separated.push("first_name || ' ' || last_name || ' ' || email LIKE ");
+        separated.push_bind_unseparated(format!("%{}%", like_literal(word)));
+        separated.push_unseparated(r" ESCAPE '\'");
+    }
     if let Some(email) = params.email {
         separated.push("email = ");
         separated.push_bind_unseparated(email);
@@ -199,6 +261,14 @@ pub async fn find_users(
         separated.push("last_name = ");
         separated.push_bind_unseparated(last_name);
     }
+    if let Some(club_id) = params.not_in_club {
+        separated.push("id NOT IN (SELECT user_id FROM memberships WHERE club_id = ");
+        separated.push_bind_unseparated(club_id);
+        separated.push_unseparated(")");
+    }
+    query
+        .push(" ORDER BY rank, first_name, last_name, id LIMIT ")
+        .push_bind(limit);
 
     tracing::debug!("Query: {}", query.sql());
 
@@ -214,20 +284,17 @@ pub async fn find_users(
                     email: row.get("email"),
                     first_name: row.get("first_name"),
                     last_name: row.get("last_name"),
+                    timezone: row.get("timezone"),
                     created_at: row.get("created_at"),
                     updated_at: row.get("updated_at"),
                 })
                 .collect::<Vec<_>>();
 
-            if users.is_empty() {
-                (StatusCode::NOT_FOUND, "No users found").into_response()
-            } else {
-                (StatusCode::OK, Json(users)).into_response()
-            }
+            (StatusCode::OK, Json(users)).into_response()
         }
         Err(e) => {
             tracing::error!("Error fetching users: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Error fetching users").into_response()
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "Error fetching users")
         }
     }
 }
@@ -252,6 +319,7 @@ pub mod test {
                 email: "test@example.com".to_string(),
                 first_name: "Test".to_string(),
                 last_name: "User".to_string(),
+                timezone: None,
             },
         )
         .await
@@ -301,6 +369,7 @@ pub mod test {
                 email: Some("updated@example.com".to_string()),
                 first_name: Some("Updated".to_string()),
                 last_name: Some("Name".to_string()),
+                timezone: Some("Europe/Berlin".to_string()),
             })
             .await;
 
@@ -309,6 +378,7 @@ pub mod test {
         assert_eq!(updated_user.email, "updated@example.com");
         assert_eq!(updated_user.first_name, "Updated");
         assert_eq!(updated_user.last_name, "Name");
+        assert_eq!(updated_user.timezone.as_deref(), Some("Europe/Berlin"));
     }
 
     #[tokio::test]
@@ -326,6 +396,10 @@ pub mod test {
         // Verify the user is deleted
         let response = server.get(&format!("/users/{}", id)).await;
         response.assert_status(StatusCode::NOT_FOUND);
+        response.assert_json(&shared::ErrorResponse {
+            status: 404,
+            message: "User not found".to_string(),
+        });
     }
 
     #[tokio::test]
@@ -384,7 +458,9 @@ pub mod test {
             .get("/users/search")
             .add_query_param("email", "nonexistent@example.com")
             .await;
-        assert_eq!(response.status_code(), 404);
+        response.assert_status_ok();
+        let users: Vec<User> = response.json();
+        assert!(users.is_empty());
     }
 
     #[tokio::test]
@@ -398,6 +474,7 @@ pub mod test {
                 email: "test2@example.com".to_string(),
                 first_name: format!("{}2", user.first_name),
                 last_name: user.last_name.clone(),
+                timezone: None,
             },
         )
         .await;
@@ -407,6 +484,7 @@ pub mod test {
                 email: "test3@example.com".to_string(),
                 first_name: user.first_name.clone(),
                 last_name: format!("{}2", user.last_name),
+                timezone: None,
             },
         )
         .await;
@@ -438,6 +516,101 @@ pub mod test {
             ))
             .await;
 
-        response.assert_status(StatusCode::NOT_FOUND);
+        response.assert_status(StatusCode::OK);
+        let users: Vec<User> = response.json();
+        assert!(users.is_empty());
+    }
+
+    async fn search(server: &TestServer, params: &[(&str, String)]) -> Vec<String> {
+        let mut request = server.get("/users/search");
+        for (name, value) in params {
+            request = request.add_query_param(name, value);
+        }
+        let response = request.await;
+        response.assert_status_ok();
+        response
+            .json::<Vec<User>>()
+            .into_iter()
+            .map(|user| user.email)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_users() {
+        let server = create_test_server().await;
+        for (email, first_name, last_name) in [
+            ("hannah@example.com", "Hannah", "Jones"),
+            ("bob@example.com", "Bob", "Annable"),
+            ("anna@example.com", "Anna", "Smith"),
+            ("100%_real@example.com", "Carl", "Percent"),
+        ] {
+            create_user(
+                &server,
+                CreateUserParams {
+                    email: email.to_string(),
+                    first_name: first_name.to_string(),
+                    last_name: last_name.to_string(),
+                    timezone: None,
+                },
+            )
+            .await;
+        }
+
+        // Prefixes of either name come before matches inside them.
+        let found = search(&server, &[("q", "ANN".to_string())]).await;
+        assert_eq!(
+            found,
+            ["anna@example.com", "bob@example.com", "hannah@example.com"]
+        );
+        let found = search(&server, &[("q", "smith an".to_string())]).await;
+        assert_eq!(found, ["anna@example.com"]);
+        let found = search(&server, &[("q", "anna@example.com".to_string())]).await;
+        assert_eq!(found, ["anna@example.com"]);
+        let found = search(&server, &[("q", "%_".to_string())]).await;
+        assert_eq!(found, ["100%_real@example.com"]);
+        let found = search(
+            &server,
+            &[("q", "ann".to_string()), ("limit", "1".to_string())],
+        )
+        .await;
+        assert_eq!(found, ["anna@example.com"]);
+        let found = search(&server, &[("q", "nobody".to_string())]).await;
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_users_not_in_club() {
+        let server = create_test_server().await;
+        let club = crate::clubs::test::create_test_club(&server).await;
+        let member = create_test_user(&server).await;
+        let other = create_user(
+            &server,
+            CreateUserParams {
+                email: "other@example.com".to_string(),
+                first_name: member.first_name.clone(),
+                last_name: member.last_name.clone(),
+                timezone: None,
+            },
+        )
+        .await;
+        server
+            .post("/memberships")
+            .json(&shared::CreateMembershipParams {
+                user_id: member.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let found = search(
+            &server,
+            &[
+                ("q", member.first_name.clone()),
+                ("not_in_club", club.id.to_string()),
+            ],
+        )
+        .await;
+        assert_eq!(found, [other.email]);
     }
 }
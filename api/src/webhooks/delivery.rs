@@ -0,0 +1,306 @@
+pub use shared::WebhookDelivery;
+
+use std::{net::IpAddr, time::Duration as StdDuration};
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{redirect, Url};
+use serde::Serialize;
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::{SqliteConnection, SqlitePool};
+
+use super::{Settings, Webhook, WebhookEvent};
+use crate::{error::AppResult, retry, sqlite::Database};
+
+pub const EVENT_HEADER: &str = "X-Bookclub-Event";
+pub const DELIVERY_HEADER: &str = "X-Bookclub-Delivery";
+/// `sha256=` followed by the hex encoded HMAC-SHA256 of the request body,
+/// keyed with the webhook's secret.
+pub const SIGNATURE_HEADER: &str = "X-Bookclub-Signature";
+
+/// Retries back off exponentially from this delay.
+const FIRST_RETRY_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 60 * 60;
+const DELIVERY_BATCH_SIZE: i64 = 50;
+
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Queues a delivery of the event to every webhook of the club subscribed to it.
+/// Call it in the same transaction as the change so the two can't disagree.
+pub async fn enqueue(
+    db: &mut SqliteConnection,
+    club_id: i64,
+    event: WebhookEvent,
+    data: impl Serialize,
+) -> AppResult<()> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT id AS "id!", club_id, url, events, created_at
+        FROM webhooks
+        WHERE club_id = ?
+        "#,
+        club_id
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let now = Utc::now();
+    let payload = json!({
+        "event": event,
+        "club_id": club_id,
+        "created_at": now,
+        "data": data,
+    })
+    .to_string();
+    let event_name = event.as_str();
+    let now = now.naive_utc();
+
+    for webhook in webhooks
+        .iter()
+        .filter(|webhook| webhook.subscribes_to(event))
+    {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload, next_attempt_at)
+            VALUES (?, ?, ?, ?)
+            "#,
+            webhook.id,
+            event_name,
+            payload,
+            now
+        )
+        .execute(&mut *db)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Whether the address is on this machine or its network: loopback, private,
+/// link-local or unspecified.
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal(IpAddr::V4(ip)),
+            None => {
+                let unique_local = ip.segments()[0] & 0xfe00 == 0xfc00;
+                let link_local = ip.segments()[0] & 0xffc0 == 0xfe80;
+                ip.is_loopback() || ip.is_unspecified() || unique_local || link_local
+            }
+        },
+    }
+}
+
+/// Posts queued deliveries to the webhooks.
+#[derive(Clone)]
+pub struct Dispatcher {
+    client: reqwest::Client,
+    pub max_attempts: i64,
+    pub poll_interval: StdDuration,
+    pub allow_private_targets: bool,
+}
+
+impl Dispatcher {
+    pub fn new(settings: &Settings) -> Result<Dispatcher> {
+        // A redirect could lead to an address `check_target` would refuse.
+        let client = reqwest::Client::builder()
+            .timeout(StdDuration::from_secs(settings.timeout_secs))
+            .redirect(redirect::Policy::none())
+            .build()?;
+
+        Ok(Dispatcher {
+            client,
+            max_attempts: settings.max_attempts,
+            poll_interval: StdDuration::from_secs(settings.poll_interval_secs),
+            allow_private_targets: settings.allow_private_targets,
+        })
+    }
+
+    /// Resolves the URL's host and refuses it if any of its addresses is
+    /// internal, so webhooks can't be used to reach the server's own network.
+    pub async fn check_target(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("Webhook URL must be an http(s) URL".to_string());
+        }
+        if self.allow_private_targets {
+            return Ok(());
+        }
+
+        // Looking up an IP literal just parses it.
+        let Some(host) = url.host_str() else {
+            return Err("Webhook URL must have a host".to_string());
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addresses: Vec<IpAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("Could not resolve {host}: {e}"))?
+            .map(|address| address.ip())
+            .collect();
+        if addresses.is_empty() || addresses.into_iter().any(is_internal) {
+            return Err("Webhook URL must point to a public address".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub async fn run(self, db: Database) {
+        let mut interval = tokio::time::interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.deliver_pending(db.as_ref()).await {
+                tracing::error!("Error delivering webhooks: {:?}", e);
+            }
+        }
+    }
+
+    /// Sends the deliveries that are due. Anything but a 2xx response counts as a
+    /// failure and is retried with exponential backoff until `max_attempts` is
+    /// reached. Returns the number of successful deliveries.
+    pub async fn deliver_pending(&self, db: &SqlitePool) -> AppResult<usize> {
+        let now = Utc::now().naive_utc();
+        let due = sqlx::query!(
+            r#"
+            SELECT webhook_deliveries.id AS "id!", webhook_deliveries.event,
+                   webhook_deliveries.payload, webhook_deliveries.attempts, webhooks.url,
+                   webhooks.secret
+            FROM webhook_deliveries
+            JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+            WHERE status = 'pending' AND next_attempt_at <= ?
+            ORDER BY next_attempt_at
+            LIMIT ?
+            "#,
+            now,
+            DELIVERY_BATCH_SIZE
+        )
+        .fetch_all(db)
+        .await?;
+
+        let mut delivered = 0;
+        for delivery in due {
+            let target = match Url::parse(&delivery.url) {
+                Ok(url) => self.check_target(&url).await,
+                Err(e) => Err(e.to_string()),
+            };
+            if let Err(error) = target {
+                self.record_failure(db, delivery.id, delivery.attempts, None, error, now)
+                    .await?;
+                continue;
+            }
+
+            let response = self
+                .client
+                .post(&delivery.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, &delivery.event)
+                .header(DELIVERY_HEADER, delivery.id)
+                .header(
+                    SIGNATURE_HEADER,
+                    sign(&delivery.secret, delivery.payload.as_bytes()),
+                )
+                .body(delivery.payload)
+                .send()
+                .await;
+
+            let (response_status, error) = match response {
+                Ok(response) if response.status().is_success() => {
+                    (Some(response.status().as_u16() as i64), None)
+                }
+                Ok(response) => (
+                    Some(response.status().as_u16() as i64),
+                    Some(format!("Endpoint responded with {}", response.status())),
+                ),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let attempts = delivery.attempts + 1;
+
+            match error {
+                None => {
+                    let delivered_at = Utc::now().naive_utc();
+                    sqlx::query!(
+                        r#"
+                        UPDATE webhook_deliveries
+                        SET status = 'delivered', attempts = ?, response_status = ?,
+                            last_error = NULL, delivered_at = ?
+                        WHERE id = ?
+                        "#,
+                        attempts,
+                        response_status,
+                        delivered_at,
+                        delivery.id
+                    )
+                    .execute(db)
+                    .await?;
+                    delivered += 1;
+                }
+                Some(error) => {
+                    self.record_failure(
+                        db,
+                        delivery.id,
+                        delivery.attempts,
+                        response_status,
+                        error,
+                        now,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+    /// Schedules a retry of the failed attempt, or gives up after `max_attempts`.
+    async fn record_failure(
+        &self,
+        db: &SqlitePool,
+        id: i64,
+        attempts: i64,
+        response_status: Option<i64>,
+        error: String,
+        now: NaiveDateTime,
+    ) -> AppResult<()> {
+        tracing::warn!("Error delivering webhook {}: {}", id, error);
+        let attempts = attempts + 1;
+        let status = if attempts >= self.max_attempts {
+            "failed"
+        } else {
+            "pending"
+        };
+        let next_attempt_at = now
+            + retry::backoff(
+                attempts,
+                Duration::seconds(FIRST_RETRY_SECS),
+                Duration::seconds(MAX_RETRY_SECS),
+            );
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = ?, attempts = ?, response_status = ?, last_error = ?,
+                next_attempt_at = ?
+            WHERE id = ?
+            "#,
+            status,
+            attempts,
+            response_status,
+            error,
+            next_attempt_at,
+            id
+        )
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,587 @@
+mod delivery;
+mod event;
+mod webhook;
+
+pub use delivery::*;
+pub use event::*;
+pub use shared::{CreateWebhookParams, CreatedWebhook, DeliveryLogParams};
+pub use webhook::*;
+
+use crate::{
+    auth::session::CurrentUser,
+    clubs::{Club, ClubExt},
+    error::{error_response, AppResult},
+    sqlite::Database,
+    AppState,
+};
+use axum::{
+    debug_handler,
+    extract::{FromRef, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::SqliteConnection;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub max_attempts: i64,
+    pub poll_interval_secs: u64,
+    pub timeout_secs: u64,
+    /// Lets webhooks target loopback, private and link-local addresses. Only
+    /// the tests turn this on, for their local receivers.
+    pub allow_private_targets: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_attempts: 8,
+            poll_interval_secs: 10,
+            timeout_secs: 10,
+            allow_private_targets: false,
+        }
+    }
+}
+
+/// Whether the user is one of the club's admins, who manage its webhooks.
+async fn is_club_admin(user_id: i64, club_id: i64, db: &mut SqliteConnection) -> AppResult<bool> {
+    let permission_level = sqlx::query_scalar!(
+        "SELECT permission_level FROM memberships WHERE user_id = ? AND club_id = ?",
+        user_id,
+        club_id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(permission_level == Some(2))
+}
+
+impl FromRef<AppState> for Dispatcher {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhooks.clone()
+    }
+}
+
+const NOT_ADMIN: &str = "Only club admins can manage the club's webhooks";
+
+#[debug_handler(state = AppState)]
+pub async fn create_webhook(
+    State(db): State<Database>,
+    State(dispatcher): State<Dispatcher>,
+    Path(club_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+    Json(params): Json<CreateWebhookParams>,
+) -> AppResult<impl IntoResponse> {
+    let target = match reqwest::Url::parse(&params.url) {
+        Ok(url) => dispatcher.check_target(&url).await,
+        Err(_) => Err("Webhook URL must be an http(s) URL".to_string()),
+    };
+    if let Err(message) = target {
+        return Ok(error_response(StatusCode::BAD_REQUEST, message));
+    }
+
+    let mut conn = db.as_ref().acquire().await?;
+    if Club::from_id(club_id, &mut conn).await?.is_none() {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Club not found"));
+    }
+    if !is_club_admin(user.id, club_id, &mut conn).await? {
+        return Ok(error_response(StatusCode::FORBIDDEN, NOT_ADMIN));
+    }
+
+    let secret = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let events = params.events.map(|events| {
+        events
+            .iter()
+            .map(WebhookEvent::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhooks (club_id, url, secret, events)
+        VALUES (?, ?, ?, ?)
+        RETURNING id AS "id!", club_id AS "club_id!", url AS "url!", events,
+                  created_at AS "created_at!"
+        "#,
+        club_id,
+        params.url,
+        secret,
+        events
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhook { webhook, secret }),
+    )
+        .into_response())
+}
+
+#[debug_handler]
+pub async fn get_club_webhooks(
+    State(db): State<Database>,
+    Path(club_id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+    if !is_club_admin(user.id, club_id, &mut conn).await? {
+        return Ok(error_response(StatusCode::FORBIDDEN, NOT_ADMIN));
+    }
+
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT id AS "id!", club_id, url, events, created_at
+        FROM webhooks
+        WHERE club_id = ?
+        ORDER BY id
+        "#,
+        club_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(webhooks).into_response())
+}
+
+/// Unregisters the webhook along with its delivery log.
+#[debug_handler]
+pub async fn delete_webhook(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+) -> AppResult<impl IntoResponse> {
+    let mut tx = db.as_ref().begin().await?;
+
+    let Some(webhook) = Webhook::from_id(id, &mut tx).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Webhook not found"));
+    };
+    if !is_club_admin(user.id, webhook.club_id, &mut tx).await? {
+        return Ok(error_response(StatusCode::FORBIDDEN, NOT_ADMIN));
+    }
+
+    sqlx::query!("DELETE FROM webhook_deliveries WHERE webhook_id = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query!("DELETE FROM webhooks WHERE id = ?", id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// The webhook's deliveries, newest first.
+#[debug_handler]
+pub async fn get_webhook_deliveries(
+    State(db): State<Database>,
+    Path(id): Path<i64>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<DeliveryLogParams>,
+) -> AppResult<impl IntoResponse> {
+    let mut conn = db.as_ref().acquire().await?;
+
+    let Some(webhook) = Webhook::from_id(id, &mut conn).await? else {
+        return Ok(error_response(StatusCode::NOT_FOUND, "Webhook not found"));
+    };
+    if !is_club_admin(user.id, webhook.club_id, &mut conn).await? {
+        return Ok(error_response(StatusCode::FORBIDDEN, NOT_ADMIN));
+    }
+
+    let deliveries = sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT id AS "id!", webhook_id, event, payload, status, attempts, response_status,
+               last_error, next_attempt_at, created_at, delivered_at
+        FROM webhook_deliveries
+        WHERE webhook_id = ?1 AND (?2 IS NULL OR status = ?2)
+        ORDER BY id DESC
+        LIMIT 100
+        "#,
+        id,
+        params.status
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(Json(deliveries).into_response())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::session::{create_session, SESSION_COOKIE};
+    use crate::clubs::memberships::CreateMembershipParams;
+    use crate::clubs::test::{create_club_admin, create_test_club};
+    use crate::create_router;
+    use crate::tests::{create_test_server_with_state, create_test_state};
+    use crate::users::test::create_user;
+    use crate::users::CreateUserParams;
+    use axum::{body::Bytes, http::HeaderMap, routing::post, Router};
+    use axum_test::TestServer;
+    use std::sync::{
+        atomic::{AtomicU16, Ordering},
+        Arc, Mutex,
+    };
+    use tokio::net::TcpListener;
+
+    /// Stands in for a club's webhook endpoint, recording what it receives and
+    /// answering with `status`.
+    #[derive(Clone, Default)]
+    struct Receiver {
+        requests: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+        status: Arc<AtomicU16>,
+    }
+
+    impl Receiver {
+        async fn start(status: StatusCode) -> (Receiver, String) {
+            let receiver = Receiver::default();
+            receiver.status.store(status.as_u16(), Ordering::SeqCst);
+
+            let state = receiver.clone();
+            let app = Router::new().route(
+                "/hook",
+                post(move |headers: HeaderMap, body: Bytes| {
+                    let state = state.clone();
+                    async move {
+                        state.requests.lock().unwrap().push((headers, body));
+                        StatusCode::from_u16(state.status.load(Ordering::SeqCst)).unwrap()
+                    }
+                }),
+            );
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/hook", listener.local_addr().unwrap());
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+            (receiver, url)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery() {
+        let (server, state) = create_test_server_with_state().await;
+        let (receiver, url) = Receiver::start(StatusCode::OK).await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let user = create_user(
+            &server,
+            CreateUserParams {
+                email: "member@example.com".to_string(),
+                first_name: "Member".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+
+        let response = server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie.clone())
+            .json(&CreateWebhookParams {
+                url,
+                events: Some(vec![WebhookEvent::MembershipCreated]),
+            })
+            .await;
+        response.assert_status(StatusCode::CREATED);
+        let created: CreatedWebhook = response.json();
+
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let delivered = state
+            .webhooks
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(delivered, 1);
+
+        let requests = receiver.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        let (headers, body) = &requests[0];
+        assert_eq!(headers[EVENT_HEADER], "membership.created");
+        assert_eq!(
+            headers[SIGNATURE_HEADER],
+            sign(&created.secret, body).as_str()
+        );
+        let payload: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["event"], "membership.created");
+        assert_eq!(payload["data"]["user_id"], user.id);
+
+        let deliveries: Vec<WebhookDelivery> = server
+            .get(&format!("/webhooks/{}/deliveries", created.webhook.id))
+            .add_header("cookie", cookie)
+            .await
+            .json();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].status, "delivered");
+        assert_eq!(deliveries[0].response_status, Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried() {
+        let (server, state) = create_test_server_with_state().await;
+        let (receiver, url) = Receiver::start(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let user = create_user(
+            &server,
+            CreateUserParams {
+                email: "member@example.com".to_string(),
+                first_name: "Member".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+
+        let created: CreatedWebhook = server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie.clone())
+            .json(&CreateWebhookParams { url, events: None })
+            .await
+            .json();
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        let delivered = state
+            .webhooks
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(delivered, 0);
+        assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+
+        let response = server
+            .get(&format!("/webhooks/{}/deliveries", created.webhook.id))
+            .add_header("cookie", cookie)
+            .add_query_param("status", "pending")
+            .await;
+        let deliveries: Vec<WebhookDelivery> = response.json();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].attempts, 1);
+        assert_eq!(deliveries[0].response_status, Some(500));
+        assert!(deliveries[0].next_attempt_at > chrono::Utc::now().naive_utc());
+
+        // Backing off, so the endpoint isn't called again right away.
+        state
+            .webhooks
+            .deliver_pending(state.db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(receiver.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_invalid_url() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+
+        let response = server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie)
+            .json(&CreateWebhookParams {
+                url: "ftp://example.com/hook".to_string(),
+                events: None,
+            })
+            .await;
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_require_a_club_admin() {
+        let (server, state) = create_test_server_with_state().await;
+        let club = create_test_club(&server).await;
+        let admin = create_club_admin(&server, &state, &[club.id]).await;
+        let created: CreatedWebhook = server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", admin)
+            .json(&CreateWebhookParams {
+                url: "http://example.com/hook".to_string(),
+                events: None,
+            })
+            .await
+            .json();
+
+        let member = create_user(
+            &server,
+            CreateUserParams {
+                email: "member@example.com".to_string(),
+                first_name: "Member".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: member.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+        let session = create_session(state.db.as_ref(), member.id).await.unwrap();
+        let member = format!("{SESSION_COOKIE}={session}");
+
+        let webhooks = format!("/clubs/{}/webhooks", club.id);
+        let webhook = format!("/webhooks/{}", created.webhook.id);
+        let deliveries = format!("/webhooks/{}/deliveries", created.webhook.id);
+        let params = CreateWebhookParams {
+            url: "http://example.com/other".to_string(),
+            events: None,
+        };
+
+        server
+            .post(&webhooks)
+            .json(&params)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get(&webhooks)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .get(&deliveries)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        server
+            .delete(&webhook)
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        server
+            .post(&webhooks)
+            .add_header("cookie", member.clone())
+            .json(&params)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        server
+            .get(&webhooks)
+            .add_header("cookie", member.clone())
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        server
+            .get(&deliveries)
+            .add_header("cookie", member.clone())
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        server
+            .delete(&webhook)
+            .add_header("cookie", member)
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_internal_target() {
+        let mut state = create_test_state().await;
+        state.webhooks.allow_private_targets = false;
+        let server = TestServer::new(create_router(state.clone())).unwrap();
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+
+        for url in [
+            "http://localhost:8080/hook",
+            "http://127.0.0.1/hook",
+            "http://[::1]/hook",
+            "http://10.0.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::ffff:127.0.0.1]/hook",
+        ] {
+            let response = server
+                .post(&format!("/clubs/{}/webhooks", club.id))
+                .add_header("cookie", cookie.clone())
+                .json(&CreateWebhookParams {
+                    url: url.to_string(),
+                    events: None,
+                })
+                .await;
+            response.assert_status(StatusCode::BAD_REQUEST);
+        }
+
+        server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie)
+            .json(&CreateWebhookParams {
+                url: "http://93.184.215.14/hook".to_string(),
+                events: None,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_to_internal_target_fails() {
+        let (server, state) = create_test_server_with_state().await;
+        let (receiver, url) = Receiver::start(StatusCode::OK).await;
+        let club = create_test_club(&server).await;
+        let cookie = create_club_admin(&server, &state, &[club.id]).await;
+        let created: CreatedWebhook = server
+            .post(&format!("/clubs/{}/webhooks", club.id))
+            .add_header("cookie", cookie.clone())
+            .json(&CreateWebhookParams { url, events: None })
+            .await
+            .json();
+        let user = create_user(
+            &server,
+            CreateUserParams {
+                email: "member@example.com".to_string(),
+                first_name: "Member".to_string(),
+                last_name: "User".to_string(),
+                timezone: None,
+            },
+        )
+        .await;
+        server
+            .post("/memberships")
+            .json(&CreateMembershipParams {
+                user_id: user.id,
+                club_id: club.id,
+                permission_level: 0,
+            })
+            .await
+            .assert_status(StatusCode::CREATED);
+
+        // The address may have been public when the webhook was registered.
+        let mut dispatcher = state.webhooks.clone();
+        dispatcher.allow_private_targets = false;
+        let delivered = dispatcher.deliver_pending(state.db.as_ref()).await.unwrap();
+        assert_eq!(delivered, 0);
+        assert!(receiver.requests.lock().unwrap().is_empty());
+
+        let deliveries: Vec<WebhookDelivery> = server
+            .get(&format!("/webhooks/{}/deliveries", created.webhook.id))
+            .add_header("cookie", cookie)
+            .await
+            .json();
+        assert_eq!(deliveries[0].attempts, 1);
+        assert_eq!(
+            deliveries[0].last_error.as_deref(),
+            Some("Webhook URL must point to a public address")
+        );
+    }
+}
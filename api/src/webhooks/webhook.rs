@@ -0,0 +1,28 @@
+use sqlx::SqliteConnection;
+
+use crate::error::AppResult;
+
+pub use shared::Webhook;
+
+/// Database access for [`Webhook`].
+pub trait WebhookExt: Sized {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>>;
+}
+
+impl WebhookExt for Webhook {
+    async fn from_id(id: i64, db: &mut SqliteConnection) -> AppResult<Option<Self>> {
+        let webhook = sqlx::query_as!(
+            Webhook,
+            r#"
+            SELECT id, club_id, url, events, created_at
+            FROM webhooks
+            WHERE id = ?
+            "#,
+            id
+        )
+        .fetch_optional(db)
+        .await?;
+
+        Ok(webhook)
+    }
+}
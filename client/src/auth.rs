@@ -0,0 +1,16 @@
+use reqwest::Url;
+
+use crate::{Client, Result};
+
+impl Client {
+    /// Where to send a browser to log in with Google. Google redirects it
+    /// back to `/auth/google/callback`, which sets the session cookie and
+    /// sends the browser on to `return_path`. The cookie's token can then be
+    /// used with [`Auth`](crate::Auth).
+    pub fn google_login_url(&self, return_path: &str) -> Result<Url> {
+        let mut url = self.url("/auth/google/login")?;
+        url.query_pairs_mut()
+            .append_pair("return_path", return_path);
+        Ok(url)
+    }
+}
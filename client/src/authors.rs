@@ -0,0 +1,39 @@
+use shared::{Author, AuthorBook, AuthorParams, AuthorStats, Credit, SetCreditsParams};
+
+use crate::{Client, Result};
+
+impl Client {
+    /// Adds the author, or returns the one with the same Open Library key.
+    pub async fn create_author(&self, params: &AuthorParams) -> Result<Author> {
+        self.post("/authors", params).await
+    }
+
+    pub async fn authors(&self) -> Result<Vec<Author>> {
+        self.get_all("/authors/list").await
+    }
+
+    pub async fn author(&self, id: i64) -> Result<Author> {
+        self.get(&format!("/authors/{id}")).await
+    }
+
+    pub async fn author_books(&self, id: i64) -> Result<Vec<AuthorBook>> {
+        self.get(&format!("/authors/{id}/books")).await
+    }
+
+    pub async fn author_stats(&self, id: i64) -> Result<AuthorStats> {
+        self.get(&format!("/authors/{id}/stats")).await
+    }
+
+    pub async fn book_credits(&self, book_id: i64) -> Result<Vec<Credit>> {
+        self.get(&format!("/books/{book_id}/authors")).await
+    }
+
+    /// Replaces the book's credits, updating its byline to its authors.
+    pub async fn set_book_credits(
+        &self,
+        book_id: i64,
+        params: &SetCreditsParams,
+    ) -> Result<Vec<Credit>> {
+        self.put(&format!("/books/{book_id}/authors"), params).await
+    }
+}
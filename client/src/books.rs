@@ -0,0 +1,49 @@
+use reqwest::{header, Method};
+use shared::{
+    Book, BookParams, BookSearchHit, FindBookParams, GoodreadsImport, GoodreadsImportParams,
+};
+
+use crate::{send, Client, Result};
+
+impl Client {
+    pub async fn create_book(&self, params: &BookParams) -> Result<Book> {
+        self.post("/books/create", params).await
+    }
+
+    pub async fn books(&self) -> Result<Vec<Book>> {
+        self.get_all("/books/list").await
+    }
+
+    pub async fn book(&self, id: i64) -> Result<Book> {
+        self.get(&format!("/books/get/{id}")).await
+    }
+
+    /// Books matching the search, best first. Fails with
+    /// [`Error::NotFound`](crate::Error::NotFound) if there are none.
+    pub async fn find_books(&self, params: &FindBookParams) -> Result<Vec<Book>> {
+        send(self.request(Method::GET, "/books/search")?.query(params)).await
+    }
+
+    /// Like [`Client::find_books`], with the matched words highlighted.
+    pub async fn search_books(&self, params: &FindBookParams) -> Result<Vec<BookSearchHit>> {
+        send(self.request(Method::GET, "/books/search")?.query(params)).await
+    }
+
+    /// Records the read shelf of a Goodreads library export as the user's
+    /// reads. A dry run reports what would change without changing it.
+    pub async fn import_goodreads(
+        &self,
+        user_id: i64,
+        csv: String,
+        params: &GoodreadsImportParams,
+    ) -> Result<GoodreadsImport> {
+        let path = format!("/users/{user_id}/goodreads-import");
+        send(
+            self.request(Method::POST, &path)?
+                .query(params)
+                .header(header::CONTENT_TYPE, "text/csv")
+                .body(csv),
+        )
+        .await
+    }
+}
@@ -0,0 +1,25 @@
+use reqwest::Method;
+use shared::CalendarToken;
+
+use crate::{send, send_text, Client, Result};
+
+impl Client {
+    /// Creates the token the user's calendar feeds are read with, replacing
+    /// any earlier one. The client must be signed in as the user.
+    pub async fn create_calendar_token(&self, user_id: i64) -> Result<CalendarToken> {
+        let path = format!("/users/{user_id}/calendar-token");
+        send(self.request(Method::POST, &path)?).await
+    }
+
+    /// The iCalendar feed of the meetings of all the user's clubs.
+    pub async fn user_feed(&self, token: &str) -> Result<String> {
+        let path = format!("/calendar/{token}/feed.ics");
+        send_text(self.request(Method::GET, &path)?).await
+    }
+
+    /// The iCalendar feed of one of the user's clubs.
+    pub async fn club_feed(&self, token: &str, club_id: i64) -> Result<String> {
+        let path = format!("/calendar/{token}/clubs/{club_id}/feed.ics");
+        send_text(self.request(Method::GET, &path)?).await
+    }
+}
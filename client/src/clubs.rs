@@ -0,0 +1,97 @@
+use reqwest::Method;
+use shared::{
+    Club, ClubArchive, CreateClubParams, CreateMembershipParams, ImportedClub, Member, Membership,
+    NominateParams, Nomination, UpdateClubParams, Venue, VenueParams,
+};
+
+use crate::{send_text, Client, Result};
+
+impl Client {
+    pub async fn create_club(&self, params: &CreateClubParams) -> Result<Club> {
+        self.post("/clubs", params).await
+    }
+
+    pub async fn clubs(&self) -> Result<Vec<Club>> {
+        self.get_all("/clubs/list").await
+    }
+
+    pub async fn club(&self, id: i64) -> Result<Club> {
+        self.get(&format!("/clubs/{id}")).await
+    }
+
+    pub async fn update_club(&self, id: i64, params: &UpdateClubParams) -> Result<Club> {
+        self.put(&format!("/clubs/{id}"), params).await
+    }
+
+    pub async fn delete_club(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/clubs/{id}")).await
+    }
+
+    /// Everything the club has recorded, for importing elsewhere. Only
+    /// available to the club's admins.
+    pub async fn club_archive(&self, id: i64) -> Result<ClubArchive> {
+        self.get(&format!("/clubs/{id}/archive")).await
+    }
+
+    /// The club's archive as a readable Markdown history.
+    pub async fn club_history(&self, id: i64) -> Result<String> {
+        send_text(self.request(Method::GET, &format!("/clubs/{id}/archive.md"))?).await
+    }
+
+    /// Creates a club from an archive, with the logged in user as an admin.
+    /// Members who already have an account are left out and listed.
+    pub async fn import_club(&self, archive: &ClubArchive) -> Result<ImportedClub> {
+        self.post("/clubs/import", archive).await
+    }
+
+    pub async fn create_membership(&self, params: &CreateMembershipParams) -> Result<Membership> {
+        self.post("/memberships", params).await
+    }
+
+    pub async fn memberships(&self) -> Result<Vec<Membership>> {
+        self.get_all("/memberships").await
+    }
+
+    pub async fn membership(&self, id: i64) -> Result<Membership> {
+        self.get(&format!("/memberships/{id}")).await
+    }
+
+    pub async fn delete_membership(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/memberships/{id}")).await
+    }
+
+    pub async fn club_members(&self, club_id: i64) -> Result<Vec<Member>> {
+        self.get(&format!("/clubs/{club_id}/members")).await
+    }
+
+    /// Saves a venue for the club to reuse. Needs to be logged in as a member.
+    pub async fn create_venue(&self, club_id: i64, params: &VenueParams) -> Result<Venue> {
+        self.post(&format!("/clubs/{club_id}/venues"), params).await
+    }
+
+    /// The club's saved venues. Private details are left out unless the
+    /// client is logged in as a member.
+    pub async fn club_venues(&self, club_id: i64) -> Result<Vec<Venue>> {
+        self.get(&format!("/clubs/{club_id}/venues")).await
+    }
+
+    pub async fn venue(&self, id: i64) -> Result<Venue> {
+        self.get(&format!("/venues/{id}")).await
+    }
+
+    /// Removes the venue from its club's list. Needs to be logged in as a member.
+    pub async fn delete_venue(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/venues/{id}")).await
+    }
+
+    /// Nominates the book for the club. Nominating it again returns the
+    /// existing nomination.
+    pub async fn nominate(&self, club_id: i64, params: &NominateParams) -> Result<Nomination> {
+        self.post(&format!("/clubs/{club_id}/nominations"), params)
+            .await
+    }
+
+    pub async fn club_nominations(&self, club_id: i64) -> Result<Vec<Nomination>> {
+        self.get(&format!("/clubs/{club_id}/nominations")).await
+    }
+}
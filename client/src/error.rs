@@ -0,0 +1,53 @@
+use reqwest::{Response, StatusCode};
+use shared::ErrorResponse;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// What can go wrong talking to the API.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The request was rejected, e.g. because of an unknown timezone.
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    /// The route needs a session and none, or an expired one, was sent.
+    #[error("not logged in: {0}")]
+    Unauthorized(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    /// Any other error status, including the server's own failures.
+    #[error("API responded with {status}: {message}")]
+    Api { status: StatusCode, message: String },
+    /// The API couldn't be reached or answered with something unexpected.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("invalid URL: {0}")]
+    Url(#[from] url::ParseError),
+}
+
+impl Error {
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::NotFound(_))
+    }
+
+    /// Turns an error response into the matching variant, using the message
+    /// of its [`ErrorResponse`] body.
+    pub(crate) async fn from_response(response: Response) -> Error {
+        let status = response.status();
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => return Error::Http(e),
+        };
+        // Errors from outside the API's handlers, such as a proxy, aren't JSON.
+        let message = match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error) => error.message,
+            Err(_) => body,
+        };
+
+        match status {
+            StatusCode::BAD_REQUEST => Error::BadRequest(message),
+            StatusCode::UNAUTHORIZED => Error::Unauthorized(message),
+            StatusCode::NOT_FOUND => Error::NotFound(message),
+            status => Error::Api { status, message },
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! A typed client for the bookclub API.
+//!
+//! ```no_run
+//! # async fn run() -> client::Result<()> {
+//! let api = client::Client::new("http://127.0.0.1:3000")?
+//!     .with_auth(client::Auth::Token("session token".to_string()));
+//! for club in api.clubs().await? {
+//!     println!("{}", club.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod auth;
+mod authors;
+mod books;
+mod calendar;
+mod clubs;
+mod error;
+mod meetings;
+mod notifications;
+mod open_library;
+mod telegram;
+mod users;
+mod webhooks;
+
+pub use error::*;
+pub use shared;
+
+use reqwest::{header, Method, RequestBuilder, Response, Url};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Name of the cookie the API keeps the session token in.
+const SESSION_COOKIE: &str = "session_token";
+/// Lists are fetched this many items at a time.
+const PAGE_SIZE: i64 = 100;
+
+/// How requests prove who is making them. Both carry the token of a session,
+/// which the API hands out when logging in, or the Telegram bot's key.
+#[derive(Debug, Clone)]
+pub enum Auth {
+    /// Sent as the session cookie, like a browser does.
+    Session(String),
+    /// Sent as an `Authorization: Bearer` header.
+    Token(String),
+}
+
+/// Client for the bookclub API, with a method for every route.
+///
+/// Requests are anonymous unless [`Client::with_auth`] is used. List methods
+/// follow the API's pagination and return every item.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    auth: Option<Auth>,
+    timezone: Option<String>,
+}
+
+impl Client {
+    /// A client for the API at `base_url`, e.g. `http://127.0.0.1:3000`.
+    pub fn new(base_url: &str) -> Result<Client> {
+        Client::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Like [`Client::new`], sending requests through `http`.
+    pub fn with_http_client(http: reqwest::Client, base_url: &str) -> Result<Client> {
+        // A trailing slash makes relative paths resolve below the base URL.
+        let base_url = Url::parse(&format!("{}/", base_url.trim_end_matches('/')))?;
+
+        Ok(Client {
+            http,
+            base_url,
+            auth: None,
+            timezone: None,
+        })
+    }
+
+    pub fn with_auth(self, auth: Auth) -> Client {
+        Client {
+            auth: Some(auth),
+            ..self
+        }
+    }
+
+    /// Has meeting dates rendered in the IANA timezone, e.g. `Europe/London`,
+    /// instead of the logged in user's.
+    pub fn with_timezone(self, timezone: impl Into<String>) -> Client {
+        Client {
+            timezone: Some(timezone.into()),
+            ..self
+        }
+    }
+
+    /// Answers `Hello, World!` when the API is up.
+    pub async fn hello(&self) -> Result<String> {
+        send_text(self.request(Method::GET, "/hi")?).await
+    }
+
+    fn url(&self, path: &str) -> Result<Url> {
+        Ok(self.base_url.join(path.trim_start_matches('/'))?)
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        Ok(self.request_url(method, self.url(path)?))
+    }
+
+    fn request_url(&self, method: Method, url: Url) -> RequestBuilder {
+        let request = self.http.request(method, url);
+        match &self.auth {
+            Some(Auth::Session(token)) => {
+                request.header(header::COOKIE, format!("{SESSION_COOKIE}={token}"))
+            }
+            Some(Auth::Token(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        send(self.request(Method::GET, path)?).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> Result<T> {
+        send(self.request(Method::POST, path)?.json(body)).await
+    }
+
+    async fn put<T: DeserializeOwned>(&self, path: &str, body: &impl Serialize) -> Result<T> {
+        send(self.request(Method::PUT, path)?.json(body)).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        checked(self.request(Method::DELETE, path)?).await?;
+        Ok(())
+    }
+
+    /// Fetches every page of a list, following the `Link` header's next page.
+    async fn get_all<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>> {
+        let mut items = vec![];
+        let mut first_page = self.url(path)?;
+        first_page
+            .query_pairs_mut()
+            .append_pair("limit", &PAGE_SIZE.to_string());
+        let mut url = Some(first_page);
+        while let Some(page_url) = url.take() {
+            let response = checked(self.request_url(Method::GET, page_url)).await?;
+            if let Some(next) = next_page(&response) {
+                url = Some(self.url(next)?);
+            }
+            items.extend(response.json::<Vec<T>>().await?);
+        }
+
+        Ok(items)
+    }
+}
+
+/// Sends the request, turning error statuses into [`Error`]s.
+async fn checked(request: RequestBuilder) -> Result<Response> {
+    let response = request.send().await?;
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(Error::from_response(response).await)
+    }
+}
+
+async fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+    Ok(checked(request).await?.json().await?)
+}
+
+async fn send_text(request: RequestBuilder) -> Result<String> {
+    Ok(checked(request).await?.text().await?)
+}
+
+/// The target of the `rel="next"` link in a `Link` header such as
+/// `</books/list?limit=100&offset=100>; rel="next"`.
+fn next_page(response: &Response) -> Option<&str> {
+    let links = response.headers().get(header::LINK)?.to_str().ok()?;
+    links.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim() == "rel=\"next\"");
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
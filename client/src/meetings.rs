@@ -0,0 +1,82 @@
+use reqwest::{Method, RequestBuilder};
+use shared::{
+    CreateMeetingParams, CreateSeriesParams, ExpandSeriesParams, Meeting, MeetingDetails,
+    MeetingSeries, UpdateMeetingParams,
+};
+
+use crate::{send, Client, Result};
+
+impl Client {
+    pub async fn create_meeting(&self, params: &CreateMeetingParams) -> Result<MeetingDetails> {
+        send(
+            self.meeting_request(Method::POST, "/meetings")?
+                .json(params),
+        )
+        .await
+    }
+
+    pub async fn meeting(&self, id: i64) -> Result<MeetingDetails> {
+        send(self.meeting_request(Method::GET, &format!("/meetings/{id}"))?).await
+    }
+
+    pub async fn update_meeting(
+        &self,
+        id: i64,
+        params: &UpdateMeetingParams,
+    ) -> Result<MeetingDetails> {
+        let request = self.meeting_request(Method::PUT, &format!("/meetings/{id}"))?;
+        send(request.json(params)).await
+    }
+
+    pub async fn delete_meeting(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/meetings/{id}")).await
+    }
+
+    pub async fn club_meetings(&self, club_id: i64) -> Result<Vec<MeetingDetails>> {
+        let path = format!("/clubs/{club_id}/meetings");
+        send(self.meeting_request(Method::GET, &path)?).await
+    }
+
+    /// Fails with [`Error::NotFound`](crate::Error::NotFound) if the club has
+    /// no upcoming meeting.
+    pub async fn next_meeting(&self, club_id: i64) -> Result<MeetingDetails> {
+        let path = format!("/clubs/{club_id}/meetings/next");
+        send(self.meeting_request(Method::GET, &path)?).await
+    }
+
+    /// Creates a recurring series and its first meetings.
+    pub async fn create_series(
+        &self,
+        club_id: i64,
+        params: &CreateSeriesParams,
+    ) -> Result<MeetingSeries> {
+        self.post(&format!("/clubs/{club_id}/series"), params).await
+    }
+
+    pub async fn club_series(&self, club_id: i64) -> Result<Vec<MeetingSeries>> {
+        self.get(&format!("/clubs/{club_id}/series")).await
+    }
+
+    /// Creates the series' meetings up to `until`, returning the new ones.
+    pub async fn expand_series(
+        &self,
+        id: i64,
+        params: &ExpandSeriesParams,
+    ) -> Result<Vec<Meeting>> {
+        let path = format!("/meeting-series/{id}/expand");
+        send(self.request(Method::POST, &path)?.query(params)).await
+    }
+
+    pub async fn delete_series(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/meeting-series/{id}")).await
+    }
+
+    /// Asks for meeting dates in the client's timezone, if it has one.
+    fn meeting_request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let request = self.request(method, path)?;
+        Ok(match &self.timezone {
+            Some(timezone) => request.query(&[("tz", timezone)]),
+            None => request,
+        })
+    }
+}
@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use shared::{Notification, NotificationEvent, NotificationPreference};
+
+use crate::{Client, Result};
+
+impl Client {
+    /// The emails sent, or still to be sent, to the user.
+    pub async fn user_notifications(&self, user_id: i64) -> Result<Vec<Notification>> {
+        self.get(&format!("/users/{user_id}/notifications")).await
+    }
+
+    pub async fn notification_preferences(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<NotificationPreference>> {
+        self.get(&format!("/users/{user_id}/notification-preferences"))
+            .await
+    }
+
+    /// Turns the given events on or off, leaving the others as they are.
+    pub async fn update_notification_preferences(
+        &self,
+        user_id: i64,
+        changes: &HashMap<NotificationEvent, bool>,
+    ) -> Result<Vec<NotificationPreference>> {
+        self.put(
+            &format!("/users/{user_id}/notification-preferences"),
+            changes,
+        )
+        .await
+    }
+}
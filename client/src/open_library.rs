@@ -0,0 +1,35 @@
+use reqwest::Method;
+use shared::{OpenLibBook, OpenLibraryResultsParams, OpenLibrarySearchParams};
+
+use crate::{send, Client, Result};
+
+impl Client {
+    /// The best match on Open Library for the title.
+    pub async fn search_open_library(
+        &self,
+        params: &OpenLibrarySearchParams,
+    ) -> Result<OpenLibBook> {
+        send(
+            self.request(Method::GET, "/open-library/search")?
+                .query(params),
+        )
+        .await
+    }
+
+    /// Matches on Open Library for the title, best first.
+    pub async fn open_library_results(
+        &self,
+        params: &OpenLibraryResultsParams,
+    ) -> Result<Vec<OpenLibBook>> {
+        send(
+            self.request(Method::GET, "/open-library/results")?
+                .query(params),
+        )
+        .await
+    }
+
+    /// The Open Library work with the id, e.g. `OL45804W`.
+    pub async fn open_library_work(&self, id: &str) -> Result<OpenLibBook> {
+        self.get(&format!("/open-library/works/{id}")).await
+    }
+}
@@ -0,0 +1,53 @@
+use reqwest::Method;
+use shared::{ChatVote, LinkChatParams, TelegramChat, TelegramLinkCode};
+
+use crate::{checked, send, Client, Result};
+
+impl Client {
+    pub async fn telegram_chats(&self) -> Result<Vec<TelegramChat>> {
+        self.get("/telegram/chats").await
+    }
+
+    pub async fn telegram_chat(&self, chat_id: i64) -> Result<TelegramChat> {
+        self.get(&format!("/telegram/chats/{chat_id}")).await
+    }
+
+    /// Issues a one-time code for linking a chat to the club. The client must
+    /// be signed in as one of the club's admins.
+    pub async fn create_telegram_link_code(&self, club_id: i64) -> Result<TelegramLinkCode> {
+        let path = format!("/clubs/{club_id}/telegram-link-code");
+        send(self.request(Method::POST, &path)?).await
+    }
+
+    /// Links the chat to the club the code was issued for, moving it over if
+    /// it was linked to another. The client must carry the bot key.
+    pub async fn link_telegram_chat(
+        &self,
+        chat_id: i64,
+        params: &LinkChatParams,
+    ) -> Result<TelegramChat> {
+        self.put(&format!("/telegram/chats/{chat_id}"), params)
+            .await
+    }
+
+    /// The client must carry the bot key.
+    pub async fn unlink_telegram_chat(&self, chat_id: i64) -> Result<()> {
+        self.delete(&format!("/telegram/chats/{chat_id}")).await
+    }
+
+    /// Tells the members and webhooks of the chat's club that a poll opened.
+    /// The client must carry the bot key.
+    pub async fn open_telegram_vote(&self, chat_id: i64, vote: &ChatVote) -> Result<()> {
+        let path = format!("/telegram/chats/{chat_id}/votes");
+        checked(self.request(Method::POST, &path)?.json(vote)).await?;
+        Ok(())
+    }
+
+    /// Tells the webhooks of the chat's club which date a poll settled on. The
+    /// client must carry the bot key.
+    pub async fn close_telegram_vote(&self, chat_id: i64, vote: &ChatVote) -> Result<()> {
+        let path = format!("/telegram/chats/{chat_id}/votes/closed");
+        checked(self.request(Method::POST, &path)?.json(vote)).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use reqwest::Method;
+use shared::{CreateUserParams, FindUserParams, UpdateUserParams, User};
+
+use crate::{send, Client, Result};
+
+impl Client {
+    pub async fn create_user(&self, params: &CreateUserParams) -> Result<User> {
+        self.post("/users/create", params).await
+    }
+
+    pub async fn users(&self) -> Result<Vec<User>> {
+        self.get_all("/users/list").await
+    }
+
+    pub async fn user(&self, id: i64) -> Result<User> {
+        self.get(&format!("/users/{id}")).await
+    }
+
+    pub async fn update_user(&self, id: i64, params: &UpdateUserParams) -> Result<User> {
+        self.put(&format!("/users/{id}"), params).await
+    }
+
+    pub async fn delete_user(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/users/{id}")).await
+    }
+
+    /// Users matching the search, best matches first.
+    pub async fn find_users(&self, params: &FindUserParams) -> Result<Vec<User>> {
+        send(self.request(Method::GET, "/users/search")?.query(params)).await
+    }
+}
@@ -0,0 +1,34 @@
+use reqwest::Method;
+use shared::{CreateWebhookParams, CreatedWebhook, DeliveryLogParams, Webhook, WebhookDelivery};
+
+use crate::{send, Client, Result};
+
+impl Client {
+    /// Registers a webhook. The secret its deliveries are signed with is only
+    /// returned here.
+    pub async fn create_webhook(
+        &self,
+        club_id: i64,
+        params: &CreateWebhookParams,
+    ) -> Result<CreatedWebhook> {
+        self.post(&format!("/clubs/{club_id}/webhooks"), params)
+            .await
+    }
+
+    pub async fn club_webhooks(&self, club_id: i64) -> Result<Vec<Webhook>> {
+        self.get(&format!("/clubs/{club_id}/webhooks")).await
+    }
+
+    pub async fn delete_webhook(&self, id: i64) -> Result<()> {
+        self.delete(&format!("/webhooks/{id}")).await
+    }
+
+    pub async fn webhook_deliveries(
+        &self,
+        id: i64,
+        params: &DeliveryLogParams,
+    ) -> Result<Vec<WebhookDelivery>> {
+        let path = format!("/webhooks/{id}/deliveries");
+        send(self.request(Method::GET, &path)?.query(params)).await
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Club;
+
+/// Raised when the format changes in a way older instances can't import.
+pub const CLUB_ARCHIVE_VERSION: u32 = 1;
+
+/// Everything a club has recorded, for moving it to another instance.
+///
+/// Ids only refer to other entries of the same archive, and people are
+/// identified by email, so the archive doesn't depend on the instance it
+/// came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClubArchive {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    pub club: ArchivedClub,
+    pub members: Vec<ArchivedMember>,
+    pub books: Vec<ArchivedBook>,
+    pub venues: Vec<ArchivedVenue>,
+    pub meetings: Vec<ArchivedMeeting>,
+    pub nominations: Vec<ArchivedNomination>,
+    pub reads: Vec<ArchivedRead>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedClub {
+    pub name: String,
+    pub description: String,
+    pub timezone: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedMember {
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub timezone: Option<String>,
+    pub permission_level: i64,
+    pub joined_at: NaiveDateTime,
+}
+
+/// A book the club met about or nominated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedBook {
+    pub id: i64,
+    pub title: String,
+    pub author: String,
+    pub isbn: Option<String>,
+    pub isbn13: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedVenue {
+    pub id: i64,
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub directions: Option<String>,
+    pub video_url: Option<String>,
+    pub private: bool,
+    pub saved: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedMeeting {
+    pub date: DateTime<Utc>,
+    pub book_id: Option<i64>,
+    pub venue_id: Option<i64>,
+    pub reading_target: Option<String>,
+    /// Emails of the members who attended.
+    pub attendees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedNomination {
+    pub book_id: i64,
+    /// Email of the member who nominated the book.
+    pub nominated_by: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A member having read one of the club's books, with their rating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedRead {
+    pub email: String,
+    pub book_id: i64,
+    pub read_at: Option<NaiveDate>,
+    pub rating: Option<i64>,
+}
+
+/// The club created from an archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedClub {
+    pub club: Club,
+    /// Emails of members who already have an account here. They aren't added
+    /// to the club, nor are their reads and attendance, until they join it
+    /// themselves.
+    pub skipped_members: Vec<String>,
+}
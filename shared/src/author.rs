@@ -0,0 +1,129 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Book;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Author {
+    pub id: i64,
+    pub name: String,
+    /// The author's key on Open Library, e.g. `OL26320A`.
+    pub open_library_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorParams {
+    pub name: String,
+    pub open_library_key: Option<String>,
+}
+
+/// What a person did for a book.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    #[default]
+    Author,
+    Translator,
+    Editor,
+}
+
+impl AuthorRole {
+    pub const ALL: [AuthorRole; 3] = [
+        AuthorRole::Author,
+        AuthorRole::Translator,
+        AuthorRole::Editor,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthorRole::Author => "author",
+            AuthorRole::Translator => "translator",
+            AuthorRole::Editor => "editor",
+        }
+    }
+}
+
+impl fmt::Display for AuthorRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuthorRole {
+    type Err = UnknownRole;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuthorRole::ALL
+            .into_iter()
+            .find(|role| role.as_str() == s)
+            .ok_or_else(|| UnknownRole(s.to_string()))
+    }
+}
+
+/// The name of a role that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRole(pub String);
+
+impl fmt::Display for UnknownRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown role '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownRole {}
+
+/// A person credited on a book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credit {
+    pub author: Author,
+    pub role: AuthorRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditParams {
+    pub author_id: i64,
+    #[serde(default)]
+    pub role: AuthorRole,
+}
+
+/// Replaces a book's credits, in the order given. The book's byline becomes
+/// the names of its authors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCreditsParams {
+    pub credits: Vec<CreditParams>,
+}
+
+/// A book the author is credited on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorBook {
+    #[serde(flatten)]
+    pub book: Book,
+    pub role: AuthorRole,
+}
+
+/// How much an author has been read, overall and by each club.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorStats {
+    pub author: Author,
+    pub books: i64,
+    /// Users who have read any of the author's books.
+    pub readers: i64,
+    /// Of the ratings users gave the author's books, from 1 to 5.
+    pub average_rating: Option<f64>,
+    /// Clubs with meetings about the author's books, the most meetings first.
+    pub clubs: Vec<ClubAuthorStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClubAuthorStats {
+    pub club_id: i64,
+    pub club_name: String,
+    pub meetings: i64,
+    /// Distinct books of the author the club met about.
+    pub books: i64,
+    /// The club's last meeting about the author, which may be planned.
+    pub latest_meeting: DateTime<Utc>,
+}
@@ -1,4 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct Book {
     pub title: String,
     pub author: String,
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookParams {
+    pub title: String,
+    pub author: String,
+}
+
+/// Searches the books' titles and authors. Every word must match, as a whole
+/// word or the start of one, ignoring case and accents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindBookParams {
+    /// Words to find in either the title or the author.
+    pub q: Option<String>,
+    /// Words to find in the title.
+    pub title: Option<String>,
+    /// Words to find in the author.
+    pub author: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// A book matching a search, best matches first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookSearchHit {
+    #[serde(flatten)]
+    pub book: Book,
+    /// The HTML-escaped title with the matched words wrapped in `<mark>` tags.
+    pub highlighted_title: String,
+    /// The author, highlighted like the title.
+    pub highlighted_author: String,
+    /// How well the book matches, lower is better.
+    pub score: f64,
 }
@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Grants read access to a user's calendar feeds without logging in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct CalendarToken {
+    pub user_id: i64,
+    pub token: String,
+    pub created_at: NaiveDateTime,
+}
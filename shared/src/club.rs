@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Club {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    /// IANA timezone name the club meets in.
+    pub timezone: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateClubParams {
+    pub name: String,
+    pub description: String,
+    /// Defaults to UTC.
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateClubParams {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub timezone: Option<String>,
+}
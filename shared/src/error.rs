@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// The body of every error the API answers with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// The HTTP status code, repeated for clients that only see the body.
+    pub status: u16,
+    pub message: String,
+}
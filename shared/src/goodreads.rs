@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GoodreadsImportParams {
+    /// Reports what the import would do without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// How an imported book was found among the existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookMatch {
+    Isbn,
+    TitleAndAuthor,
+    /// No existing book matched, so one was, or would be, created.
+    Created,
+}
+
+/// A book from the read shelf of a Goodreads export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoodreadsRead {
+    pub title: String,
+    pub author: String,
+    /// Empty for books a dry run would create.
+    pub book_id: Option<i64>,
+    pub matched_by: BookMatch,
+    pub read_at: Option<NaiveDate>,
+    pub rating: Option<i64>,
+    pub shelves: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoodreadsImport {
+    pub dry_run: bool,
+    pub books_created: usize,
+    pub reads: Vec<GoodreadsRead>,
+    /// Rows left out because they aren't on the read shelf.
+    pub skipped: usize,
+}
@@ -1,3 +1,40 @@
+//! The request and response bodies of the bookclub API, shared by the server
+//! and its clients.
+
+mod archive;
+mod author;
 mod book;
+mod calendar;
+mod club;
+mod error;
+mod goodreads;
+mod meeting;
+mod membership;
+mod nomination;
+mod notification;
+mod open_library;
+mod page;
+mod series;
+mod telegram;
+mod user;
+mod venue;
+mod webhook;
 
+pub use archive::*;
+pub use author::*;
 pub use book::*;
+pub use calendar::*;
+pub use club::*;
+pub use error::*;
+pub use goodreads::*;
+pub use meeting::*;
+pub use membership::*;
+pub use nomination::*;
+pub use notification::*;
+pub use open_library::*;
+pub use page::*;
+pub use series::*;
+pub use telegram::*;
+pub use user::*;
+pub use venue::*;
+pub use webhook::*;
@@ -0,0 +1,62 @@
+use std::ops::Deref;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Venue, VenueParams};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Meeting {
+    pub id: i64,
+    /// Stored in UTC and rendered in the requester's timezone.
+    pub date: DateTime<FixedOffset>,
+    pub book_id: Option<i64>,
+    pub club_id: i64,
+    pub series_id: Option<i64>,
+    /// The occurrence of the series this meeting was generated for, kept when it is moved.
+    /// This is a wall-clock time in the club's timezone, like the series' start.
+    pub occurrence: Option<NaiveDateTime>,
+    pub venue_id: Option<i64>,
+    /// How far members should have read by the meeting, e.g. "Chapters 1-10".
+    pub reading_target: Option<String>,
+}
+
+/// A meeting as returned by the API, with its venue resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingDetails {
+    #[serde(flatten)]
+    pub meeting: Meeting,
+    pub venue: Option<Venue>,
+}
+
+/// The meeting's fields are flattened into the response, so they read like it.
+impl Deref for MeetingDetails {
+    type Target = Meeting;
+
+    fn deref(&self) -> &Meeting {
+        &self.meeting
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMeetingParams {
+    pub club_id: i64,
+    pub book_id: Option<i64>,
+    /// Must carry an offset, e.g. `2030-01-09T19:00:00+01:00`.
+    pub date: DateTime<Utc>,
+    /// One of the club's saved venues.
+    pub venue_id: Option<i64>,
+    /// A one-off location, instead of a saved venue.
+    pub location: Option<VenueParams>,
+    pub reading_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateMeetingParams {
+    pub date: Option<DateTime<Utc>>,
+    pub book_id: Option<i64>,
+    pub venue_id: Option<i64>,
+    pub location: Option<VenueParams>,
+    pub reading_target: Option<String>,
+}
@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Membership {
+    pub id: i64,
+    pub user_id: i64,
+    pub club_id: i64,
+    pub permission_level: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMembershipParams {
+    pub user_id: i64,
+    pub club_id: i64,
+    pub permission_level: i32,
+}
+
+/// A club member with the user's details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub user_id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub permission_level: i64,
+    pub joined_at: NaiveDateTime,
+}
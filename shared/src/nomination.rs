@@ -0,0 +1,22 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A book put forward for the club to read, with the book's details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Nomination {
+    pub id: i64,
+    pub club_id: i64,
+    pub book_id: i64,
+    pub title: String,
+    pub author: String,
+    /// The user who nominated the book, unless it came from an anonymous client
+    /// such as the Telegram bot.
+    pub nominated_by: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NominateParams {
+    pub book_id: i64,
+}
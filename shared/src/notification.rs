@@ -0,0 +1,86 @@
+use std::{fmt, str::FromStr};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    MeetingScheduled,
+    MeetingTomorrow,
+    /// A date poll for the next meeting opened in one of the club's chats.
+    VoteOpened,
+    /// The user was added to a club.
+    ClubJoined,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 4] = [
+        NotificationEvent::MeetingScheduled,
+        NotificationEvent::MeetingTomorrow,
+        NotificationEvent::VoteOpened,
+        NotificationEvent::ClubJoined,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::MeetingScheduled => "meeting_scheduled",
+            NotificationEvent::MeetingTomorrow => "meeting_tomorrow",
+            NotificationEvent::VoteOpened => "vote_opened",
+            NotificationEvent::ClubJoined => "club_joined",
+        }
+    }
+}
+
+impl fmt::Display for NotificationEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationEvent {
+    type Err = UnknownEvent;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotificationEvent::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| UnknownEvent(s.to_string()))
+    }
+}
+
+/// The name of an event that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownEvent(pub String);
+
+impl fmt::Display for UnknownEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown event '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownEvent {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Notification {
+    pub id: i64,
+    pub user_id: i64,
+    pub event: String,
+    pub subject: String,
+    pub body: String,
+    pub dedup_key: Option<String>,
+    /// `pending`, `sent`, or `failed` once it ran out of attempts.
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreference {
+    pub event: NotificationEvent,
+    pub enabled: bool,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// A match from Open Library's search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenLibBook {
+    pub title: String,
+    pub author_name: Option<Vec<String>>,
+    /// The authors' keys, e.g. `OL26320A`, in the same order as their names.
+    pub author_key: Option<Vec<String>>,
+    /// E.g. `/works/OL45804W`.
+    pub key: String,
+    /// Id of the cover on covers.openlibrary.org.
+    pub cover_i: Option<i64>,
+    pub first_publish_year: Option<i32>,
+}
+
+impl OpenLibBook {
+    /// The first of the book's authors.
+    pub fn author(&self) -> &str {
+        self.author_name
+            .as_ref()
+            .and_then(|authors| authors.first())
+            .map_or("Unknown", String::as_str)
+    }
+
+    /// The work's id, e.g. `OL45804W`.
+    pub fn work_id(&self) -> &str {
+        self.key.trim_start_matches("/works/")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenLibrarySearchParams {
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenLibraryResultsParams {
+    pub title: String,
+    pub limit: Option<usize>,
+}
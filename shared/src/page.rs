@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Query parameters of the list endpoints. Without either, the whole list is
+/// returned. When there are more items than the page holds, the response has a
+/// `Link` header with the URL of the next page, marked `rel="next"`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PageParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
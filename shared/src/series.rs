@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct MeetingSeries {
+    pub id: i64,
+    pub club_id: i64,
+    pub rrule: String,
+    /// Wall-clock time in the club's timezone, so a 19:00 meeting stays at 19:00
+    /// across DST changes.
+    pub starts_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSeriesParams {
+    /// An RFC 5545 recurrence rule, e.g. `FREQ=MONTHLY;BYDAY=2TU`.
+    pub rrule: String,
+    pub starts_at: NaiveDateTime,
+    pub until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExpandSeriesParams {
+    pub until: Option<NaiveDateTime>,
+}
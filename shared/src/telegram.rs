@@ -0,0 +1,40 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+/// A Telegram chat linked to a club. A chat belongs to at most one club, a
+/// club can be linked to several chats.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct TelegramChat {
+    pub chat_id: i64,
+    pub club_id: i64,
+    pub linked_at: NaiveDateTime,
+}
+
+/// A one-time code from a club admin, which a chat sends with `/link <code>` to
+/// be linked to the club.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct TelegramLinkCode {
+    pub code: String,
+    pub club_id: i64,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkChatParams {
+    pub code: String,
+}
+
+/// A date poll the bot runs in a linked chat. The bot keeps the votes, the API
+/// only hears about the poll to tell the club's members and webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatVote {
+    /// Numbered within the chat.
+    pub poll_id: u32,
+    /// The days on offer.
+    pub dates: Vec<NaiveDate>,
+    /// When the meeting the poll settled on starts, in the club's timezone.
+    /// Only set once the poll is closed.
+    pub outcome: Option<NaiveDateTime>,
+}
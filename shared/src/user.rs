@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct User {
+    pub id: i64,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    /// IANA timezone name, if the user has picked one.
+    pub timezone: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUserParams {
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateUserParams {
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// Searches users, e.g. for a member picker. The fields match exactly, `q`
+/// matches loosely, and every given one must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindUserParams {
+    /// Words found anywhere in the name or email, ignoring case. Users whose
+    /// name or email starts with it come first.
+    pub q: Option<String>,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    /// Leaves out the members of this club.
+    pub not_in_club: Option<i64>,
+    pub limit: Option<usize>,
+}
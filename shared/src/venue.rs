@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Where a meeting takes place: a physical address, a video link, or both.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Venue {
+    pub id: i64,
+    pub club_id: i64,
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub directions: Option<String>,
+    pub video_url: Option<String>,
+    /// Private venues, e.g. someone's home, only show their details to club members.
+    pub private: bool,
+    /// Saved venues are listed for the club to reuse.
+    pub saved: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VenueParams {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub directions: Option<String>,
+    pub video_url: Option<String>,
+    #[serde(default)]
+    pub private: bool,
+}
@@ -0,0 +1,129 @@
+use std::{fmt, str::FromStr};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::UnknownEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "membership.created")]
+    MembershipCreated,
+    #[serde(rename = "membership.deleted")]
+    MembershipDeleted,
+    #[serde(rename = "meeting.created")]
+    MeetingCreated,
+    #[serde(rename = "meeting.updated")]
+    MeetingUpdated,
+    #[serde(rename = "meeting.deleted")]
+    MeetingDeleted,
+    /// A book was picked for one of the club's meetings.
+    #[serde(rename = "book.selected")]
+    BookSelected,
+    #[serde(rename = "vote.opened")]
+    VoteOpened,
+    #[serde(rename = "vote.closed")]
+    VoteClosed,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 8] = [
+        WebhookEvent::MembershipCreated,
+        WebhookEvent::MembershipDeleted,
+        WebhookEvent::MeetingCreated,
+        WebhookEvent::MeetingUpdated,
+        WebhookEvent::MeetingDeleted,
+        WebhookEvent::BookSelected,
+        WebhookEvent::VoteOpened,
+        WebhookEvent::VoteClosed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::MembershipCreated => "membership.created",
+            WebhookEvent::MembershipDeleted => "membership.deleted",
+            WebhookEvent::MeetingCreated => "meeting.created",
+            WebhookEvent::MeetingUpdated => "meeting.updated",
+            WebhookEvent::MeetingDeleted => "meeting.deleted",
+            WebhookEvent::BookSelected => "book.selected",
+            WebhookEvent::VoteOpened => "vote.opened",
+            WebhookEvent::VoteClosed => "vote.closed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = UnknownEvent;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WebhookEvent::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| UnknownEvent(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Webhook {
+    pub id: i64,
+    pub club_id: i64,
+    pub url: String,
+    /// Comma separated event names, `None` for every event.
+    pub events: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Webhook {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        match &self.events {
+            Some(events) => events.split(',').any(|name| name == event.as_str()),
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebhookParams {
+    pub url: String,
+    /// Leave out to receive every event.
+    pub events: Option<Vec<WebhookEvent>>,
+}
+
+/// Only returned when the webhook is registered, the secret can't be read back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    /// `pending`, `delivered`, or `failed` once it ran out of attempts.
+    pub status: String,
+    pub attempts: i64,
+    /// The HTTP status of the last attempt, if the endpoint answered at all.
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryLogParams {
+    /// `pending`, `delivered` or `failed`.
+    pub status: Option<String>,
+}
@@ -0,0 +1,207 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use client::{Auth, Client};
+pub use shared::{
+    Book, BookParams, ChatVote, Club, CreateMeetingParams, FindBookParams, LinkChatParams,
+    MeetingDetails, Member, NominateParams, Nomination, OpenLibBook, OpenLibraryResultsParams,
+    OpenLibrarySearchParams, TelegramChat, UpdateMeetingParams,
+};
+
+const DEFAULT_API_URL: &str = "http://127.0.0.1:3000";
+
+/// Client for the bookclub API, which owns all of the club's data. The bot
+/// only keeps its own dialogue state.
+#[derive(Clone)]
+pub struct ApiClient {
+    client: Client,
+}
+
+impl ApiClient {
+    /// Talks to the API at `BOOKCLUB_API_URL`, defaulting to a local instance.
+    /// Linking chats and reporting polls needs the API's bot key in
+    /// `BOOKCLUB_BOT_KEY`.
+    pub fn from_env() -> Result<ApiClient> {
+        let base_url =
+            std::env::var("BOOKCLUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        let mut client = Client::new(&base_url)?;
+        if let Ok(key) = std::env::var("BOOKCLUB_BOT_KEY") {
+            client = client.with_auth(Auth::Token(key));
+        }
+
+        Ok(ApiClient { client })
+    }
+
+    /// The club linked to the chat, if any.
+    pub async fn linked_club(&self, chat_id: i64) -> Result<Option<Club>> {
+        let Some(chat) = optional(self.client.telegram_chat(chat_id).await)? else {
+            return Ok(None);
+        };
+
+        self.club(chat.club_id).await
+    }
+
+    /// Links the chat to the club a link code was issued for. Returns `None`
+    /// if the code is unknown, used or expired.
+    pub async fn link_chat(&self, chat_id: i64, code: &str) -> Result<Option<Club>> {
+        let params = LinkChatParams {
+            code: code.to_string(),
+        };
+        let Some(chat) = optional(self.client.link_telegram_chat(chat_id, &params).await)? else {
+            return Ok(None);
+        };
+
+        self.club(chat.club_id).await
+    }
+
+    /// Returns whether the chat was linked.
+    pub async fn unlink_chat(&self, chat_id: i64) -> Result<bool> {
+        Ok(optional(self.client.unlink_telegram_chat(chat_id).await)?.is_some())
+    }
+
+    /// Tells the members and webhooks of the chat's club about the poll.
+    /// Returns whether the chat is linked to a club.
+    pub async fn open_vote(&self, chat_id: i64, vote: &ChatVote) -> Result<bool> {
+        Ok(optional(self.client.open_telegram_vote(chat_id, vote).await)?.is_some())
+    }
+
+    /// Tells the webhooks of the chat's club which date the poll settled on.
+    /// Returns whether the chat is linked to a club.
+    pub async fn close_vote(&self, chat_id: i64, vote: &ChatVote) -> Result<bool> {
+        Ok(optional(self.client.close_telegram_vote(chat_id, vote).await)?.is_some())
+    }
+
+    pub async fn linked_chats(&self) -> Result<Vec<TelegramChat>> {
+        Ok(self.client.telegram_chats().await?)
+    }
+
+    pub async fn club(&self, club_id: i64) -> Result<Option<Club>> {
+        optional(self.client.club(club_id).await)
+    }
+
+    pub async fn next_meeting(&self, club_id: i64) -> Result<Option<MeetingDetails>> {
+        optional(self.client.next_meeting(club_id).await)
+    }
+
+    pub async fn club_meetings(&self, club_id: i64) -> Result<Vec<MeetingDetails>> {
+        Ok(optional(self.client.club_meetings(club_id).await)?.unwrap_or_default())
+    }
+
+    pub async fn members(&self, club_id: i64) -> Result<Vec<Member>> {
+        Ok(optional(self.client.club_members(club_id).await)?.unwrap_or_default())
+    }
+
+    pub async fn book(&self, book_id: i64) -> Result<Option<Book>> {
+        optional(self.client.book(book_id).await)
+    }
+
+    /// Finds the book by title in the API, otherwise looks it up on Open Library
+    /// and adds it. Returns `None` if neither knows the book.
+    pub async fn find_or_create_book(&self, title: &str) -> Result<Option<Book>> {
+        if let Some(book) = self.find_books(title).await?.into_iter().next() {
+            return Ok(Some(book));
+        }
+
+        let params = OpenLibrarySearchParams {
+            title: title.to_string(),
+        };
+        let Some(found) = optional(self.client.search_open_library(&params).await)? else {
+            return Ok(None);
+        };
+
+        self.add_book(&found.title, found.author()).await.map(Some)
+    }
+
+    /// Finds the book by title in the API, adding it if it isn't there yet.
+    pub async fn add_book(&self, title: &str, author: &str) -> Result<Book> {
+        let books = self.find_books(title).await?;
+        if let Some(book) = books.into_iter().find(|book| book.title == title) {
+            return Ok(book);
+        }
+
+        let params = BookParams {
+            title: title.to_string(),
+            author: author.to_string(),
+        };
+        Ok(self.client.create_book(&params).await?)
+    }
+
+    async fn find_books(&self, title: &str) -> Result<Vec<Book>> {
+        let params = FindBookParams {
+            title: Some(title.to_string()),
+            ..Default::default()
+        };
+        Ok(optional(self.client.find_books(&params).await)?.unwrap_or_default())
+    }
+
+    pub async fn search_open_library(&self, query: &str, limit: usize) -> Result<Vec<OpenLibBook>> {
+        let params = OpenLibraryResultsParams {
+            title: query.to_string(),
+            limit: Some(limit),
+        };
+        Ok(optional(self.client.open_library_results(&params).await)?.unwrap_or_default())
+    }
+
+    pub async fn open_library_work(&self, work_id: &str) -> Result<Option<OpenLibBook>> {
+        optional(self.client.open_library_work(work_id).await)
+    }
+
+    pub async fn nominate(&self, club_id: i64, book_id: i64) -> Result<Nomination> {
+        match optional(
+            self.client
+                .nominate(club_id, &NominateParams { book_id })
+                .await,
+        )? {
+            Some(nomination) => Ok(nomination),
+            None => bail!("Club {club_id} not found"),
+        }
+    }
+
+    pub async fn create_meeting(
+        &self,
+        club_id: i64,
+        date: DateTime<Utc>,
+    ) -> Result<MeetingDetails> {
+        let params = CreateMeetingParams {
+            club_id,
+            book_id: None,
+            date,
+            venue_id: None,
+            location: None,
+            reading_target: None,
+        };
+        match optional(self.client.create_meeting(&params).await)? {
+            Some(meeting) => Ok(meeting),
+            None => bail!("Club {club_id} not found"),
+        }
+    }
+
+    pub async fn set_meeting_book(&self, meeting_id: i64, book_id: i64) -> Result<MeetingDetails> {
+        let changes = UpdateMeetingParams {
+            book_id: Some(book_id),
+            ..Default::default()
+        };
+        Ok(self.client.update_meeting(meeting_id, &changes).await?)
+    }
+
+    pub async fn set_reading_target(
+        &self,
+        meeting_id: i64,
+        target: &str,
+    ) -> Result<MeetingDetails> {
+        let changes = UpdateMeetingParams {
+            reading_target: Some(target.to_string()),
+            ..Default::default()
+        };
+        Ok(self.client.update_meeting(meeting_id, &changes).await?)
+    }
+}
+
+/// The API answers 404 for anything it doesn't know, which the bot tells the
+/// chat about rather than treating as a failure.
+fn optional<T>(result: client::Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.is_not_found() => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
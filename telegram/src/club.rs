@@ -0,0 +1,374 @@
+use anyhow::Result;
+use teloxide::{prelude::*, types::UserId};
+
+use crate::{
+    api::{ApiClient, Club, MeetingDetails},
+    locale::{tr, Language},
+};
+
+fn not_linked(language: Language) -> String {
+    tr!(
+        language,
+        "This chat isn't linked to a club yet. Ask a club admin for a link code and send /link <code>.",
+        "Dieser Chat ist noch mit keinem Club verknüpft. Frag einen Club-Admin nach einem Code und sende /link <Code>.",
+    )
+}
+
+fn no_meeting(language: Language) -> String {
+    tr!(
+        language,
+        "There is no upcoming meeting.",
+        "Es ist kein Treffen geplant.",
+    )
+}
+
+/// Only admins may change which club a group talks to, or its settings. Anyone
+/// may in a private chat. Linking also takes a code from one of the club's
+/// admins, so a chat can't pick any club it likes.
+pub async fn may_manage_chat(bot: &Bot, msg: &Message) -> Result<bool> {
+    if msg.chat.is_private() {
+        return Ok(true);
+    }
+    let Some(user) = msg.from() else {
+        return Ok(false);
+    };
+    let member = bot.get_chat_member(msg.chat.id, user.id).await?;
+
+    Ok(member.is_privileged())
+}
+
+pub async fn linked_club(
+    bot: &Bot,
+    api: &ApiClient,
+    msg: &Message,
+    language: Language,
+) -> Result<Option<Club>> {
+    let club = api.linked_club(msg.chat.id.0).await?;
+    if club.is_none() {
+        bot.send_message(msg.chat.id, not_linked(language)).await?;
+    }
+
+    Ok(club)
+}
+
+/// Clubs with a linked group the Telegram user is in. The bot doesn't know
+/// which account a Telegram user has, so these stand in for their memberships.
+pub async fn member_clubs(bot: &Bot, api: &ApiClient, user: UserId) -> Result<Vec<Club>> {
+    let mut club_ids = vec![];
+    for chat in api.linked_chats().await? {
+        if !club_ids.contains(&chat.club_id) && in_chat(bot, chat.chat_id, user).await {
+            club_ids.push(chat.club_id);
+        }
+    }
+
+    let mut clubs = vec![];
+    for club_id in club_ids {
+        if let Some(club) = api.club(club_id).await? {
+            clubs.push(club);
+        }
+    }
+
+    Ok(clubs)
+}
+
+/// Whether the Telegram user is in one of the club's linked groups.
+pub async fn is_member(bot: &Bot, api: &ApiClient, user: UserId, club_id: i64) -> Result<bool> {
+    for chat in api.linked_chats().await? {
+        if chat.club_id == club_id && in_chat(bot, chat.chat_id, user).await {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Chats the bot was removed from can't be asked, so they count as not joined.
+async fn in_chat(bot: &Bot, chat_id: i64, user: UserId) -> bool {
+    bot.get_chat_member(ChatId(chat_id), user)
+        .await
+        .is_ok_and(|member| member.is_present())
+}
+
+pub async fn link(
+    bot: Bot,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    code: String,
+) -> Result<()> {
+    if !may_manage_chat(&bot, &msg).await? {
+        let reply = tr!(
+            language,
+            "Only chat admins can link a club.",
+            "Nur Admins des Chats können einen Club verknüpfen.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let code = code.trim();
+    if code.is_empty() {
+        let reply = tr!(
+            language,
+            "Send the link code from a club admin: /link <code>",
+            "Sende den Code von einem Club-Admin: /link <Code>",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let reply = match api.link_chat(msg.chat.id.0, code).await? {
+        Some(club) => {
+            let name = club.name;
+            tr!(
+                language,
+                "This chat now belongs to {name}.",
+                "Dieser Chat gehört jetzt zu {name}.",
+            )
+        }
+        None => tr!(
+            language,
+            "That link code is unknown or has expired, ask a club admin for a new one.",
+            "Dieser Code ist unbekannt oder abgelaufen, frag einen Club-Admin nach einem neuen.",
+        ),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+pub async fn unlink(bot: Bot, api: ApiClient, msg: Message, language: Language) -> Result<()> {
+    if !may_manage_chat(&bot, &msg).await? {
+        let reply = tr!(
+            language,
+            "Only chat admins can unlink a club.",
+            "Nur Admins des Chats können die Verknüpfung aufheben.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let reply = match api.unlink_chat(msg.chat.id.0).await? {
+        true => tr!(
+            language,
+            "This chat is no longer linked to a club.",
+            "Dieser Chat ist nicht mehr mit einem Club verknüpft.",
+        ),
+        false => not_linked(language),
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+/// Shows the book for the next meeting, or sets it when a title is given.
+pub async fn book(
+    bot: Bot,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    title: String,
+) -> Result<()> {
+    let Some(club) = linked_club(&bot, &api, &msg, language).await? else {
+        return Ok(());
+    };
+    let Some(meeting) = api.next_meeting(club.id).await? else {
+        bot.send_message(msg.chat.id, no_meeting(language)).await?;
+        return Ok(());
+    };
+
+    let title = title.trim();
+    if title.is_empty() {
+        let reply = match book_title(&api, &meeting, language).await? {
+            Some(book) => tr!(language, "We're reading {book}.", "Wir lesen {book}."),
+            None => tr!(
+                language,
+                "The book for the next meeting hasn't been picked yet.",
+                "Das Buch für das nächste Treffen steht noch nicht fest.",
+            ),
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let Some(book) = api.find_or_create_book(title).await? else {
+        let reply = tr!(
+            language,
+            "I couldn't find a book called '{title}'.",
+            "Ich konnte kein Buch namens „{title}“ finden.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+    let meeting = api.set_meeting_book(meeting.id, book.id).await?;
+    let (title, author, date) = (
+        book.title,
+        book.author,
+        language.short_date(meeting.date.date_naive()),
+    );
+    let reply = tr!(
+        language,
+        "{title} by {author} is the book for the meeting on {date}.",
+        "{title} von {author} ist das Buch für das Treffen am {date}.",
+    );
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+/// The meeting's date, book, reading target and location, headed by `title`.
+pub fn describe_meeting(
+    title: &str,
+    meeting: &MeetingDetails,
+    book: Option<String>,
+    language: Language,
+) -> String {
+    let (date, time) = (
+        language.long_date(meeting.date.date_naive()),
+        language.time(meeting.date.time()),
+    );
+    let mut lines = vec![tr!(
+        language,
+        "{title}: {date} at {time}",
+        "{title}: {date} um {time}",
+    )];
+    if let Some(book) = book {
+        lines.push(tr!(language, "Book: {book}", "Buch: {book}"));
+    }
+    if let Some(target) = &meeting.reading_target {
+        lines.push(tr!(language, "Read up to: {target}", "Lesen bis: {target}"));
+    }
+    if let Some(venue) = &meeting.venue {
+        let place = [venue.name.as_deref(), venue.address.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !place.is_empty() {
+            lines.push(tr!(language, "Where: {place}", "Wo: {place}"));
+        }
+        if let Some(directions) = &venue.directions {
+            lines.push(directions.clone());
+        }
+        if let Some(video_url) = &venue.video_url {
+            lines.push(tr!(
+                language,
+                "Join online: {video_url}",
+                "Online dabei sein: {video_url}",
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// The title and author of the meeting's book, if one has been picked.
+pub async fn book_title(
+    api: &ApiClient,
+    meeting: &MeetingDetails,
+    language: Language,
+) -> Result<Option<String>> {
+    let Some(book_id) = meeting.book_id else {
+        return Ok(None);
+    };
+    let book = api.book(book_id).await?;
+
+    Ok(book.map(|book| {
+        let (title, author) = (book.title, book.author);
+        tr!(language, "{title} by {author}", "{title} von {author}")
+    }))
+}
+
+/// Shows how far to read for the next meeting, or sets it when a target is given.
+pub async fn target(
+    bot: Bot,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    target: String,
+) -> Result<()> {
+    let Some(club) = linked_club(&bot, &api, &msg, language).await? else {
+        return Ok(());
+    };
+    let Some(meeting) = api.next_meeting(club.id).await? else {
+        bot.send_message(msg.chat.id, no_meeting(language)).await?;
+        return Ok(());
+    };
+
+    let target = target.trim();
+    let reply = if target.is_empty() {
+        match &meeting.reading_target {
+            Some(target) => tr!(
+                language,
+                "Read up to {target} for the next meeting.",
+                "Lest bis {target} für das nächste Treffen.",
+            ),
+            None => tr!(
+                language,
+                "No reading target has been set for the next meeting.",
+                "Für das nächste Treffen ist noch kein Leseziel gesetzt.",
+            ),
+        }
+    } else {
+        let meeting = api.set_reading_target(meeting.id, target).await?;
+        let date = language.short_date(meeting.date.date_naive());
+        tr!(
+            language,
+            "Read up to {target} for the meeting on {date}.",
+            "Lest bis {target} für das Treffen am {date}.",
+        )
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+pub async fn next(bot: Bot, api: ApiClient, msg: Message, language: Language) -> Result<()> {
+    let Some(club) = linked_club(&bot, &api, &msg, language).await? else {
+        return Ok(());
+    };
+    let Some(meeting) = api.next_meeting(club.id).await? else {
+        bot.send_message(msg.chat.id, no_meeting(language)).await?;
+        return Ok(());
+    };
+
+    let book = book_title(&api, &meeting, language).await?;
+    let title = tr!(language, "Next meeting", "Nächstes Treffen");
+    bot.send_message(
+        msg.chat.id,
+        describe_meeting(&title, &meeting, book, language),
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn members(bot: Bot, api: ApiClient, msg: Message, language: Language) -> Result<()> {
+    let Some(club) = linked_club(&bot, &api, &msg, language).await? else {
+        return Ok(());
+    };
+
+    let members = api.members(club.id).await?;
+    let name = club.name;
+    let reply = if members.is_empty() {
+        tr!(
+            language,
+            "{name} has no members yet.",
+            "{name} hat noch keine Mitglieder.",
+        )
+    } else {
+        let names = members
+            .iter()
+            .map(|member| format!("• {} {}", member.first_name, member.last_name))
+            .collect::<Vec<_>>()
+            .join("\n");
+        tr!(
+            language,
+            "{name} members:\n{names}",
+            "Mitglieder von {name}:\n{names}",
+        )
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use reqwest::Url;
+use teloxide::{
+    prelude::*,
+    types::{
+        InlineKeyboardButton, InlineKeyboardMarkup, InlineQueryResult, InlineQueryResultArticle,
+        InputMessageContent, InputMessageContentText, Me,
+    },
+};
+
+use crate::{
+    api::{ApiClient, Club, OpenLibBook},
+    club,
+    locale::{tr, Language},
+};
+
+/// Prefix of the `/start` payload the "nominate" buttons open the bot with,
+/// followed by `<club_id>-<work_id>`.
+const NOMINATE_PREFIX: &str = "nominate-";
+const MAX_RESULTS: usize = 10;
+/// Open Library returns little of use for shorter queries.
+const MIN_QUERY_CHARS: usize = 3;
+
+/// Answers `@bot <title>` with matching books from Open Library.
+pub async fn search(bot: Bot, api: ApiClient, me: Me, q: InlineQuery) -> Result<()> {
+    let query = q.query.trim();
+    let books = if query.chars().count() < MIN_QUERY_CHARS {
+        vec![]
+    } else {
+        api.search_open_library(query, MAX_RESULTS).await?
+    };
+
+    let clubs = if books.is_empty() {
+        vec![]
+    } else {
+        club::member_clubs(&bot, &api, q.from.id).await?
+    };
+
+    // Inline queries don't come from a chat, so answer in the user's language.
+    let language = Language::from_client(q.from.language_code.as_deref());
+    let results = books
+        .iter()
+        .map(|book| InlineQueryResult::Article(book_card(book, &me, language, &clubs)))
+        .collect::<Vec<_>>();
+    // The buttons depend on who asks, so Telegram mustn't share the answer.
+    bot.answer_inline_query(q.id, results)
+        .is_personal(true)
+        .await?;
+
+    Ok(())
+}
+
+fn book_card(
+    book: &OpenLibBook,
+    me: &Me,
+    language: Language,
+    clubs: &[Club],
+) -> InlineQueryResultArticle {
+    let byline = match book.first_publish_year {
+        Some(year) => format!("{} ({year})", book.author()),
+        None => book.author().to_string(),
+    };
+    let (title, key) = (&book.title, &book.key);
+    let text = tr!(
+        language,
+        "📚 {title}\nby {byline}\nhttps://openlibrary.org{key}",
+        "📚 {title}\nvon {byline}\nhttps://openlibrary.org{key}",
+    );
+
+    let mut card = InlineQueryResultArticle::new(
+        book.work_id(),
+        &book.title,
+        InputMessageContent::Text(InputMessageContentText::new(text)),
+    )
+    .description(byline);
+
+    // Messages sent through inline mode don't tell the bot which chat they are
+    // in, so nominating goes through a private chat with the bot and the
+    // button names the club, one for each club the user is in.
+    let buttons = clubs
+        .iter()
+        .filter_map(|club| {
+            let url = format!(
+                "https://t.me/{}?start={NOMINATE_PREFIX}{}-{}",
+                me.username(),
+                club.id,
+                book.work_id()
+            );
+            let name = &club.name;
+            let text = tr!(language, "Nominate for {name}", "Für {name} vorschlagen");
+            Some(vec![InlineKeyboardButton::url(
+                text,
+                Url::parse(&url).ok()?,
+            )])
+        })
+        .collect::<Vec<_>>();
+    if !buttons.is_empty() {
+        card = card.reply_markup(InlineKeyboardMarkup::new(buttons));
+    }
+    if let Some(cover) = book.cover_i {
+        if let Ok(url) = Url::parse(&format!(
+            "https://covers.openlibrary.org/b/id/{cover}-S.jpg"
+        )) {
+            card = card.thumb_url(url);
+        }
+    }
+
+    card
+}
+
+/// The club and Open Library work of a `/start` payload from a "nominate"
+/// button.
+pub fn parse_nominate(payload: &str) -> Option<(i64, &str)> {
+    let (club_id, work_id) = payload.strip_prefix(NOMINATE_PREFIX)?.split_once('-')?;
+    if work_id.is_empty() {
+        return None;
+    }
+
+    Some((club_id.parse().ok()?, work_id))
+}
+
+/// Nominates the Open Library work for the club from the button. Anyone can
+/// open the bot with any payload, so the user has to be in one of the club's
+/// linked groups.
+pub async fn nominate(
+    bot: Bot,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    club_id: i64,
+    work_id: &str,
+) -> Result<()> {
+    let member = match msg.from() {
+        Some(user) => club::is_member(&bot, &api, user.id, club_id).await?,
+        None => false,
+    };
+    let club = if member {
+        api.club(club_id).await?
+    } else {
+        None
+    };
+    let Some(club) = club else {
+        let reply = tr!(
+            language,
+            "You can only nominate books for clubs whose group you are in.",
+            "Du kannst nur Bücher für Clubs vorschlagen, in deren Gruppe du bist.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+    let Some(found) = api.open_library_work(work_id).await? else {
+        let reply = tr!(
+            language,
+            "I couldn't find that book on Open Library.",
+            "Ich konnte das Buch bei Open Library nicht finden.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+
+    let book = api.add_book(&found.title, found.author()).await?;
+    let nomination = api.nominate(club.id, book.id).await?;
+    let (title, author, name) = (nomination.title, nomination.author, club.name);
+    let reply = tr!(
+        language,
+        "Nominated {title} by {author} for {name}.",
+        "{title} von {author} ist für {name} vorgeschlagen.",
+    );
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_nominate() {
+        assert_eq!(parse_nominate("nominate-3-OL45804W"), Some((3, "OL45804W")));
+        assert_eq!(parse_nominate("nominate-OL45804W"), None);
+        assert_eq!(parse_nominate("nominate-3-"), None);
+        assert_eq!(parse_nominate("hello"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+
+/// Formats the message in the language, like `format!`:
+/// `tr!(language, "Poll #{id}", "Umfrage #{id}")`.
+macro_rules! tr {
+    ($language:expr, $english:literal, $german:literal $(,)?) => {
+        match $language {
+            $crate::locale::Language::English => format!($english),
+            $crate::locale::Language::German => format!($german),
+        }
+    };
+}
+pub(crate) use tr;
+
+const TIME_FORMAT: &str = "%H:%M";
+const GERMAN_WEEKDAYS: [&str; 7] = [
+    "Montag",
+    "Dienstag",
+    "Mittwoch",
+    "Donnerstag",
+    "Freitag",
+    "Samstag",
+    "Sonntag",
+];
+const GERMAN_MONTHS: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+
+/// The language the bot talks to a chat in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// Accepts the code or the name, in either language.
+    pub fn parse(s: &str) -> Option<Language> {
+        match s.to_lowercase().as_str() {
+            "en" | "english" | "englisch" => Some(Language::English),
+            "de" | "german" | "deutsch" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    /// The language of a Telegram client, from its IETF code such as `de-AT`.
+    pub fn from_client(code: Option<&str>) -> Language {
+        let primary = code.and_then(|code| code.split('-').next());
+        primary.and_then(Language::parse).unwrap_or_default()
+    }
+
+    pub fn weekday(self, day: Weekday) -> String {
+        match self {
+            Language::English => day_name(day).to_string(),
+            Language::German => GERMAN_WEEKDAYS[day.num_days_from_monday() as usize].to_string(),
+        }
+    }
+
+    /// Parses the name of a day of the week, in full or abbreviated.
+    pub fn parse_weekday(s: &str) -> Option<Weekday> {
+        let s = s.to_lowercase();
+        if let Ok(day) = s.parse::<Weekday>() {
+            return Some(day);
+        }
+        let mut day = Weekday::Mon;
+        for name in GERMAN_WEEKDAYS {
+            let name = name.to_lowercase();
+            if s == name || (s.len() >= 2 && name.starts_with(&s)) {
+                return Some(day);
+            }
+            day = day.succ();
+        }
+
+        None
+    }
+
+    /// A compact date for buttons and lists, e.g. `Mon 05 Feb`.
+    pub fn short_date(self, date: NaiveDate) -> String {
+        match self {
+            Language::English => date.format("%a %d %b").to_string(),
+            Language::German => {
+                let day = GERMAN_WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+                let month = GERMAN_MONTHS[date.month0() as usize];
+                format!(
+                    "{} {}. {}",
+                    abbreviate(day, 2),
+                    date.format("%d"),
+                    abbreviate(month, 3)
+                )
+            }
+        }
+    }
+
+    /// A date written out in full, e.g. `Monday 05 February`.
+    pub fn long_date(self, date: NaiveDate) -> String {
+        match self {
+            Language::English => date.format("%A %d %B").to_string(),
+            Language::German => {
+                let day = GERMAN_WEEKDAYS[date.weekday().num_days_from_monday() as usize];
+                let month = GERMAN_MONTHS[date.month0() as usize];
+                format!("{day}, {}. {month}", date.format("%d"))
+            }
+        }
+    }
+
+    pub fn time(self, time: NaiveTime) -> String {
+        time.format(TIME_FORMAT).to_string()
+    }
+}
+
+fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn abbreviate(name: &str, chars: usize) -> String {
+    name.chars().take(chars).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_weekday() {
+        assert_eq!(Language::parse_weekday("Sunday"), Some(Weekday::Sun));
+        assert_eq!(Language::parse_weekday("mon"), Some(Weekday::Mon));
+        assert_eq!(Language::parse_weekday("Mittwoch"), Some(Weekday::Wed));
+        assert_eq!(Language::parse_weekday("do"), Some(Weekday::Thu));
+        assert_eq!(Language::parse_weekday("SO"), Some(Weekday::Sun));
+        assert_eq!(Language::parse_weekday("m"), None);
+        assert_eq!(Language::parse_weekday("Montags"), None);
+        assert_eq!(Language::parse_weekday(""), None);
+    }
+
+    #[test]
+    fn test_short_date() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        assert_eq!(Language::English.short_date(date), "Wed 05 Mar");
+        assert_eq!(Language::German.short_date(date), "Mi 05. Mär");
+
+        let date = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        assert_eq!(Language::English.short_date(date), "Sun 02 Feb");
+        assert_eq!(Language::German.short_date(date), "So 02. Feb");
+    }
+}
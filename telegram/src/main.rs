@@ -1,25 +1,39 @@
-use std::collections::HashSet;
+mod api;
+mod club;
+mod inline;
+mod locale;
+mod poll;
+mod reminders;
+mod settings;
+mod webhook;
 
 use anyhow::Result;
-use chrono::{offset::Local, DateTime};
-use chrono_english::{parse_date_string, Dialect};
 
 use teloxide::{
     dispatching::dialogue::{serializer::Json, Dialogue, SqliteStorage},
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup, Me},
+    types::Me,
     utils::command::{BotCommands, ParseError},
 };
 
+use api::ApiClient;
+use locale::{tr, Language};
+use poll::{Poll, Polls};
+use reminders::Reminders;
+use settings::SettingsStore;
+use webhook::Webhook;
+
+/// Holds the dialogue state, the chats' settings and the record of posted
+/// reminders.
+const DATABASE_PATH: &str = "telegram.sqlite";
+
 #[derive(Clone, Default, serde::Serialize, serde::Deserialize, Debug)]
 enum State {
     #[default]
     Start,
-    Polling {
-        start: DateTime<Local>,
-        end: DateTime<Local>,
-        selected: HashSet<String>,
-    },
+    /// A chat's only poll, as stored before chats could run several.
+    Polling(Poll),
+    Polls(Polls),
 }
 
 type DialogState = Dialogue<State, SqliteStorage<Json>>;
@@ -29,21 +43,50 @@ async fn main() {
     pretty_env_logger::init();
     dotenv::dotenv().ok();
 
-    let storage = SqliteStorage::open("telegram.sqlite", Json).await.unwrap();
+    let storage = SqliteStorage::open(DATABASE_PATH, Json).await.unwrap();
 
     let bot = Bot::from_env();
+    let api = ApiClient::from_env().expect("Invalid BOOKCLUB_API_URL");
+    let database_url = format!("sqlite:{DATABASE_PATH}");
+    let settings = SettingsStore::open(&database_url).await.unwrap();
 
-    let handler = dptree::entry()
-        .enter_dialogue::<Update, SqliteStorage<Json>, State>()
-        .branch(Update::filter_message().endpoint(message_handler))
-        .branch(Update::filter_callback_query().endpoint(callback_handler));
+    let reminders = Reminders::from_env(bot.clone(), api.clone(), settings.clone(), &database_url)
+        .await
+        .unwrap();
+    tokio::spawn(reminders.run());
 
-    Dispatcher::builder(bot, handler)
-        .dependencies(dptree::deps![storage])
+    // Inline queries don't come from a chat, so they have no dialogue.
+    let handler = dptree::entry()
+        .branch(Update::filter_inline_query().endpoint(inline::search))
+        .branch(
+            dptree::entry()
+                .enter_dialogue::<Update, SqliteStorage<Json>, State>()
+                .branch(Update::filter_message().endpoint(message_handler))
+                .branch(Update::filter_callback_query().endpoint(poll::callback)),
+        );
+
+    let mut dispatcher = Dispatcher::builder(bot.clone(), handler)
+        .dependencies(dptree::deps![storage, api, settings])
         .enable_ctrlc_handler()
-        .build()
-        .dispatch()
-        .await;
+        .build();
+
+    // Long polling unless a webhook is configured.
+    match Webhook::from_env().unwrap() {
+        Some(webhook) => {
+            let listener = webhook.listen(&bot).await.unwrap();
+            dispatcher
+                .dispatch_with_listener(
+                    listener,
+                    LoggingErrorHandler::with_custom_text("An error from the webhook listener"),
+                )
+                .await;
+            // So that polling works again if the bot is started without one.
+            if let Err(e) = bot.delete_webhook().await {
+                log::warn!("Failed to delete the webhook: {e:?}");
+            }
+        }
+        None => dispatcher.dispatch().await,
+    }
 }
 
 #[derive(BotCommands, Clone)]
@@ -52,131 +95,146 @@ async fn main() {
     description = "These commands are supported:"
 )]
 enum Command {
+    #[command(description = "off")]
+    Start(String),
     #[command(description = "echo the text")]
     Echo(String),
     #[command(
-        description = "create a poll for dates between start and end",
-        parse_with = split_dates
+        description = "create a poll for dates between start and end, or for this or next week, optionally with time slots: /polldate monday to friday at 18:00, 20:00 (or at 18:00-21:00)"
+    )]
+    PollDate(String),
+    #[command(
+        description = "close a poll and schedule the winning date: /closepoll [poll id] [HH:MM]"
+    )]
+    ClosePoll(String),
+    #[command(description = "list this chat's polls, or show one: /polls [poll id]")]
+    Polls(String),
+    #[command(
+        description = "link this chat to a club with a code from a club admin: /link <code>"
     )]
-    PollDate {
-        start: DateTime<Local>,
-        end: DateTime<Local>,
-    },
+    Link(String),
+    #[command(description = "unlink this chat from its club")]
+    Unlink,
+    #[command(description = "show the book for the next meeting, or set it: /book <title>")]
+    Book(String),
+    #[command(
+        description = "show how far to read for the next meeting, or set it: /target <chapters>"
+    )]
+    Target(String),
+    #[command(description = "show the next meeting")]
+    Next,
+    #[command(description = "list the club's members")]
+    Members,
+    #[command(
+        description = "show or change this chat's language, date format and first day of the week: /settings [language|dialect|week <value>]"
+    )]
+    Settings(String),
     #[command(description = "display this text")]
     Help,
 }
 
-async fn callback_handler(bot: Bot, dialog: DialogState, q: CallbackQuery) -> Result<()> {
-    match dialog.get_or_default().await? {
-        State::Polling {
-            start,
-            end,
-            selected,
-        } => {
-            if let Some(data) = q.data {
-                println!("callback_handler: {data} {start} {end}");
-            }
-            let message_id = if let Some(id) = q.inline_message_id {
-                id
-            } else if let Some(msg) = q.message {
-                msg.id.to_string()
-            } else {
-                println!("No message id");
-                return Ok(());
-            };
-
-            println!("callback_handler: {message_id}");
-
-            let markup = make_keyboard(start, end, &selected);
-            bot.edit_message_reply_markup_inline(message_id)
-                .reply_markup(markup)
-                .await?;
-        }
-        State::Start => {
-            println!("callback_handler: start")
-        }
-    }
-    Ok(())
-}
-
-async fn message_handler(bot: Bot, dialog: DialogState, msg: Message, me: Me) -> Result<()> {
+async fn message_handler(
+    bot: Bot,
+    dialog: DialogState,
+    api: ApiClient,
+    store: SettingsStore,
+    msg: Message,
+    me: Me,
+) -> Result<()> {
     println!("message_handler");
 
     let text = msg.text().ok_or(anyhow::anyhow!("No text in message"))?;
+    let settings = store.get(msg.chat.id).await?;
+    let language = settings.language;
     match BotCommands::parse(text, me.username()) {
+        Ok(Command::Start(payload)) => match inline::parse_nominate(&payload) {
+            Some((club_id, work_id)) => {
+                inline::nominate(bot, api, msg, language, club_id, work_id).await?
+            }
+            None => {
+                bot.send_message(msg.chat.id, help(language)).await?;
+            }
+        },
         Ok(Command::Echo(text)) => {
-            bot.send_message(msg.chat.id, format!("you said '{text}'"))
-                .await?;
-        }
-        Ok(Command::PollDate { start, end }) => {
-            bot.send_message(msg.chat.id, format!("start: {start}, end: {end}"))
-                .await?;
-            let selected = HashSet::new();
-            let buttons = make_keyboard(start, end, &selected);
-            dialog
-                .update(State::Polling {
-                    start,
-                    end,
-                    selected,
-                })
-                .await?;
-            bot.send_message(msg.chat.id, "Select Days")
-                .reply_markup(buttons)
-                .await?;
+            let reply = tr!(language, "you said '{text}'", "du hast „{text}“ gesagt");
+            bot.send_message(msg.chat.id, reply).await?;
         }
+        Ok(Command::PollDate(args)) => match poll::parse_dates(&args, &settings) {
+            Ok(dates) => poll::start(bot, dialog, api, msg, language, dates).await?,
+            Err(err) => {
+                bot.send_message(msg.chat.id, err).await?;
+            }
+        },
+        Ok(Command::ClosePoll(args)) => poll::close(bot, dialog, api, msg, language, args).await?,
+        Ok(Command::Polls(args)) => poll::show(bot, dialog, msg, language, args).await?,
+        Ok(Command::Link(code)) => club::link(bot, api, msg, language, code).await?,
+        Ok(Command::Unlink) => club::unlink(bot, api, msg, language).await?,
+        Ok(Command::Book(title)) => club::book(bot, api, msg, language, title).await?,
+        Ok(Command::Target(target)) => club::target(bot, api, msg, language, target).await?,
+        Ok(Command::Next) => club::next(bot, api, msg, language).await?,
+        Ok(Command::Members) => club::members(bot, api, msg, language).await?,
+        Ok(Command::Settings(args)) => settings::command(bot, store, msg, settings, args).await?,
         Ok(Command::Help) => {
-            bot.send_message(msg.chat.id, Command::descriptions().to_string())
-                .await?;
+            bot.send_message(msg.chat.id, help(language)).await?;
         }
         Err(err) => {
-            bot.send_message(msg.chat.id, err.to_string()).await?;
+            bot.send_message(msg.chat.id, parse_error(err, language))
+                .await?;
         }
     };
 
     Ok(())
 }
 
-fn make_keyboard(
-    start: DateTime<Local>,
-    end: DateTime<Local>,
-    selected: &HashSet<String>,
-) -> InlineKeyboardMarkup {
-    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
-
-    let dur = end - start;
-    let days = dur.num_days();
-
-    for d in 0..days {
-        let date = start + chrono::Duration::days(d);
-
-        let date_id = date.format("%Y-%m-%d").to_string();
-        let display_date = if selected.contains(&date_id) {
-            format!("{} ✅", date.format("%a %d %b"))
-        } else {
-            date.format("%a %d %b").to_string()
-        };
-
-        keyboard.push(vec![InlineKeyboardButton::callback(display_date, date_id)]);
+fn help(language: Language) -> String {
+    match language {
+        Language::English => Command::descriptions().to_string(),
+        Language::German => {
+            let commands = Command::bot_commands()
+                .into_iter()
+                .map(|command| {
+                    let description =
+                        german_description(&command.command).unwrap_or(&command.description);
+                    format!("{} — {description}", command.command)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("Diese Befehle werden unterstützt:\n\n{commands}")
+        }
     }
+}
 
-    InlineKeyboardMarkup::new(keyboard)
+fn german_description(command: &str) -> Option<&'static str> {
+    let description = match command {
+        "/echo" => "wiederholt den Text",
+        "/polldate" => "startet eine Umfrage für die Tage zwischen Start und Ende, oder für diese oder nächste Woche, optional mit Uhrzeiten: /polldate 5.2. bis 9.2. um 18:00, 20:00 (oder um 18:00-21:00)",
+        "/closepoll" => "beendet eine Umfrage und plant den gewählten Termin: /closepoll [Umfrage-ID] [HH:MM]",
+        "/polls" => "listet die Umfragen dieses Chats, oder zeigt eine: /polls [Umfrage-ID]",
+        "/link" => "verknüpft diesen Chat mit einem Club, mit einem Code von einem Club-Admin: /link <Code>",
+        "/unlink" => "hebt die Verknüpfung mit dem Club auf",
+        "/book" => "zeigt das Buch für das nächste Treffen, oder legt es fest: /book <Titel>",
+        "/target" => "zeigt, wie weit bis zum nächsten Treffen gelesen wird, oder legt es fest: /target <Kapitel>",
+        "/next" => "zeigt das nächste Treffen",
+        "/members" => "listet die Mitglieder des Clubs",
+        "/settings" => "zeigt oder ändert Sprache, Datumsformat und ersten Wochentag dieses Chats: /settings [sprache|dialekt|woche <Wert>]",
+        "/help" => "zeigt diesen Text",
+        _ => return None,
+    };
+
+    Some(description)
 }
 
-fn split_dates(s: String) -> Result<(DateTime<Local>, DateTime<Local>), ParseError> {
-    let mut iter = s.split(" to ");
-    let start = iter
-        .next()
-        .ok_or(ParseError::Custom("dates must be separated by 'to'".into()))?;
-    let start = parse_date_string(start, Local::now(), Dialect::Uk).map_err(|e| {
-        ParseError::Custom(format!("unable to parse start date: {e:?} '{start}'").into())
-    })?;
-
-    let end = iter
-        .next()
-        .ok_or(ParseError::Custom("dates must be separated by 'to'".into()))?;
-    let end = parse_date_string(end, Local::now(), Dialect::Uk).map_err(|e| {
-        ParseError::Custom(format!("unable to parse end date: {e:?} '{end}'").into())
-    })?;
-
-    Ok((start, end))
+fn parse_error(err: ParseError, language: Language) -> String {
+    match err {
+        ParseError::UnknownCommand(command) => tr!(
+            language,
+            "Unknown command: {command}",
+            "Unbekannter Befehl: {command}, siehe /help",
+        ),
+        err => tr!(
+            language,
+            "{err}",
+            "Das habe ich nicht verstanden ({err}), siehe /help",
+        ),
+    }
 }
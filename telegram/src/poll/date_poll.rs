@@ -0,0 +1,733 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use chrono::{offset::Local, DateTime, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId, User};
+
+use crate::locale::{tr, Language};
+
+/// How many of the best dates the summary lists.
+const SUMMARY_DATES: usize = 3;
+/// Telegram caps inline keyboards at 100 buttons, so long polls are paged.
+const OPTIONS_PER_PAGE: usize = 10;
+/// Spacing of the slots a time window such as `18:00-21:00` is split into.
+const WINDOW_SLOT_MINUTES: i64 = 60;
+const DATE_ID_FORMAT: &str = "%Y-%m-%d";
+const SLOT_ID_FORMAT: &str = "%Y-%m-%dT%H:%M";
+const TIME_FORMAT: &str = "%H:%M";
+const CLOSE: &str = "close";
+/// The page indicator between the paging buttons, which does nothing.
+const NOOP: &str = "noop";
+const PICK_PREFIX: &str = "pick:";
+const PICK_PAGE_PREFIX: &str = "pickpage:";
+const PAGE_PREFIX: &str = "page:";
+/// Keyboards sent before polls had ids belong to the chat's first poll.
+const LEGACY_POLL_ID: u32 = 1;
+
+/// What a button of a poll keyboard asks for, decoded from its callback data.
+pub enum Action {
+    Vote(String),
+    Close,
+    /// The creator settling a tie between the top dates.
+    Pick(NaiveDate, NaiveTime),
+    /// Shows another page of the tied dates, whole days starting at the time.
+    PickPage(usize, NaiveTime),
+    Page(usize),
+    Noop,
+}
+
+impl Action {
+    /// Decodes callback data of the form `<poll id>:<action>`.
+    pub fn parse(data: &str) -> Option<(u32, Action)> {
+        let (poll_id, data) = match data.split_once(':') {
+            Some((poll_id, action)) if poll_id.parse::<u32>().is_ok() => {
+                (poll_id.parse().ok()?, action)
+            }
+            _ => (LEGACY_POLL_ID, data),
+        };
+
+        Some((poll_id, Action::parse_action(data)?))
+    }
+
+    fn parse_action(data: &str) -> Option<Action> {
+        if data == CLOSE {
+            return Some(Action::Close);
+        }
+        if data == NOOP {
+            return Some(Action::Noop);
+        }
+        if let Some(pick) = data.strip_prefix(PICK_PREFIX) {
+            let (date, time) = pick.split_once(' ')?;
+            let date = NaiveDate::parse_from_str(date, DATE_ID_FORMAT).ok()?;
+            let time = NaiveTime::parse_from_str(time, TIME_FORMAT).ok()?;
+            return Some(Action::Pick(date, time));
+        }
+        if let Some(pick_page) = data.strip_prefix(PICK_PAGE_PREFIX) {
+            let (page, time) = pick_page.split_once(' ')?;
+            let time = NaiveTime::parse_from_str(time, TIME_FORMAT).ok()?;
+            return Some(Action::PickPage(page.parse().ok()?, time));
+        }
+        if let Some(page) = data.strip_prefix(PAGE_PREFIX) {
+            return Some(Action::Page(page.parse().ok()?));
+        }
+
+        Some(Action::Vote(data.to_string()))
+    }
+}
+
+/// Parses the times of a slot poll, e.g. `18:00, 20:00` or the window
+/// `18:00-21:00`, which becomes a slot every hour starting in it.
+pub fn parse_slots(s: &str) -> Option<Vec<NaiveTime>> {
+    let mut slots = vec![];
+    for part in s.split([',', ' ']).filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let from = NaiveTime::parse_from_str(from, TIME_FORMAT).ok()?;
+                let to = NaiveTime::parse_from_str(to, TIME_FORMAT).ok()?;
+                let mut slot = from;
+                while slot < to {
+                    slots.push(slot);
+                    let (next, wrapped) =
+                        slot.overflowing_add_signed(Duration::minutes(WINDOW_SLOT_MINUTES));
+                    if wrapped != 0 {
+                        break;
+                    }
+                    slot = next;
+                }
+            }
+            None => slots.push(NaiveTime::parse_from_str(part, TIME_FORMAT).ok()?),
+        }
+    }
+    slots.sort();
+    slots.dedup();
+
+    (!slots.is_empty()).then_some(slots)
+}
+
+/// Something people can vote for: a whole day, or a time slot on a day.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PollOption {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+}
+
+impl PollOption {
+    /// Identifies the option in votes and callback data.
+    fn id(&self) -> String {
+        match self.time {
+            Some(time) => self.date.and_time(time).format(SLOT_ID_FORMAT).to_string(),
+            None => self.date.format(DATE_ID_FORMAT).to_string(),
+        }
+    }
+
+    pub fn label(&self, language: Language) -> String {
+        match self.time {
+            Some(time) => format!("{} {}", language.short_date(self.date), language.time(time)),
+            None => language.short_date(self.date),
+        }
+    }
+}
+
+/// The date a closed poll settled on.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct Outcome {
+    pub date: NaiveDate,
+    pub time: NaiveTime,
+}
+
+/// A poll for the days between `start` and `end` on which people are available,
+/// optionally split into time slots.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Poll {
+    /// Unique within the chat, assigned by [`Polls::add`](super::Polls::add).
+    #[serde(default)]
+    pub id: u32,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    /// The times offered on each day. Empty for a poll of whole days.
+    #[serde(default)]
+    pub slots: Vec<NaiveTime>,
+    /// Who is available for each option, keyed by the option's id.
+    #[serde(default)]
+    pub votes: BTreeMap<String, BTreeSet<u64>>,
+    /// Names of everyone who voted, by Telegram user id.
+    #[serde(default)]
+    pub voters: BTreeMap<u64, String>,
+    /// The message listing the best dates, edited as votes come in.
+    #[serde(default)]
+    pub summary_message: Option<MessageId>,
+    /// The message carrying the voting keyboard.
+    #[serde(default)]
+    pub message: Option<MessageId>,
+    /// The keyboard page currently shown.
+    #[serde(default)]
+    pub page: usize,
+    /// Telegram user id of whoever started the poll.
+    #[serde(default)]
+    pub creator: Option<u64>,
+    /// Set once the poll is closed.
+    #[serde(default)]
+    pub outcome: Option<Outcome>,
+}
+
+impl Poll {
+    pub fn new(
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        slots: Vec<NaiveTime>,
+        creator: Option<&User>,
+    ) -> Poll {
+        Poll {
+            id: 0,
+            start,
+            end,
+            slots,
+            votes: BTreeMap::new(),
+            voters: BTreeMap::new(),
+            summary_message: None,
+            message: None,
+            page: 0,
+            creator: creator.map(|user| user.id.0),
+            outcome: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.outcome.is_none()
+    }
+
+    fn callback_data(&self, action: &str) -> String {
+        format!("{}:{action}", self.id)
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        (0..(self.end - self.start).num_days())
+            .map(|d| (self.start + Duration::days(d)).date_naive())
+    }
+
+    pub fn options(&self) -> Vec<PollOption> {
+        self.days()
+            .flat_map(|date| {
+                let times = if self.slots.is_empty() {
+                    vec![None]
+                } else {
+                    self.slots.iter().copied().map(Some).collect()
+                };
+                times.into_iter().map(move |time| PollOption { date, time })
+            })
+            .collect()
+    }
+
+    /// Adds or removes the user's vote for the option. Returns whether the user
+    /// is now available, or `None` if the option isn't part of the poll.
+    pub fn toggle(&mut self, option_id: &str, user: &User) -> Option<bool> {
+        if !self.options().iter().any(|option| option.id() == option_id) {
+            return None;
+        }
+
+        let user_id = user.id.0;
+        let votes = self.votes.entry(option_id.to_string()).or_default();
+        let available = if votes.remove(&user_id) {
+            false
+        } else {
+            votes.insert(user_id);
+            true
+        };
+        if votes.is_empty() {
+            self.votes.remove(option_id);
+        }
+
+        // Keep the name of everyone who still has a vote somewhere.
+        if self.votes.values().any(|votes| votes.contains(&user_id)) {
+            self.voters.insert(user_id, user.full_name());
+        } else {
+            self.voters.remove(&user_id);
+        }
+
+        Some(available)
+    }
+
+    fn page_count(&self) -> usize {
+        self.options().len().div_ceil(OPTIONS_PER_PAGE).max(1)
+    }
+
+    /// Shows the page, clamped to the pages the poll has.
+    pub fn set_page(&mut self, page: usize) {
+        self.page = page.min(self.page_count() - 1);
+    }
+
+    pub fn keyboard(&self, language: Language) -> InlineKeyboardMarkup {
+        let pages = self.page_count();
+        let page = self.page.min(pages - 1);
+
+        let mut keyboard = self
+            .options()
+            .iter()
+            .skip(page * OPTIONS_PER_PAGE)
+            .take(OPTIONS_PER_PAGE)
+            .map(|option| {
+                let label = match self.available_for(option).len() {
+                    0 => option.label(language),
+                    count => format!("{} · {count} ✅", option.label(language)),
+                };
+                vec![InlineKeyboardButton::callback(
+                    label,
+                    self.callback_data(&option.id()),
+                )]
+            })
+            .collect::<Vec<_>>();
+
+        if pages > 1 {
+            keyboard.push(
+                self.navigation(page, pages, language, |page| format!("{PAGE_PREFIX}{page}")),
+            );
+        }
+
+        InlineKeyboardMarkup::new(keyboard).append_row(vec![InlineKeyboardButton::callback(
+            tr!(language, "Close poll", "Umfrage beenden"),
+            self.callback_data(CLOSE),
+        )])
+    }
+
+    /// Buttons for the creator to choose between tied options, paged like the
+    /// poll's keyboard. Whole days start at `time`.
+    pub fn pick_keyboard(
+        &self,
+        options: &[PollOption],
+        time: NaiveTime,
+        page: usize,
+        language: Language,
+    ) -> InlineKeyboardMarkup {
+        let pages = options.len().div_ceil(OPTIONS_PER_PAGE).max(1);
+        let page = page.min(pages - 1);
+
+        let mut keyboard = options
+            .iter()
+            .skip(page * OPTIONS_PER_PAGE)
+            .take(OPTIONS_PER_PAGE)
+            .map(|option| {
+                let data = self.callback_data(&format!(
+                    "{PICK_PREFIX}{} {}",
+                    option.date.format(DATE_ID_FORMAT),
+                    option.time.unwrap_or(time).format(TIME_FORMAT)
+                ));
+                vec![InlineKeyboardButton::callback(option.label(language), data)]
+            })
+            .collect::<Vec<_>>();
+
+        if pages > 1 {
+            let time = time.format(TIME_FORMAT);
+            keyboard.push(self.navigation(page, pages, language, |page| {
+                format!("{PICK_PAGE_PREFIX}{page} {time}")
+            }));
+        }
+
+        InlineKeyboardMarkup::new(keyboard)
+    }
+
+    /// Previous and next buttons around the page number, which only shows
+    /// where the keyboard is. `page_action` makes the action showing a page.
+    fn navigation(
+        &self,
+        page: usize,
+        pages: usize,
+        language: Language,
+        page_action: impl Fn(usize) -> String,
+    ) -> Vec<InlineKeyboardButton> {
+        let mut navigation = vec![];
+        if page > 0 {
+            navigation.push(InlineKeyboardButton::callback(
+                tr!(language, "‹ Prev", "‹ Zurück"),
+                self.callback_data(&page_action(page - 1)),
+            ));
+        }
+        navigation.push(InlineKeyboardButton::callback(
+            format!("{}/{pages}", page + 1),
+            self.callback_data(NOOP),
+        ));
+        if page + 1 < pages {
+            navigation.push(InlineKeyboardButton::callback(
+                tr!(language, "Next ›", "Weiter ›"),
+                self.callback_data(&page_action(page + 1)),
+            ));
+        }
+
+        navigation
+    }
+
+    /// The options with votes, most popular first and earliest first on a tie,
+    /// with the names of the people available.
+    pub fn ranking(&self) -> Vec<(PollOption, Vec<&str>)> {
+        let mut ranking = self
+            .options()
+            .into_iter()
+            .map(|option| (option, self.available_for(&option)))
+            .filter(|(_, names)| !names.is_empty())
+            .collect::<Vec<_>>();
+        // The sort is stable, so options with equal votes stay in date order.
+        ranking.sort_by_key(|(_, names)| Reverse(names.len()));
+
+        ranking
+    }
+
+    /// The options with the most votes. More than one means a tie.
+    pub fn winners(&self) -> Vec<PollOption> {
+        let ranking = self.ranking();
+        let Some((_, best)) = ranking.first() else {
+            return vec![];
+        };
+        let most_votes = best.len();
+
+        ranking
+            .iter()
+            .take_while(|(_, names)| names.len() == most_votes)
+            .map(|(option, _)| *option)
+            .collect()
+    }
+
+    /// The names of everyone available for the option.
+    pub fn available_for(&self, option: &PollOption) -> Vec<&str> {
+        self.votes
+            .get(&option.id())
+            .into_iter()
+            .flatten()
+            .filter_map(|user_id| self.voters.get(user_id))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The option the outcome was picked from.
+    fn outcome_option(&self, outcome: &Outcome) -> PollOption {
+        PollOption {
+            date: outcome.date,
+            time: (!self.slots.is_empty()).then_some(outcome.time),
+        }
+    }
+
+    /// One line describing the poll, for listing a chat's polls.
+    pub fn status(&self, language: Language) -> String {
+        let last_day = self.days().last().unwrap_or(self.start.date_naive());
+        let (id, start, end) = (
+            self.id,
+            language.short_date(self.start.date_naive()),
+            language.short_date(last_day),
+        );
+        let range = tr!(
+            language,
+            "#{id} {start} to {end}",
+            "#{id} {start} bis {end}"
+        );
+        match self.outcome {
+            Some(outcome) => {
+                let (date, time) = (
+                    language.short_date(outcome.date),
+                    language.time(outcome.time),
+                );
+                tr!(
+                    language,
+                    "{range}: closed, meeting on {date} at {time}",
+                    "{range}: beendet, Treffen am {date} um {time}",
+                )
+            }
+            None => {
+                let voted = self.voters.len();
+                tr!(
+                    language,
+                    "{range}: open, {voted} voted",
+                    "{range}: offen, {voted} abgestimmt",
+                )
+            }
+        }
+    }
+
+    pub fn summary(&self, language: Language) -> String {
+        let id = self.id;
+        if let Some(outcome) = &self.outcome {
+            let available = self.available_for(&self.outcome_option(outcome));
+            let (date, time, count, names) = (
+                language.long_date(outcome.date),
+                language.time(outcome.time),
+                available.len(),
+                available.join(", "),
+            );
+            return tr!(
+                language,
+                "Poll #{id} is closed. We're meeting on {date} at {time} ({count} available: {names}).",
+                "Umfrage #{id} ist beendet. Wir treffen uns am {date} um {time} ({count} können: {names}).",
+            );
+        }
+        let ranking = self.ranking();
+        if ranking.is_empty() {
+            return tr!(
+                language,
+                "Poll #{id}: no votes yet.",
+                "Umfrage #{id}: noch keine Stimmen.",
+            );
+        }
+
+        let best = ranking
+            .iter()
+            .take(SUMMARY_DATES)
+            .map(|(option, names)| {
+                format!(
+                    "{} ({}): {}",
+                    option.label(language),
+                    names.len(),
+                    names.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let voted = self.voters.len();
+        tr!(
+            language,
+            "Poll #{id}, best dates so far, {voted} voted:\n{best}",
+            "Umfrage #{id}, bisher beste Termine, {voted} abgestimmt:\n{best}",
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+    use teloxide::types::{InlineKeyboardButtonKind, UserId};
+
+    use super::*;
+
+    fn user(id: u64, name: &str) -> User {
+        User {
+            id: UserId(id),
+            is_bot: false,
+            first_name: name.to_string(),
+            last_name: None,
+            username: None,
+            language_code: None,
+            is_premium: false,
+            added_to_attachment_menu: false,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    /// A poll of `days` days from Monday 3 March 2025.
+    fn poll(days: i64, slots: Vec<NaiveTime>) -> Poll {
+        let start = Local
+            .from_local_datetime(&date(3).and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .unwrap();
+        let mut poll = Poll::new(start, start + Duration::days(days), slots, None);
+        poll.id = 2;
+        poll
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// The text and callback data of each button, row by row.
+    fn buttons(keyboard: InlineKeyboardMarkup) -> Vec<Vec<(String, String)>> {
+        keyboard
+            .inline_keyboard
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|button| match button.kind {
+                        InlineKeyboardButtonKind::CallbackData(data) => (button.text, data),
+                        kind => panic!("Unexpected button {kind:?}"),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn day(day: u32) -> PollOption {
+        PollOption {
+            date: date(day),
+            time: None,
+        }
+    }
+
+    #[test]
+    fn test_toggle() {
+        let mut poll = poll(3, vec![]);
+        let (ann, bob) = (user(1, "Ann"), user(2, "Bob"));
+
+        assert_eq!(poll.toggle("2025-03-04", &ann), Some(true));
+        assert_eq!(poll.toggle("2025-03-04", &bob), Some(true));
+        assert_eq!(poll.toggle("2025-03-05", &ann), Some(true));
+        assert_eq!(poll.available_for(&day(4)), ["Ann", "Bob"]);
+
+        assert_eq!(poll.toggle("2025-03-04", &ann), Some(false));
+        assert_eq!(poll.available_for(&day(4)), ["Bob"]);
+        assert!(poll.voters.contains_key(&1));
+
+        // Without any votes left Ann no longer counts as having voted.
+        assert_eq!(poll.toggle("2025-03-05", &ann), Some(false));
+        assert!(!poll.votes.contains_key("2025-03-05"));
+        assert_eq!(poll.voters.keys().collect::<Vec<_>>(), [&2]);
+
+        // Only the poll's own options can be voted for.
+        assert_eq!(poll.toggle("2025-03-06", &ann), None);
+        assert_eq!(poll.toggle("2025-03-04T19:00", &ann), None);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let mut poll = poll(3, vec![]);
+        let (ann, bob, cat) = (user(1, "Ann"), user(2, "Bob"), user(3, "Cat"));
+        assert!(poll.ranking().is_empty());
+
+        poll.toggle("2025-03-05", &bob);
+        poll.toggle("2025-03-05", &ann);
+        poll.toggle("2025-03-03", &cat);
+        poll.toggle("2025-03-04", &ann);
+        poll.toggle("2025-03-04", &cat);
+
+        // Most votes first, equal votes in date order, days without votes left out.
+        let ranking = poll.ranking();
+        assert_eq!(
+            ranking,
+            [
+                (day(4), vec!["Ann", "Cat"]),
+                (day(5), vec!["Ann", "Bob"]),
+                (day(3), vec!["Cat"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_winners() {
+        let mut poll = poll(3, vec![]);
+        let (ann, bob) = (user(1, "Ann"), user(2, "Bob"));
+        assert!(poll.winners().is_empty());
+
+        poll.toggle("2025-03-05", &ann);
+        poll.toggle("2025-03-05", &bob);
+        poll.toggle("2025-03-03", &bob);
+        assert_eq!(poll.winners(), [day(5)]);
+
+        // A tie lists every date with the most votes, earliest first.
+        poll.toggle("2025-03-03", &ann);
+        assert_eq!(poll.winners(), [day(3), day(5)]);
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let time = NaiveTime::from_hms_opt(19, 30, 0).unwrap();
+        assert!(matches!(Action::parse("2:close"), Some((2, Action::Close))));
+        assert!(matches!(
+            Action::parse("2:2025-03-04"),
+            Some((2, Action::Vote(id))) if id == "2025-03-04"
+        ));
+        assert!(matches!(
+            Action::parse("2:pick:2025-03-04 19:30"),
+            Some((2, Action::Pick(d, t))) if d == date(4) && t == time
+        ));
+        assert!(Action::parse("2:pick:2025-03-04").is_none());
+        assert!(Action::parse("2:pick:tomorrow 19:30").is_none());
+
+        // Keyboards from before polls had ids belong to the first poll, also
+        // when the option's id has a colon of its own.
+        assert!(matches!(Action::parse("close"), Some((1, Action::Close))));
+        assert!(matches!(
+            Action::parse("2025-03-04T19:30"),
+            Some((1, Action::Vote(id))) if id == "2025-03-04T19:30"
+        ));
+        assert!(matches!(
+            Action::parse("pick:2025-03-04 19:30"),
+            Some((1, Action::Pick(..)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_slots() {
+        assert_eq!(
+            parse_slots("20:00, 18:00 18:00"),
+            Some(vec![time(18, 0), time(20, 0)])
+        );
+        assert_eq!(
+            parse_slots("18:00-21:00"),
+            Some(vec![time(18, 0), time(19, 0), time(20, 0)])
+        );
+        assert_eq!(
+            parse_slots("17:30,18:30-20:00"),
+            Some(vec![time(17, 30), time(18, 30), time(19, 30)])
+        );
+        // A window running up to midnight stops there.
+        assert_eq!(
+            parse_slots("22:30-23:59"),
+            Some(vec![time(22, 30), time(23, 30)])
+        );
+        assert_eq!(parse_slots("21:00-18:00"), None);
+        assert_eq!(parse_slots(""), None);
+        assert_eq!(parse_slots("7pm"), None);
+        assert_eq!(parse_slots("18:00, later"), None);
+    }
+
+    #[test]
+    fn test_keyboard_pages() {
+        // Three days of five slots don't fit on one page.
+        let slots = (18..23).map(|hour| time(hour, 0)).collect();
+        let mut poll = poll(3, slots);
+
+        let first = buttons(poll.keyboard(Language::English));
+        assert_eq!(first.len(), OPTIONS_PER_PAGE + 2);
+        assert_eq!(first[0][0].1, "2:2025-03-03T18:00");
+        assert_eq!(
+            first[OPTIONS_PER_PAGE],
+            [
+                ("1/2".to_string(), "2:noop".to_string()),
+                ("Next ›".to_string(), "2:page:1".to_string()),
+            ]
+        );
+        assert_eq!(first[OPTIONS_PER_PAGE + 1][0].1, "2:close");
+
+        poll.set_page(7);
+        assert_eq!(poll.page, 1);
+        let last = buttons(poll.keyboard(Language::English));
+        assert_eq!(last.len(), 5 + 2);
+        assert_eq!(last[0][0].1, "2:2025-03-05T18:00");
+        assert_eq!(
+            last[5],
+            [
+                ("‹ Prev".to_string(), "2:page:0".to_string()),
+                ("2/2".to_string(), "2:noop".to_string()),
+            ]
+        );
+        assert!(matches!(Action::parse("2:noop"), Some((2, Action::Noop))));
+
+        // Polls that fit on one page have no paging buttons.
+        let mut poll = self::poll(3, vec![]);
+        poll.set_page(1);
+        assert_eq!(poll.page, 0);
+        let only = buttons(poll.keyboard(Language::English));
+        assert_eq!(only.len(), 3 + 1);
+    }
+
+    #[test]
+    fn test_pick_keyboard_pages() {
+        let poll = poll(14, vec![]);
+        let options = poll.options();
+        assert_eq!(options.len(), 14);
+
+        let first = buttons(poll.pick_keyboard(&options, time(19, 0), 0, Language::English));
+        assert_eq!(first.len(), OPTIONS_PER_PAGE + 1);
+        assert_eq!(first[0][0].1, "2:pick:2025-03-03 19:00");
+        assert_eq!(first[OPTIONS_PER_PAGE][1].1, "2:pickpage:1 19:00");
+
+        let last = buttons(poll.pick_keyboard(&options, time(19, 0), 9, Language::English));
+        assert_eq!(last.len(), 4 + 1);
+        assert_eq!(last[0][0].1, "2:pick:2025-03-13 19:00");
+        assert_eq!(last[4][0].1, "2:pickpage:0 19:00");
+        assert!(matches!(
+            Action::parse("2:pickpage:0 19:00"),
+            Some((2, Action::PickPage(0, t))) if t == time(19, 0)
+        ));
+    }
+}
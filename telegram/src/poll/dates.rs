@@ -0,0 +1,235 @@
+use chrono::{offset::Local, DateTime, Datelike, Duration, NaiveDate, TimeZone};
+use chrono_english::parse_date_string;
+
+use super::{parse_slots, PollDates};
+use crate::{locale::tr, settings::Settings};
+
+/// Between the start and end date.
+const RANGE_SEPARATORS: [&str; 2] = [" to ", " bis "];
+/// Before the time slots.
+const SLOT_SEPARATORS: [&str; 2] = [" at ", " um "];
+const THIS_WEEK: [&str; 2] = ["this week", "diese woche"];
+const NEXT_WEEK: [&str; 2] = ["next week", "nächste woche"];
+
+/// Parses the arguments of `/polldate`: `<start> to <end>`, `this week` or
+/// `next week`, optionally followed by `at <slots>`.
+pub fn parse_dates(s: &str, settings: &Settings) -> Result<PollDates, String> {
+    let language = settings.language;
+    let now = Local::now();
+
+    // Dates may contain "at" themselves, so only a suffix of valid slots counts.
+    let (dates, slots) = SLOT_SEPARATORS
+        .iter()
+        .find_map(|separator| {
+            let (dates, slots) = s.rsplit_once(separator)?;
+            Some((dates, parse_slots(slots)?))
+        })
+        .unwrap_or((s, vec![]));
+    let dates = dates.trim();
+
+    if let Some((start, end)) = week(dates, settings, now) {
+        return Ok((start, end, slots));
+    }
+
+    let Some((start, end)) = RANGE_SEPARATORS
+        .iter()
+        .find_map(|separator| dates.split_once(separator))
+    else {
+        return Err(tr!(
+            language,
+            "dates must be separated by 'to'",
+            "Die Daten müssen durch „bis“ getrennt sein",
+        ));
+    };
+    let start = parse_date(start, settings, now).map_err(|e| {
+        tr!(
+            language,
+            "unable to parse start date: {e} '{start}'",
+            "Startdatum „{start}“ nicht erkannt",
+        )
+    })?;
+    // Polls run up to `end`, so it's the day after the last one offered.
+    let end = parse_date(end, settings, now)
+        .and_then(|end| {
+            midnight(end.date_naive() + Duration::days(1))
+                .ok_or_else(|| "no midnight after it".to_string())
+        })
+        .map_err(|e| {
+            tr!(
+                language,
+                "unable to parse end date: {e} '{end}'",
+                "Enddatum „{end}“ nicht erkannt",
+            )
+        })?;
+
+    Ok((start, end, slots))
+}
+
+fn parse_date(
+    s: &str,
+    settings: &Settings,
+    now: DateTime<Local>,
+) -> Result<DateTime<Local>, String> {
+    if let Some(date) = parse_dotted(s, now) {
+        return Ok(date);
+    }
+
+    parse_date_string(s.trim(), now, settings.dialect.dialect()).map_err(|e| format!("{e:?}"))
+}
+
+/// A day-first date such as `5.2.` or `05.02.2025`, as German members write
+/// them.
+fn parse_dotted(s: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let mut parts = s.trim().trim_end_matches('.').split('.');
+    let day = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let year = match parts.next() {
+        Some(year) => match year.parse().ok()? {
+            year @ 0..=99 => 2000 + year,
+            year => year,
+        },
+        None => now.year(),
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    midnight(NaiveDate::from_ymd_opt(year, month, day)?)
+}
+
+/// The rest of this week or all of next week, with weeks starting on the
+/// chat's first day of the week.
+fn week(
+    s: &str,
+    settings: &Settings,
+    now: DateTime<Local>,
+) -> Option<(DateTime<Local>, DateTime<Local>)> {
+    let s = s.to_lowercase();
+    let weeks_ahead = if THIS_WEEK.contains(&s.as_str()) {
+        0
+    } else if NEXT_WEEK.contains(&s.as_str()) {
+        1
+    } else {
+        return None;
+    };
+
+    let today = now.date_naive();
+    let since_week_start = (7 + today.weekday().num_days_from_monday()
+        - settings.week_start.num_days_from_monday())
+        % 7;
+    let week_start = today - Duration::days(since_week_start.into()) + Duration::weeks(weeks_ahead);
+    // Days of this week that have passed can't be voted for.
+    let start = week_start.max(today);
+
+    Some((midnight(start)?, midnight(week_start + Duration::weeks(1))?))
+}
+
+fn midnight(date: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveTime, Weekday};
+
+    use super::*;
+    use crate::{locale::Language, poll::Poll};
+
+    fn day(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        midnight(NaiveDate::from_ymd_opt(year, month, day).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_dotted() {
+        let now = day(2025, 3, 5);
+        assert_eq!(parse_dotted("5.2.", now), Some(day(2025, 2, 5)));
+        assert_eq!(parse_dotted(" 05.02.2026 ", now), Some(day(2026, 2, 5)));
+        assert_eq!(parse_dotted("5.2.26", now), Some(day(2026, 2, 5)));
+        assert_eq!(parse_dotted("31.2.", now), None);
+        assert_eq!(parse_dotted("5.2.2025.1", now), None);
+        assert_eq!(parse_dotted("5", now), None);
+        assert_eq!(parse_dotted("Feb 5", now), None);
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        let settings = Settings::default();
+        let (start, end, slots) = parse_dates("5.2.2025 to 7.2.2025", &settings).unwrap();
+        assert_eq!((start, end), (day(2025, 2, 5), day(2025, 2, 8)));
+        assert!(slots.is_empty());
+        // The end date is one of the days voted on.
+        let days: Vec<_> = Poll::new(start, end, slots, None).days().collect();
+        assert_eq!(
+            days,
+            [
+                day(2025, 2, 5).date_naive(),
+                day(2025, 2, 6).date_naive(),
+                day(2025, 2, 7).date_naive()
+            ]
+        );
+
+        let (start, _, slots) =
+            parse_dates("5.2.2025 bis 7.2.2025 um 18:00-20:00", &settings).unwrap();
+        assert_eq!(start, day(2025, 2, 5));
+        assert_eq!(
+            slots,
+            [
+                NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(19, 0, 0).unwrap()
+            ]
+        );
+
+        // Weeks already end on the day after, so no day is added.
+        let (start, end, slots) = parse_dates("next week at 19:00", &settings).unwrap();
+        assert_eq!(end - start, Duration::weeks(1));
+        assert_eq!(start.weekday(), Weekday::Mon);
+        assert_eq!(slots.len(), 1);
+        let days: Vec<_> = Poll::new(start, end, slots, None).days().collect();
+        assert_eq!(days.len(), 7);
+        assert_eq!(days.last().unwrap().weekday(), Weekday::Sun);
+
+        assert!(parse_dates("5.2.2025", &settings).is_err());
+        let german = Settings {
+            language: Language::German,
+            ..Settings::default()
+        };
+        assert_eq!(
+            parse_dates("5.2.2025 bis someday", &german).unwrap_err(),
+            "Enddatum „someday“ nicht erkannt"
+        );
+    }
+
+    #[test]
+    fn test_week() {
+        let sunday = Settings {
+            week_start: Weekday::Sun,
+            ..Settings::default()
+        };
+        // A Wednesday.
+        let now = day(2025, 3, 5);
+
+        // The days of this week that have passed are left out.
+        assert_eq!(
+            week("this week", &sunday, now),
+            Some((day(2025, 3, 5), day(2025, 3, 9)))
+        );
+        assert_eq!(
+            week("Nächste Woche", &sunday, now),
+            Some((day(2025, 3, 9), day(2025, 3, 16)))
+        );
+        assert_eq!(
+            week("next week", &Settings::default(), now),
+            Some((day(2025, 3, 10), day(2025, 3, 17)))
+        );
+
+        // On the first day of the week, this week is all of it.
+        let now = day(2025, 3, 9);
+        assert_eq!(
+            week("this week", &sunday, now),
+            Some((day(2025, 3, 9), day(2025, 3, 16)))
+        );
+        assert_eq!(week("the week after", &sunday, now), None);
+    }
+}
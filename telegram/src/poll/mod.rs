@@ -0,0 +1,452 @@
+mod date_poll;
+mod dates;
+mod polls;
+
+pub use date_poll::{parse_slots, Poll};
+use date_poll::{Action, Outcome};
+pub use dates::parse_dates;
+pub use polls::Polls;
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use teloxide::{prelude::*, types::User};
+
+use crate::{
+    api::{ApiClient, ChatVote},
+    locale::{tr, Language},
+    settings::SettingsStore,
+    DialogState, State,
+};
+
+/// Polls only pick a day, so the meeting starts at this time unless `/closepoll`
+/// is given another one.
+const DEFAULT_MEETING_TIME: (u32, u32) = (19, 0);
+
+/// The start, end and time slots of a `/polldate`.
+pub type PollDates = (DateTime<Local>, DateTime<Local>, Vec<NaiveTime>);
+
+/// What closing a poll needs to talk to the chat and the API.
+#[derive(Clone, Copy)]
+struct ChatContext<'a> {
+    bot: &'a Bot,
+    dialog: &'a DialogState,
+    api: &'a ApiClient,
+    chat_id: ChatId,
+    language: Language,
+}
+
+async fn load(dialog: &DialogState) -> Result<Polls> {
+    Ok(Polls::from(dialog.get_or_default().await?))
+}
+
+async fn save(dialog: &DialogState, polls: Polls) -> Result<()> {
+    dialog.update(State::Polls(polls)).await?;
+    Ok(())
+}
+
+pub async fn start(
+    bot: Bot,
+    dialog: DialogState,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    (start, end, slots): PollDates,
+) -> Result<()> {
+    let mut polls = load(&dialog).await?;
+    let id = polls.add(Poll::new(start, end, slots, msg.from()));
+    let poll = polls.get_mut(id).expect("poll was just added");
+
+    let message = bot
+        .send_message(
+            msg.chat.id,
+            tr!(
+                language,
+                "Poll #{id}: select the days you can make",
+                "Umfrage #{id}: wähle die Tage, an denen du kannst",
+            ),
+        )
+        .reply_markup(poll.keyboard(language))
+        .await?;
+    let summary = bot
+        .send_message(msg.chat.id, poll.summary(language))
+        .await?;
+    poll.message = Some(message.id);
+    poll.summary_message = Some(summary.id);
+    let vote = ChatVote {
+        poll_id: id,
+        dates: poll.days().collect(),
+        outcome: None,
+    };
+    save(&dialog, polls).await?;
+
+    // The poll works without the API, which only emails the club's members.
+    if let Err(e) = api.open_vote(msg.chat.id.0, &vote).await {
+        log::warn!("Failed to report poll #{id} to the API: {e:?}");
+    }
+
+    Ok(())
+}
+
+pub async fn callback(
+    bot: Bot,
+    dialog: DialogState,
+    api: ApiClient,
+    settings: SettingsStore,
+    q: CallbackQuery,
+) -> Result<()> {
+    let (Some((poll_id, action)), Some(message)) = (
+        q.data.as_deref().and_then(Action::parse),
+        q.message.as_ref(),
+    ) else {
+        bot.answer_callback_query(q.id).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat.id;
+    let language = settings.get(chat_id).await?.language;
+    let chat = ChatContext {
+        bot: &bot,
+        dialog: &dialog,
+        api: &api,
+        chat_id,
+        language,
+    };
+
+    let mut polls = load(&dialog).await?;
+    let Some(poll) = polls.get_mut(poll_id) else {
+        bot.answer_callback_query(q.id)
+            .text(tr!(
+                language,
+                "This poll no longer exists.",
+                "Diese Umfrage gibt es nicht mehr.",
+            ))
+            .await?;
+        return Ok(());
+    };
+    if !poll.is_open() {
+        bot.answer_callback_query(q.id)
+            .text(tr!(
+                language,
+                "This poll is closed.",
+                "Diese Umfrage ist beendet.",
+            ))
+            .await?;
+        return Ok(());
+    }
+
+    match action {
+        Action::Vote(date_id) => {
+            let Some(available) = poll.toggle(&date_id, &q.from) else {
+                bot.answer_callback_query(q.id)
+                    .text(tr!(
+                        language,
+                        "That day isn't part of this poll.",
+                        "Dieser Tag gehört nicht zur Umfrage.",
+                    ))
+                    .await?;
+                return Ok(());
+            };
+            let keyboard = poll.keyboard(language);
+            let summary = poll.summary_message.map(|id| (id, poll.summary(language)));
+            save(&dialog, polls).await?;
+
+            let answer = if available {
+                tr!(
+                    language,
+                    "Marked you as available.",
+                    "Du bist als verfügbar eingetragen.",
+                )
+            } else {
+                tr!(
+                    language,
+                    "Removed your vote.",
+                    "Deine Stimme wurde entfernt.",
+                )
+            };
+            bot.answer_callback_query(q.id).text(answer).await?;
+            bot.edit_message_reply_markup(chat_id, message.id)
+                .reply_markup(keyboard)
+                .await?;
+            if let Some((summary_message, summary)) = summary {
+                bot.edit_message_text(chat_id, summary_message, summary)
+                    .await?;
+            }
+        }
+        Action::Page(page) => {
+            poll.set_page(page);
+            let keyboard = poll.keyboard(language);
+            save(&dialog, polls).await?;
+
+            bot.answer_callback_query(q.id).await?;
+            bot.edit_message_reply_markup(chat_id, message.id)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Action::PickPage(page, time) => {
+            let keyboard = poll.pick_keyboard(&poll.winners(), time, page, language);
+
+            bot.answer_callback_query(q.id).await?;
+            bot.edit_message_reply_markup(chat_id, message.id)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        Action::Noop => {
+            bot.answer_callback_query(q.id).await?;
+        }
+        Action::Close => {
+            bot.answer_callback_query(&q.id).await?;
+            close_poll(&chat, &q.from, polls, poll_id, default_time()).await?;
+        }
+        Action::Pick(date, time) => {
+            if !may_close(&bot, chat_id, &q.from, poll).await? {
+                bot.answer_callback_query(&q.id)
+                    .text(tr!(
+                        language,
+                        "Only the poll's creator can pick the date.",
+                        "Nur wer die Umfrage gestartet hat, kann den Termin wählen.",
+                    ))
+                    .await?;
+                return Ok(());
+            }
+            bot.answer_callback_query(&q.id).await?;
+            // The choice is made, so the tie breaking buttons can go.
+            bot.edit_message_reply_markup(chat_id, message.id).await?;
+            finish(&chat, polls, poll_id, date, time).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Closes a poll: `/closepoll [poll id] [HH:MM]`. Without an id the latest open
+/// poll is closed, and without a time the meeting starts at the default time.
+pub async fn close(
+    bot: Bot,
+    dialog: DialogState,
+    api: ApiClient,
+    msg: Message,
+    language: Language,
+    args: String,
+) -> Result<()> {
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+
+    let mut poll_id = None;
+    let mut time = default_time();
+    for arg in args.split_whitespace() {
+        if let Ok(id) = arg.trim_start_matches('#').parse::<u32>() {
+            poll_id = Some(id);
+        } else if let Ok(parsed) = NaiveTime::parse_from_str(arg, "%H:%M") {
+            time = parsed;
+        } else {
+            let usage = tr!(
+                language,
+                "Use /closepoll [poll id] [HH:MM], e.g. /closepoll 2 19:30",
+                "Nutze /closepoll [Umfrage-ID] [HH:MM], z.B. /closepoll 2 19:30",
+            );
+            bot.send_message(msg.chat.id, usage).await?;
+            return Ok(());
+        }
+    }
+
+    let polls = load(&dialog).await?;
+    let poll = match poll_id {
+        Some(id) => polls.get(id),
+        None => polls.latest_open(),
+    };
+    let Some(poll) = poll else {
+        let reply = tr!(
+            language,
+            "There is no such poll running.",
+            "Diese Umfrage läuft nicht.",
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+    if !poll.is_open() {
+        bot.send_message(msg.chat.id, poll.summary(language))
+            .await?;
+        return Ok(());
+    }
+
+    let chat = ChatContext {
+        bot: &bot,
+        dialog: &dialog,
+        api: &api,
+        chat_id: msg.chat.id,
+        language,
+    };
+    let poll_id = poll.id;
+    close_poll(&chat, user, polls, poll_id, time).await
+}
+
+/// Lists the chat's polls, or shows one of them: `/polls [poll id]`.
+pub async fn show(
+    bot: Bot,
+    dialog: DialogState,
+    msg: Message,
+    language: Language,
+    args: String,
+) -> Result<()> {
+    let polls = load(&dialog).await?;
+
+    let args = args.trim().trim_start_matches('#');
+    let reply = if args.is_empty() {
+        let lines = polls
+            .iter()
+            .map(|poll| poll.status(language))
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            tr!(
+                language,
+                "This chat hasn't run any polls yet, start one with /polldate.",
+                "In diesem Chat gab es noch keine Umfrage, starte eine mit /polldate.",
+            )
+        } else {
+            lines.join("\n")
+        }
+    } else {
+        match args.parse().ok().and_then(|id| polls.get(id)) {
+            Some(poll) => poll.summary(language),
+            None => tr!(
+                language,
+                "There is no poll #{args}.",
+                "Es gibt keine Umfrage #{args}.",
+            ),
+        }
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+
+    Ok(())
+}
+
+fn default_time() -> NaiveTime {
+    let (hour, minute) = DEFAULT_MEETING_TIME;
+    NaiveTime::from_hms_opt(hour, minute, 0).expect("valid default meeting time")
+}
+
+/// The poll's creator may close it, as may the chat's admins.
+async fn may_close(bot: &Bot, chat_id: ChatId, user: &User, poll: &Poll) -> Result<bool> {
+    if poll.creator == Some(user.id.0) {
+        return Ok(true);
+    }
+    let member = bot.get_chat_member(chat_id, user.id).await?;
+
+    Ok(member.is_privileged())
+}
+
+/// Settles on the winning date, or asks the creator to break a tie.
+async fn close_poll(
+    chat: &ChatContext<'_>,
+    user: &User,
+    polls: Polls,
+    poll_id: u32,
+    time: NaiveTime,
+) -> Result<()> {
+    let ChatContext {
+        bot,
+        chat_id,
+        language,
+        ..
+    } = *chat;
+    let Some(poll) = polls.get(poll_id) else {
+        return Ok(());
+    };
+    if !may_close(bot, chat_id, user, poll).await? {
+        let reply = tr!(
+            language,
+            "Only the poll's creator can close it.",
+            "Nur wer die Umfrage gestartet hat, kann sie beenden.",
+        );
+        bot.send_message(chat_id, reply).await?;
+        return Ok(());
+    }
+
+    match poll.winners().as_slice() {
+        [] => {
+            let reply = tr!(
+                language,
+                "Nobody has voted in poll #{poll_id} yet, so there's no date to pick.",
+                "In Umfrage #{poll_id} hat noch niemand abgestimmt, es gibt also keinen Termin.",
+            );
+            bot.send_message(chat_id, reply).await?;
+        }
+        [option] => {
+            let (date, time) = (option.date, option.time.unwrap_or(time));
+            finish(chat, polls, poll_id, date, time).await?;
+        }
+        options => {
+            let count = options.len();
+            let reply = tr!(
+                language,
+                "Poll #{poll_id} is a tie between {count} dates. The poll's creator can pick one:",
+                "Umfrage #{poll_id} endet unentschieden zwischen {count} Terminen. Wer sie gestartet hat, kann einen wählen:",
+            );
+            bot.send_message(chat_id, reply)
+                .reply_markup(poll.pick_keyboard(options, time, 0, language))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Closes the poll on the date and, when the chat is linked to a club,
+/// schedules the meeting through the API.
+async fn finish(
+    chat: &ChatContext<'_>,
+    mut polls: Polls,
+    poll_id: u32,
+    date: NaiveDate,
+    time: NaiveTime,
+) -> Result<()> {
+    let ChatContext {
+        bot,
+        dialog,
+        api,
+        chat_id,
+        language,
+    } = *chat;
+    let Some(poll) = polls.get_mut(poll_id) else {
+        return Ok(());
+    };
+    poll.outcome = Some(Outcome { date, time });
+    let mut lines = vec![poll.summary(language)];
+
+    if let Some(club) = api.linked_club(chat_id.0).await? {
+        // Times are entered in the club's timezone.
+        let tz = club.timezone.parse::<Tz>().unwrap_or(Tz::UTC);
+        let local = date.and_time(time);
+        let start = tz
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&local));
+        api.create_meeting(club.id, start).await?;
+        let vote = ChatVote {
+            poll_id,
+            dates: poll.days().collect(),
+            outcome: Some(local),
+        };
+        if let Err(e) = api.close_vote(chat_id.0, &vote).await {
+            log::warn!("Failed to report the outcome of poll #{poll_id} to the API: {e:?}");
+        }
+        let name = club.name;
+        lines.push(tr!(
+            language,
+            "Added it to {name}'s meetings.",
+            "Zu den Treffen von {name} hinzugefügt.",
+        ));
+    }
+
+    if let Some(message) = poll.message {
+        bot.edit_message_reply_markup(chat_id, message).await?;
+    }
+    if let Some(summary_message) = poll.summary_message {
+        bot.edit_message_text(chat_id, summary_message, poll.summary(language))
+            .await?;
+    }
+    bot.send_message(chat_id, lines.join("\n")).await?;
+    save(dialog, polls).await
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::Poll;
+use crate::State;
+
+/// Every poll a chat has run, open and closed, by id.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+pub struct Polls {
+    next_id: u32,
+    polls: BTreeMap<u32, Poll>,
+}
+
+impl Polls {
+    /// Stores the poll under a fresh id and returns that id.
+    pub fn add(&mut self, mut poll: Poll) -> u32 {
+        self.next_id = self.next_id.max(1);
+        let id = self.next_id;
+        self.next_id += 1;
+        poll.id = id;
+        self.polls.insert(id, poll);
+
+        id
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Poll> {
+        self.polls.get(&id)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Poll> {
+        self.polls.get_mut(&id)
+    }
+
+    /// The most recently started poll that is still open.
+    pub fn latest_open(&self) -> Option<&Poll> {
+        self.polls.values().rev().find(|poll| poll.is_open())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Poll> {
+        self.polls.values()
+    }
+}
+
+impl From<State> for Polls {
+    fn from(state: State) -> Polls {
+        match state {
+            State::Start => Polls::default(),
+            // A chat's only poll from before polls had ids.
+            State::Polling(poll) => {
+                let mut polls = Polls::default();
+                polls.add(poll);
+                polls
+            }
+            State::Polls(polls) => polls,
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::time::Duration as StdDuration;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use teloxide::prelude::*;
+
+use crate::{
+    api::{ApiClient, MeetingDetails},
+    club,
+    locale::{tr, Language},
+    settings::SettingsStore,
+};
+
+/// How long before a meeting reminders are posted, unless
+/// `TELEGRAM_REMINDER_OFFSETS` says otherwise, e.g. `7d,1d,2h`.
+const DEFAULT_OFFSETS: &str = "7d,1d";
+const CHECK_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
+/// Posts reminders for upcoming meetings in every linked chat. Posted
+/// reminders are recorded in the bot's database, so a restart doesn't post
+/// them again.
+pub struct Reminders {
+    bot: Bot,
+    api: ApiClient,
+    settings: SettingsStore,
+    db: SqlitePool,
+    /// Longest first.
+    offsets: Vec<Duration>,
+}
+
+impl Reminders {
+    pub async fn from_env(
+        bot: Bot,
+        api: ApiClient,
+        settings: SettingsStore,
+        database_url: &str,
+    ) -> Result<Reminders> {
+        let offsets = std::env::var("TELEGRAM_REMINDER_OFFSETS")
+            .unwrap_or_else(|_| DEFAULT_OFFSETS.to_string());
+        let offsets = parse_offsets(&offsets)?;
+
+        let db = SqlitePool::connect(database_url).await?;
+        create_table(&db).await?;
+
+        Ok(Reminders {
+            bot,
+            api,
+            settings,
+            db,
+            offsets,
+        })
+    }
+
+    pub async fn run(self) {
+        loop {
+            if let Err(e) = self.send_due(Utc::now()).await {
+                log::warn!("Failed to send meeting reminders: {e:?}");
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    async fn send_due(&self, now: DateTime<Utc>) -> Result<()> {
+        for chat in self.api.linked_chats().await? {
+            let Some(club) = self.api.club(chat.club_id).await? else {
+                continue;
+            };
+            let meetings = self.api.club_meetings(club.id).await?;
+
+            for meeting in &meetings {
+                let due = due_offsets(&self.offsets, meeting.date.with_timezone(&Utc), now);
+                let Some(offset) = claim_latest(&self.db, chat.chat_id, meeting.id, &due).await?
+                else {
+                    continue;
+                };
+
+                if let Err(e) = self.post(chat.chat_id, &club.name, offset, meeting).await {
+                    // Give the reminder another go on the next check, and don't
+                    // hold up the other chats' reminders meanwhile.
+                    log::warn!(
+                        "Failed to post the reminder for meeting {} in chat {}: {e:?}",
+                        meeting.id,
+                        chat.chat_id
+                    );
+                    release(&self.db, chat.chat_id, meeting.id, offset).await?;
+                    continue;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn post(
+        &self,
+        chat_id: i64,
+        club_name: &str,
+        offset: Duration,
+        meeting: &MeetingDetails,
+    ) -> Result<()> {
+        let language = self.settings.get(ChatId(chat_id)).await?.language;
+        let book = club::book_title(&self.api, meeting, language).await?;
+        let offset = describe_offset(offset, language);
+        let title = tr!(
+            language,
+            "{club_name} meets in {offset}",
+            "{club_name} trifft sich in {offset}",
+        );
+        self.bot
+            .send_message(
+                ChatId(chat_id),
+                club::describe_meeting(&title, meeting, book, language),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+async fn create_table(db: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS sent_reminders
+        (
+            chat_id INTEGER NOT NULL,
+            meeting_id INTEGER NOT NULL,
+            offset_minutes INTEGER NOT NULL,
+            sent_at DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+            PRIMARY KEY (chat_id, meeting_id, offset_minutes)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// The offsets whose reminder time has come for a meeting that is still ahead,
+/// longest first.
+fn due_offsets(offsets: &[Duration], date: DateTime<Utc>, now: DateTime<Utc>) -> Vec<Duration> {
+    if date <= now {
+        return vec![];
+    }
+
+    offsets
+        .iter()
+        .copied()
+        .filter(|offset| date - *offset <= now)
+        .collect()
+}
+
+/// Claims all due reminders and returns the one to post, if any wasn't posted
+/// yet. After downtime several reminders can be due at once, only the latest
+/// of them is worth posting.
+async fn claim_latest(
+    db: &SqlitePool,
+    chat_id: i64,
+    meeting_id: i64,
+    due: &[Duration],
+) -> Result<Option<Duration>> {
+    let mut post = None;
+    for offset in due {
+        let claimed = claim(db, chat_id, meeting_id, *offset).await?;
+        post = claimed.then_some(*offset);
+    }
+
+    Ok(post)
+}
+
+/// Records the reminder as posted. Returns `false` if it already was.
+async fn claim(db: &SqlitePool, chat_id: i64, meeting_id: i64, offset: Duration) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO sent_reminders (chat_id, meeting_id, offset_minutes)
+        VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(chat_id)
+    .bind(meeting_id)
+    .bind(offset.num_minutes())
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+async fn release(db: &SqlitePool, chat_id: i64, meeting_id: i64, offset: Duration) -> Result<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM sent_reminders
+        WHERE chat_id = ? AND meeting_id = ? AND offset_minutes = ?
+        "#,
+    )
+    .bind(chat_id)
+    .bind(meeting_id)
+    .bind(offset.num_minutes())
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Parses offsets such as `7d,1d,2h,30m`, longest first.
+fn parse_offsets(s: &str) -> Result<Vec<Duration>> {
+    let mut offsets = vec![];
+    for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let unit_at = part.len() - part.chars().last().map_or(0, char::len_utf8);
+        let (amount, unit) = part.split_at(unit_at);
+        let Ok(amount) = amount.parse::<i64>() else {
+            bail!("Invalid reminder offset '{part}'");
+        };
+        let offset = match unit {
+            "d" => Duration::days(amount),
+            "h" => Duration::hours(amount),
+            "m" => Duration::minutes(amount),
+            _ => bail!("Invalid reminder offset '{part}', use d, h or m"),
+        };
+        offsets.push(offset);
+    }
+    offsets.sort_by_key(|offset| std::cmp::Reverse(*offset));
+    offsets.dedup();
+
+    Ok(offsets)
+}
+
+fn describe_offset(offset: Duration, language: Language) -> String {
+    let (amount, unit) = if offset.num_minutes() % (24 * 60) == 0 {
+        (offset.num_days(), Unit::Day)
+    } else if offset.num_minutes() % 60 == 0 {
+        (offset.num_hours(), Unit::Hour)
+    } else {
+        (offset.num_minutes(), Unit::Minute)
+    };
+
+    // German uses the dative after "in": in einem Tag, in 7 Tagen.
+    match (unit, amount) {
+        (Unit::Day, 1) => tr!(language, "1 day", "einem Tag"),
+        (Unit::Day, _) => tr!(language, "{amount} days", "{amount} Tagen"),
+        (Unit::Hour, 1) => tr!(language, "1 hour", "einer Stunde"),
+        (Unit::Hour, _) => tr!(language, "{amount} hours", "{amount} Stunden"),
+        (Unit::Minute, 1) => tr!(language, "1 minute", "einer Minute"),
+        (Unit::Minute, _) => tr!(language, "{amount} minutes", "{amount} Minuten"),
+    }
+}
+
+enum Unit {
+    Day,
+    Hour,
+    Minute,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_db() -> SqlitePool {
+        // Every connection to an in-memory database gets a database of its own.
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        create_table(&db).await.unwrap();
+        db
+    }
+
+    #[test]
+    fn test_parse_offsets() {
+        let offsets = parse_offsets("1d, 2h,7d,,30m,1d").unwrap();
+        assert_eq!(
+            offsets,
+            vec![
+                Duration::days(7),
+                Duration::days(1),
+                Duration::hours(2),
+                Duration::minutes(30)
+            ]
+        );
+        assert!(parse_offsets("").unwrap().is_empty());
+        assert!(parse_offsets("2w").is_err());
+        assert!(parse_offsets("d").is_err());
+        assert!(parse_offsets("1.5h").is_err());
+    }
+
+    #[test]
+    fn test_describe_offset() {
+        let en = |offset| describe_offset(offset, Language::English);
+        let de = |offset| describe_offset(offset, Language::German);
+        assert_eq!(en(Duration::days(1)), "1 day");
+        assert_eq!(en(Duration::days(7)), "7 days");
+        assert_eq!(en(Duration::hours(48)), "2 days");
+        assert_eq!(en(Duration::hours(1)), "1 hour");
+        assert_eq!(en(Duration::minutes(90)), "90 minutes");
+        assert_eq!(de(Duration::days(1)), "einem Tag");
+        assert_eq!(de(Duration::days(7)), "7 Tagen");
+        assert_eq!(de(Duration::hours(2)), "2 Stunden");
+        assert_eq!(de(Duration::minutes(1)), "einer Minute");
+    }
+
+    #[test]
+    fn test_due_offsets() {
+        let offsets = [Duration::days(7), Duration::days(1), Duration::hours(2)];
+        let now = Utc::now();
+
+        assert!(due_offsets(&offsets, now + Duration::days(8), now).is_empty());
+        assert_eq!(
+            due_offsets(&offsets, now + Duration::days(3), now),
+            vec![Duration::days(7)]
+        );
+        assert_eq!(
+            due_offsets(&offsets, now + Duration::hours(1), now),
+            offsets.to_vec()
+        );
+        assert!(due_offsets(&offsets, now - Duration::hours(1), now).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_claim_latest() {
+        let db = test_db().await;
+        let due = [Duration::days(7), Duration::days(1)];
+
+        // Only the latest of several due reminders is posted, and only once.
+        let claimed = claim_latest(&db, 1, 10, &due).await.unwrap();
+        assert_eq!(claimed, Some(Duration::days(1)));
+        assert_eq!(claim_latest(&db, 1, 10, &due).await.unwrap(), None);
+
+        // Other chats get their own reminders.
+        let claimed = claim_latest(&db, 2, 10, &due[..1]).await.unwrap();
+        assert_eq!(claimed, Some(Duration::days(7)));
+
+        // A released reminder is posted on the next check.
+        release(&db, 1, 10, Duration::days(1)).await.unwrap();
+        let claimed = claim_latest(&db, 1, 10, &due).await.unwrap();
+        assert_eq!(claimed, Some(Duration::days(1)));
+    }
+}
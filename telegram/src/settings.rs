@@ -0,0 +1,233 @@
+use anyhow::Result;
+use chrono::Weekday;
+use chrono_english::Dialect;
+use sqlx::{Row, SqlitePool};
+use teloxide::prelude::*;
+
+use crate::{
+    club,
+    locale::{tr, Language},
+};
+
+/// How dates typed into the chat are read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateDialect {
+    /// `5/2` is the 5th of February and `next friday` the one of next week.
+    #[default]
+    Uk,
+    /// `5/2` is May 2nd and `next friday` the coming one.
+    Us,
+}
+
+impl DateDialect {
+    pub fn code(self) -> &'static str {
+        match self {
+            DateDialect::Uk => "uk",
+            DateDialect::Us => "us",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<DateDialect> {
+        match s.to_lowercase().as_str() {
+            "uk" | "gb" | "eu" | "de" => Some(DateDialect::Uk),
+            "us" => Some(DateDialect::Us),
+            _ => None,
+        }
+    }
+
+    pub fn dialect(self) -> Dialect {
+        match self {
+            DateDialect::Uk => Dialect::Uk,
+            DateDialect::Us => Dialect::Us,
+        }
+    }
+}
+
+/// A chat's preferences, set with `/settings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub language: Language,
+    pub dialect: DateDialect,
+    /// The day `this week` and `next week` start on.
+    pub week_start: Weekday,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            language: Language::default(),
+            dialect: DateDialect::default(),
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+/// Every chat's settings, kept in the bot's database.
+#[derive(Clone)]
+pub struct SettingsStore {
+    db: SqlitePool,
+}
+
+impl SettingsStore {
+    pub async fn open(database_url: &str) -> Result<SettingsStore> {
+        let db = SqlitePool::connect(database_url).await?;
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS chat_settings
+            (
+                chat_id INTEGER PRIMARY KEY NOT NULL,
+                language TEXT NOT NULL,
+                dialect TEXT NOT NULL,
+                week_start INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&db)
+        .await?;
+
+        Ok(SettingsStore { db })
+    }
+
+    /// The chat's settings, or the defaults if it has none.
+    pub async fn get(&self, chat_id: ChatId) -> Result<Settings> {
+        let row = sqlx::query(
+            r#"
+            SELECT language, dialect, week_start FROM chat_settings WHERE chat_id = ?
+            "#,
+        )
+        .bind(chat_id.0)
+        .fetch_optional(&self.db)
+        .await?;
+        let Some(row) = row else {
+            return Ok(Settings::default());
+        };
+
+        let defaults = Settings::default();
+        let week_start: i64 = row.try_get("week_start")?;
+        Ok(Settings {
+            language: Language::parse(row.try_get("language")?).unwrap_or(defaults.language),
+            dialect: DateDialect::parse(row.try_get("dialect")?).unwrap_or(defaults.dialect),
+            week_start: match week_start {
+                0..=6 => (0..week_start).fold(Weekday::Mon, |day, _| day.succ()),
+                _ => defaults.week_start,
+            },
+        })
+    }
+
+    async fn save(&self, chat_id: ChatId, settings: &Settings) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO chat_settings (chat_id, language, dialect, week_start)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (chat_id) DO UPDATE
+            SET language = excluded.language,
+                dialect = excluded.dialect,
+                week_start = excluded.week_start
+            "#,
+        )
+        .bind(chat_id.0)
+        .bind(settings.language.code())
+        .bind(settings.dialect.code())
+        .bind(settings.week_start.num_days_from_monday())
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Shows the chat's settings, or changes one:
+/// `/settings language|dialect|week <value>`.
+pub async fn command(
+    bot: Bot,
+    store: SettingsStore,
+    msg: Message,
+    settings: Settings,
+    args: String,
+) -> Result<()> {
+    let args = args.split_whitespace().collect::<Vec<_>>();
+    let [name, value] = args.as_slice() else {
+        let reply = if args.is_empty() {
+            describe(&settings)
+        } else {
+            usage(settings.language)
+        };
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    };
+
+    if !club::may_manage_chat(&bot, &msg).await? {
+        let reply = tr!(
+            settings.language,
+            "Only chat admins can change the settings.",
+            "Nur Admins des Chats können die Einstellungen ändern."
+        );
+        bot.send_message(msg.chat.id, reply).await?;
+        return Ok(());
+    }
+
+    let changed = match name.to_lowercase().as_str() {
+        "language" | "sprache" => Language::parse(value).map(|language| Settings {
+            language,
+            ..settings
+        }),
+        "dialect" | "dialekt" => DateDialect::parse(value).map(|dialect| Settings {
+            dialect,
+            ..settings
+        }),
+        "week" | "woche" => Language::parse_weekday(value).map(|week_start| Settings {
+            week_start,
+            ..settings
+        }),
+        _ => None,
+    };
+    let Some(settings) = changed else {
+        bot.send_message(msg.chat.id, usage(settings.language))
+            .await?;
+        return Ok(());
+    };
+
+    store.save(msg.chat.id, &settings).await?;
+    // Answer in the new language, so a change of language shows at once.
+    bot.send_message(msg.chat.id, describe(&settings)).await?;
+
+    Ok(())
+}
+
+fn describe(settings: &Settings) -> String {
+    let Settings {
+        language,
+        dialect,
+        week_start,
+    } = *settings;
+    let (name, dialect_name, week_start) = (
+        language.name(),
+        dialect.code().to_uppercase(),
+        language.weekday(week_start),
+    );
+    let example = match dialect {
+        DateDialect::Uk => tr!(language, "5/2 is 5 February", "5/2 ist der 5. Februar"),
+        DateDialect::Us => tr!(language, "5/2 is May 2", "5/2 ist der 2. Mai"),
+    };
+
+    let settings = tr!(
+        language,
+        "Settings for this chat:\nLanguage: {name}\nDates: {dialect_name}, {example}\nWeeks start on: {week_start}",
+        "Einstellungen für diesen Chat:\nSprache: {name}\nDatumsangaben: {dialect_name}, {example}\nWochen beginnen am: {week_start}",
+    );
+    format!("{settings}\n\n{}", usage(language))
+}
+
+fn usage(language: Language) -> String {
+    let languages = Language::ALL
+        .iter()
+        .map(|language| language.code())
+        .collect::<Vec<_>>()
+        .join("|");
+
+    tr!(
+        language,
+        "Change them with /settings language {languages}, /settings dialect uk|us or /settings week monday|sunday|…",
+        "Ändern mit /settings sprache {languages}, /settings dialekt uk|us oder /settings woche montag|sonntag|…",
+    )
+}
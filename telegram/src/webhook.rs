@@ -0,0 +1,136 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use reqwest::Url;
+use teloxide::{
+    prelude::*,
+    stop::{mk_stop_token, StopToken},
+    types::Update,
+    update_listeners::{StatefulListener, UpdateListener},
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Telegram sends the secret given when registering the webhook in this header.
+const SECRET_HEADER: &str = "x-telegram-bot-api-secret-token";
+const DEFAULT_ADDRESS: &str = "0.0.0.0:8443";
+
+type UpdateSender = mpsc::UnboundedSender<Result<Update, Infallible>>;
+
+/// Receives updates from Telegram over HTTP instead of polling for them. Set
+/// `TELEGRAM_WEBHOOK_URL` to the public URL Telegram should post updates to;
+/// the bot listens on `TELEGRAM_WEBHOOK_ADDRESS` for requests to its path, so
+/// a reverse proxy can forward them there.
+pub struct Webhook {
+    url: Url,
+    address: SocketAddr,
+    secret: String,
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    secret: String,
+    tx: UpdateSender,
+}
+
+impl Webhook {
+    /// The configured webhook, or `None` when the bot should use long polling.
+    pub fn from_env() -> Result<Option<Webhook>> {
+        let Ok(url) = std::env::var("TELEGRAM_WEBHOOK_URL") else {
+            return Ok(None);
+        };
+        let url = Url::parse(&url).context("Invalid TELEGRAM_WEBHOOK_URL")?;
+        let address = std::env::var("TELEGRAM_WEBHOOK_ADDRESS")
+            .unwrap_or_else(|_| DEFAULT_ADDRESS.to_string())
+            .parse()
+            .context("Invalid TELEGRAM_WEBHOOK_ADDRESS")?;
+        let secret = std::env::var("TELEGRAM_WEBHOOK_SECRET")
+            .context("TELEGRAM_WEBHOOK_SECRET must be set in webhook mode")?;
+        // Telegram's rules for the secret token.
+        let valid = (1..=256).contains(&secret.len())
+            && secret
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            bail!("TELEGRAM_WEBHOOK_SECRET must be 1-256 of A-Z, a-z, 0-9, _ and -");
+        }
+
+        Ok(Some(Webhook {
+            url,
+            address,
+            secret,
+        }))
+    }
+
+    /// Registers the webhook with Telegram and starts listening for updates.
+    /// The listener stops serving once the dispatcher stops it.
+    pub async fn listen(self, bot: &Bot) -> Result<impl UpdateListener<Err = Infallible>> {
+        bot.set_webhook(self.url.clone())
+            .secret_token(self.secret.clone())
+            .await?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (stop_token, stop_flag) = mk_stop_token();
+        let app = Router::new()
+            .route(self.url.path(), post(receive))
+            .with_state(WebhookState {
+                secret: self.secret,
+                tx,
+            });
+
+        let listener = tokio::net::TcpListener::bind(self.address)
+            .await
+            .with_context(|| format!("Failed to listen on {}", self.address))?;
+        log::info!("Listening for webhook updates on {}", self.address);
+        tokio::spawn(async move {
+            // Stopping the server drops the sender, which ends the update stream.
+            if let Err(e) = axum::serve(listener, app)
+                .with_graceful_shutdown(stop_flag)
+                .await
+            {
+                log::error!("Webhook server failed: {e:?}");
+            }
+        });
+
+        let stream = UnboundedReceiverStream::new(rx);
+        Ok(StatefulListener::new(
+            (stream, stop_token),
+            tuple_first_mut,
+            |state: &mut (_, StopToken)| state.1.clone(),
+        ))
+    }
+}
+
+fn tuple_first_mut<A, B>(tuple: &mut (A, B)) -> &mut A {
+    &mut tuple.0
+}
+
+async fn receive(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    let secret = headers.get(SECRET_HEADER).map(|value| value.as_bytes());
+    if secret != Some(state.secret.as_bytes()) {
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    // Telegram retries updates that aren't answered with a 200, which won't
+    // help with one we can't parse.
+    match serde_json::from_str::<Update>(&body) {
+        Ok(update) => {
+            if state.tx.send(Ok(update)).is_err() {
+                return StatusCode::SERVICE_UNAVAILABLE;
+            }
+        }
+        Err(e) => log::warn!("Failed to parse update from Telegram: {e}"),
+    }
+
+    StatusCode::OK
+}